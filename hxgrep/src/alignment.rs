@@ -0,0 +1,246 @@
+//! Block/sector alignment support
+//!
+//! Filesystem structures are only meaningful at sector or cluster boundaries
+//! (e.g. NTFS `FILE0` records every 1024 bytes). This module restricts regex
+//! matching to aligned offsets and maps offsets to LBA / cluster numbers.
+
+use crate::cli::Cli;
+use crate::error::{BingrepError, Result};
+use regex::bytes::Regex;
+use std::ops::Range;
+
+/// Default sector size used for LBA calculation
+pub const DEFAULT_SECTOR_SIZE: u64 = 512;
+
+/// Alignment constraint and sector/cluster geometry for reporting
+#[derive(Debug, Clone)]
+pub struct Alignment {
+    align: u64,
+    align_offset: u64,
+    sector_size: u64,
+    cluster_size: Option<u64>,
+}
+
+impl Alignment {
+    /// Create a new alignment constraint
+    ///
+    /// # Arguments
+    ///
+    /// * `align` - Block size hits must be aligned to (1 disables filtering)
+    /// * `align_offset` - Offset within each block where hits must start
+    /// * `sector_size` - Sector size used for LBA calculation
+    /// * `cluster_size` - Optional cluster size used for cluster numbers
+    pub fn new(
+        align: u64,
+        align_offset: u64,
+        sector_size: u64,
+        cluster_size: Option<u64>,
+    ) -> Result<Self> {
+        if align == 0 {
+            return Err(BingrepError::InvalidArgument(
+                "--align must be greater than 0".to_string(),
+            ));
+        }
+        if sector_size == 0 {
+            return Err(BingrepError::InvalidArgument(
+                "--sector-size must be greater than 0".to_string(),
+            ));
+        }
+        if cluster_size == Some(0) {
+            return Err(BingrepError::InvalidArgument(
+                "--cluster-size must be greater than 0".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            align,
+            align_offset: align_offset % align,
+            sector_size,
+            cluster_size,
+        })
+    }
+
+    /// Build an alignment from CLI options
+    ///
+    /// Returns `None` when no alignment or geometry option was given.
+    pub fn from_cli(cli: &Cli) -> Result<Option<Self>> {
+        if cli.align.is_none() && cli.align_offset != 0 {
+            return Err(BingrepError::InvalidArgument(
+                "--align-offset requires --align".to_string(),
+            ));
+        }
+
+        if cli.align.is_none() && cli.sector_size.is_none() && cli.cluster_size.is_none() {
+            return Ok(None);
+        }
+
+        Self::new(
+            cli.align.unwrap_or(1),
+            cli.align_offset,
+            cli.sector_size.unwrap_or(DEFAULT_SECTOR_SIZE),
+            cli.cluster_size,
+        )
+        .map(Some)
+    }
+
+    /// Whether this alignment actually filters hits
+    pub fn is_filtering(&self) -> bool {
+        self.align > 1
+    }
+
+    /// Check whether an absolute offset satisfies the alignment
+    pub fn is_aligned(&self, offset: u64) -> bool {
+        offset % self.align == self.align_offset
+    }
+
+    /// First aligned offset at or after `offset`
+    pub fn next_aligned(&self, offset: u64) -> u64 {
+        let block_start = offset - offset % self.align;
+        let candidate = block_start + self.align_offset;
+        if candidate >= offset {
+            candidate
+        } else {
+            candidate + self.align
+        }
+    }
+
+    /// Logical block address containing `offset`
+    pub fn lba(&self, offset: u64) -> u64 {
+        offset / self.sector_size
+    }
+
+    /// Cluster number containing `offset`, if a cluster size is configured
+    pub fn cluster(&self, offset: u64) -> Option<u64> {
+        self.cluster_size.map(|size| offset / size)
+    }
+
+    /// Describe `offset` as LBA (and cluster) for output, e.g. `LBA 2, cluster 0`
+    pub fn describe(&self, offset: u64) -> String {
+        let mut description = format!("LBA {}", self.lba(offset));
+
+        let within_sector = offset % self.sector_size;
+        if within_sector != 0 {
+            description.push_str(&format!("+0x{:X}", within_sector));
+        }
+
+        if let Some(cluster) = self.cluster(offset) {
+            description.push_str(&format!(", cluster {}", cluster));
+        }
+
+        description
+    }
+}
+
+/// Regex matcher that only reports hits at aligned offsets
+///
+/// Instead of filtering the results of an unanchored search, the matcher
+/// tries an anchored regex at each aligned position only, so overlapping
+/// aligned hits are never hidden by an earlier unaligned match.
+pub struct AlignedMatcher<'r> {
+    regex: &'r Regex,
+    anchored: Option<Regex>,
+    alignment: Option<Alignment>,
+}
+
+impl<'r> AlignedMatcher<'r> {
+    /// Create a matcher for `regex` with an optional alignment constraint
+    pub fn new(regex: &'r Regex, alignment: Option<&Alignment>) -> Result<Self> {
+        let anchored = match alignment {
+            Some(a) if a.is_filtering() => {
+                Some(Regex::new(&format!("^(?:{})", regex.as_str()))?)
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            regex,
+            anchored,
+            alignment: alignment.cloned(),
+        })
+    }
+
+    /// Alignment used for filtering and LBA/cluster reporting, if any
+    pub fn alignment(&self) -> Option<&Alignment> {
+        self.alignment.as_ref()
+    }
+
+    /// Find matches in `data`, which starts at absolute offset `base_offset`
+    ///
+    /// Returns match ranges relative to `data`.
+    pub fn find_iter<'a>(
+        &'a self,
+        data: &'a [u8],
+        base_offset: u64,
+    ) -> Box<dyn Iterator<Item = Range<usize>> + 'a> {
+        match (&self.anchored, &self.alignment) {
+            (Some(anchored), Some(alignment)) => {
+                let first = (alignment.next_aligned(base_offset) - base_offset) as usize;
+                let step = alignment.align as usize;
+                Box::new((first..data.len()).step_by(step).filter_map(move |pos| {
+                    anchored
+                        .find(&data[pos..])
+                        .map(|mat| pos..pos + mat.end())
+                }))
+            }
+            _ => Box::new(self.regex.find_iter(data).map(|mat| mat.range())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_aligned_and_next_aligned() {
+        let alignment = Alignment::new(512, 0, 512, None).unwrap();
+        assert!(alignment.is_aligned(0));
+        assert!(alignment.is_aligned(1024));
+        assert!(!alignment.is_aligned(1));
+        assert_eq!(alignment.next_aligned(1), 512);
+        assert_eq!(alignment.next_aligned(512), 512);
+
+        let with_offset = Alignment::new(1024, 8, 512, None).unwrap();
+        assert!(with_offset.is_aligned(1032));
+        assert_eq!(with_offset.next_aligned(9), 1032);
+        assert_eq!(with_offset.next_aligned(1032), 1032);
+    }
+
+    #[test]
+    fn test_describe() {
+        let alignment = Alignment::new(1, 0, 512, Some(4096)).unwrap();
+        assert_eq!(alignment.describe(1024), "LBA 2, cluster 0");
+        assert_eq!(alignment.describe(8192 + 16), "LBA 16+0x10, cluster 2");
+    }
+
+    #[test]
+    fn test_invalid_alignment() {
+        assert!(Alignment::new(0, 0, 512, None).is_err());
+        assert!(Alignment::new(512, 0, 0, None).is_err());
+        assert!(Alignment::new(512, 0, 512, Some(0)).is_err());
+    }
+
+    #[test]
+    fn test_aligned_matcher_finds_overlapping_aligned_hits() {
+        // An unaligned "AAAA" run would hide the aligned hit at 4 from find_iter
+        let data = b"xxAAAAAAxxxx";
+        let regex = Regex::new("AAAA").unwrap();
+        let alignment = Alignment::new(4, 0, 512, None).unwrap();
+        let matcher = AlignedMatcher::new(&regex, Some(&alignment)).unwrap();
+
+        let hits: Vec<_> = matcher.find_iter(data, 0).collect();
+        assert_eq!(hits, vec![4..8]);
+    }
+
+    #[test]
+    fn test_aligned_matcher_uses_absolute_offsets() {
+        let data = b"FILE0xxxFILE0xxx";
+        let regex = Regex::new("FILE0").unwrap();
+        let alignment = Alignment::new(16, 0, 512, None).unwrap();
+        let matcher = AlignedMatcher::new(&regex, Some(&alignment)).unwrap();
+
+        // data starts at absolute offset 8, so only the second hit is aligned
+        let hits: Vec<_> = matcher.find_iter(data, 8).collect();
+        assert_eq!(hits, vec![8..13]);
+    }
+}
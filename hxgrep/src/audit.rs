@@ -0,0 +1,413 @@
+//! Tamper-evident audit log (`--audit-log`, `verify-audit`)
+//!
+//! Every run appends one JSON record per line: when it ran, the full
+//! argument vector, the inputs with their size, modification time and
+//! digests, the patterns, the number of matches, errors and the exit status.
+//! Each record stores the SHA-256 of the record before it and its own
+//! SHA-256, so editing, removing or reordering records breaks the chain,
+//! which `verify-audit` detects.
+
+use crate::error::{BingrepError, Result};
+use crate::hashing::RegionHashes;
+use crate::inspector::civil_from_days;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Version of the audit record format
+pub const AUDIT_VERSION: u32 = 1;
+
+/// `previous_hash` of the first record of a log
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Inputs expanded during the run (multi-file mode), see [`record_inputs`]
+static INPUTS: Mutex<Option<Vec<PathBuf>>> = Mutex::new(None);
+
+/// Digests computed while the inputs were read, see [`record_input_hashes`]
+static INPUT_HASHES: Mutex<Vec<(PathBuf, RegionHashes)>> = Mutex::new(Vec::new());
+
+/// Record the inputs of a run whose inputs are expanded at run time
+pub fn record_inputs(paths: &[PathBuf]) {
+    if let Ok(mut inputs) = INPUTS.lock() {
+        inputs.get_or_insert_with(Vec::new).extend_from_slice(paths);
+    }
+}
+
+/// Inputs recorded with [`record_inputs`], if any
+pub fn recorded_inputs() -> Option<Vec<PathBuf>> {
+    INPUTS.lock().ok().and_then(|inputs| inputs.clone())
+}
+
+/// Record the digests of an input computed while it was searched
+pub fn record_input_hashes<P: AsRef<Path>>(path: P, hashes: &RegionHashes) {
+    if let Ok(mut input_hashes) = INPUT_HASHES.lock() {
+        input_hashes.push((path.as_ref().to_path_buf(), hashes.clone()));
+    }
+}
+
+fn recorded_hashes(path: &Path) -> Option<RegionHashes> {
+    let input_hashes = INPUT_HASHES.lock().ok()?;
+    input_hashes
+        .iter()
+        .find(|(input, _)| input == path)
+        .map(|(_, hashes)| hashes.clone())
+}
+
+/// An input of an audited run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AuditInput {
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    /// Modification time (RFC 3339, UTC)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hashes: Option<RegionHashes>,
+    /// What `hashes` cover; always `input`, the bytes read during the run
+    /// (the media data of forensic images)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash_scope: Option<String>,
+    /// Why the input could not be described
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl AuditInput {
+    /// Describe the input at `path`
+    ///
+    /// Digests are the ones computed while the run read the input (see
+    /// [`record_input_hashes`]); the input is not read again, so inputs the
+    /// run never read get none.
+    pub fn describe<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref();
+        let mut input = AuditInput {
+            path: path.to_string_lossy().into_owned(),
+            size: None,
+            modified: None,
+            hashes: recorded_hashes(path),
+            hash_scope: None,
+            error: None,
+        };
+        if input.hashes.is_some() {
+            input.hash_scope = Some("input".to_string());
+        }
+        if path == Path::new("-") {
+            return input;
+        }
+
+        let metadata = match fs::metadata(path) {
+            Ok(metadata) => metadata,
+            Err(e) => {
+                input.error = Some(e.to_string());
+                return input;
+            }
+        };
+        input.size = Some(metadata.len());
+        input.modified = metadata.modified().ok().map(format_time);
+        input
+    }
+}
+
+/// One run in the audit log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AuditRecord {
+    pub version: u32,
+    /// Position in the log, starting at 1
+    pub sequence: u64,
+    pub tool: String,
+    pub tool_version: String,
+    /// Start and end of the run (RFC 3339, UTC)
+    pub started: String,
+    pub finished: String,
+    pub argv: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub working_directory: Option<String>,
+    pub inputs: Vec<AuditInput>,
+    pub patterns: Vec<String>,
+    /// Matches found by the run
+    pub matches: u64,
+    pub errors: Vec<String>,
+    pub exit_status: u8,
+    /// `record_hash` of the record before this one ([`GENESIS_HASH`] for the first)
+    pub previous_hash: String,
+    /// SHA-256 of this record with an empty `record_hash`, see [`AuditRecord::compute_hash`]
+    pub record_hash: String,
+}
+
+impl AuditRecord {
+    /// Start a record for a run that started at `started`
+    ///
+    /// The chain fields are filled in by [`AuditLog::append`].
+    pub fn new(started: SystemTime, argv: Vec<String>) -> Self {
+        Self {
+            version: AUDIT_VERSION,
+            sequence: 0,
+            tool: env!("CARGO_PKG_NAME").to_string(),
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            started: format_time(started),
+            finished: format_time(SystemTime::now()),
+            argv,
+            working_directory: std::env::current_dir()
+                .ok()
+                .map(|dir| dir.display().to_string()),
+            inputs: Vec::new(),
+            patterns: Vec::new(),
+            matches: 0,
+            errors: Vec::new(),
+            exit_status: 0,
+            previous_hash: String::new(),
+            record_hash: String::new(),
+        }
+    }
+
+    /// SHA-256 over the compact JSON of the record with `record_hash` empty
+    pub fn compute_hash(&self) -> String {
+        let unsealed = AuditRecord {
+            record_hash: String::new(),
+            ..self.clone()
+        };
+        let json = serde_json::to_vec(&unsealed).unwrap_or_default();
+        hex::encode(Sha256::digest(json))
+    }
+}
+
+/// An append-only audit log file
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+impl AuditLog {
+    /// Open the log at `path`, which is created on the first append
+    ///
+    /// An existing log must end with a valid record, so a run is refused
+    /// before it starts instead of extending a damaged chain.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let log = Self {
+            path: path.as_ref().to_path_buf(),
+        };
+        log.last_record()?;
+        Ok(log)
+    }
+
+    /// Chain `record` to the last record of the log and append it
+    ///
+    /// The log is locked while it is read and extended, so concurrent runs
+    /// appending to the same log keep a single chain.
+    pub fn append(&self, mut record: AuditRecord) -> Result<AuditRecord> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let _lock = FileLock::exclusive(&file)?;
+
+        let last = self.last_record()?;
+        record.sequence = last.as_ref().map_or(1, |last| last.sequence + 1);
+        record.previous_hash = last.map_or_else(|| GENESIS_HASH.to_string(), |last| last.record_hash);
+        record.record_hash = record.compute_hash();
+
+        let mut line =
+            serde_json::to_vec(&record).map_err(|e| BingrepError::Output(e.to_string()))?;
+        line.push(b'\n');
+        (&file).write_all(&line)?;
+        file.sync_data()?;
+        Ok(record)
+    }
+
+    /// Last record of the log, `None` for a missing or empty log
+    fn last_record(&self) -> Result<Option<AuditRecord>> {
+        let text = match fs::read_to_string(&self.path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let Some((index, line)) = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .last()
+        else {
+            return Ok(None);
+        };
+        serde_json::from_str(line).map(Some).map_err(|e| {
+            BingrepError::InvalidArgument(format!(
+                "audit log {} line {} is not a valid audit record: {}",
+                self.path.display(),
+                index + 1,
+                e
+            ))
+        })
+    }
+}
+
+/// Exclusive `flock` held until dropped
+struct FileLock<'f>(&'f File);
+
+impl<'f> FileLock<'f> {
+    fn exclusive(file: &'f File) -> io::Result<Self> {
+        use std::os::fd::AsRawFd;
+
+        // SAFETY: flock only operates on the open descriptor
+        if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Self(file))
+    }
+}
+
+impl Drop for FileLock<'_> {
+    fn drop(&mut self) {
+        use std::os::fd::AsRawFd;
+
+        // SAFETY: see FileLock::exclusive
+        unsafe {
+            libc::flock(self.0.as_raw_fd(), libc::LOCK_UN);
+        }
+    }
+}
+
+/// Result of checking an audit log
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuditVerification {
+    /// Number of records read
+    pub records: usize,
+    /// `record_hash` of the last record; keep it elsewhere to detect truncation
+    pub last_hash: Option<String>,
+    /// Problems found, one per broken record
+    pub problems: Vec<String>,
+}
+
+impl AuditVerification {
+    /// Whether the whole chain is intact
+    pub fn is_valid(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Check every record of the log and the chain between them
+pub fn verify<P: AsRef<Path>>(path: P) -> Result<AuditVerification> {
+    let text = fs::read_to_string(path)?;
+    let mut verification = AuditVerification::default();
+    let mut previous_hash = GENESIS_HASH.to_string();
+
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let line_number = index + 1;
+        verification.records += 1;
+        let sequence = verification.records as u64;
+
+        let record: AuditRecord = match serde_json::from_str(line) {
+            Ok(record) => record,
+            Err(e) => {
+                verification
+                    .problems
+                    .push(format!("line {}: not a valid audit record: {}", line_number, e));
+                // The next record can only be checked against this line's claim
+                previous_hash = String::new();
+                continue;
+            }
+        };
+
+        if record.sequence != sequence {
+            verification.problems.push(format!(
+                "line {}: sequence {} where {} was expected (record removed or reordered)",
+                line_number, record.sequence, sequence
+            ));
+        }
+        if !previous_hash.is_empty() && record.previous_hash != previous_hash {
+            verification.problems.push(format!(
+                "line {}: previous_hash does not match the record before it",
+                line_number
+            ));
+        }
+        if record.compute_hash() != record.record_hash {
+            verification.problems.push(format!(
+                "line {}: record_hash does not match the record contents (record modified)",
+                line_number
+            ));
+        }
+        previous_hash = record.record_hash;
+    }
+
+    verification.last_hash = (verification.records > 0 && !previous_hash.is_empty()).then_some(previous_hash);
+    Ok(verification)
+}
+
+/// Format a time as RFC 3339 in UTC with milliseconds
+pub fn format_time(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = since_epoch.as_secs() as i64;
+    let (year, month, day) = civil_from_days(seconds.div_euclid(86_400));
+    let secs = seconds.rem_euclid(86_400);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn record(pattern: &str) -> AuditRecord {
+        let mut record = AuditRecord::new(UNIX_EPOCH, vec!["hxgrep".to_string()]);
+        record.patterns.push(pattern.to_string());
+        record
+    }
+
+    #[test]
+    fn test_format_time() {
+        let time = UNIX_EPOCH + Duration::from_millis(1_700_000_000_123);
+        assert_eq!(format_time(time), "2023-11-14T22:13:20.123Z");
+    }
+
+    #[test]
+    fn test_chain_detects_modification() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let log = AuditLog::open(&path).unwrap();
+
+        let first = log.append(record("FILE0")).unwrap();
+        assert_eq!(first.sequence, 1);
+        assert_eq!(first.previous_hash, GENESIS_HASH);
+        let second = log.append(record("KEY")).unwrap();
+        assert_eq!(second.previous_hash, first.record_hash);
+        log.append(record("MZ")).unwrap();
+
+        let verification = verify(&path).unwrap();
+        assert!(verification.is_valid(), "{:?}", verification.problems);
+        assert_eq!(verification.records, 3);
+
+        // Changing a pattern breaks that record's hash
+        let text = fs::read_to_string(&path).unwrap();
+        fs::write(&path, text.replacen("\"KEY\"", "\"KEX\"", 1)).unwrap();
+        let verification = verify(&path).unwrap();
+        assert_eq!(verification.problems.len(), 1);
+        assert!(verification.problems[0].starts_with("line 2: record_hash"));
+
+        // Removing a record breaks the chain and the sequence
+        let lines: Vec<&str> = text.lines().collect();
+        fs::write(&path, format!("{}\n{}\n", lines[0], lines[2])).unwrap();
+        let verification = verify(&path).unwrap();
+        assert_eq!(verification.problems.len(), 2);
+
+        // A damaged log is not extended
+        fs::write(&path, "not json\n").unwrap();
+        assert!(AuditLog::open(&path).is_err());
+    }
+}
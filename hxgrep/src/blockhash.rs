@@ -0,0 +1,390 @@
+//! Known-block hash matching (sector hash sets)
+//!
+//! Hashes every aligned block of an input and looks the digests up in a hash
+//! set, so fragments of known files are found in unallocated space even when
+//! no file system metadata points at them. Hash sets are plain lists of hex
+//! digests (optionally followed by a name, as written by `md5sum`) or
+//! NSRL-style CSV files with a column per algorithm and a `FileName` column.
+
+use crate::cli::HashAlgorithm;
+use crate::error::{BingrepError, Result};
+use crate::output::OutputFormatter;
+use crate::parallel::scan_chunks;
+use crate::structured_output::StructuredRecord;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek};
+use std::path::Path;
+
+/// Column names that hold the file name in CSV hash sets
+const NAME_COLUMNS: &[&str] = &["filename", "file_name", "name", "path"];
+
+/// A block whose digest is in the hash set
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BlockHashMatch {
+    /// File path of the searched input
+    pub file_path: String,
+    /// Byte offset of the block
+    pub offset: u64,
+    /// Block size in bytes
+    pub length: u64,
+    /// Hash algorithm
+    pub algorithm: String,
+    /// Digest of the block as lowercase hex
+    pub hash: String,
+    /// Name of the known file from the hash set, if it has one
+    pub known_file: Option<String>,
+    /// Hash set file the digest was loaded from
+    pub hashset: String,
+}
+
+impl StructuredRecord for BlockHashMatch {
+    fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    fn hex_line(&self, hex_offset_length: usize) -> String {
+        let line = format!(
+            "{} : {} {}",
+            OutputFormatter::format_offset(self.offset, hex_offset_length),
+            self.algorithm,
+            self.hash
+        );
+        match &self.known_file {
+            Some(name) => OutputFormatter::append_annotation(&line, name),
+            None => line,
+        }
+    }
+
+    fn plain_line(&self) -> String {
+        format!(
+            "{}:{} {} {}",
+            self.file_path,
+            self.offset,
+            self.hash,
+            self.known_file.as_deref().unwrap_or("")
+        )
+    }
+}
+
+/// Entry of a loaded hash set
+#[derive(Debug, Clone)]
+struct KnownBlock {
+    name: Option<String>,
+    /// Index into [`KnownHashes::sources`]
+    source: usize,
+}
+
+/// Digests loaded from one or more hash set files
+#[derive(Debug)]
+pub struct KnownHashes {
+    algorithm: HashAlgorithm,
+    hashes: HashMap<Vec<u8>, KnownBlock>,
+    sources: Vec<String>,
+}
+
+impl KnownHashes {
+    /// Create an empty hash set for `algorithm` digests
+    pub fn new(algorithm: HashAlgorithm) -> Self {
+        Self {
+            algorithm,
+            hashes: HashMap::new(),
+            sources: Vec::new(),
+        }
+    }
+
+    /// Number of distinct digests
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    /// Whether no digest was loaded
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    /// Load a hash set file, detecting plain lists and CSV files
+    ///
+    /// A file whose first line names a column for the algorithm (e.g.
+    /// `"SHA-1","MD5",...,"FileName"` in NSRL files) is read as CSV;
+    /// anything else as one digest per line. The first name loaded for a
+    /// digest is kept.
+    pub fn load<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut first_line = String::new();
+        reader.read_line(&mut first_line)?;
+
+        let source = self.sources.len();
+        self.sources.push(path.display().to_string());
+
+        let header = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .from_reader(first_line.as_bytes())
+            .records()
+            .next()
+            .and_then(|record| record.ok());
+        let hash_column = header.as_ref().and_then(|header| {
+            header
+                .iter()
+                .position(|column| normalize_column(column) == self.algorithm.name())
+        });
+
+        match (header, hash_column) {
+            (Some(header), Some(hash_column)) => {
+                let name_column = header
+                    .iter()
+                    .position(|column| NAME_COLUMNS.contains(&normalize_column(column).as_str()));
+                self.load_csv(path, reader, source, hash_column, name_column)
+            }
+            _ => self.load_list(path, first_line.as_bytes().chain(reader), source),
+        }
+    }
+
+    /// Read CSV rows after the header line
+    fn load_csv<R: Read>(
+        &mut self,
+        path: &Path,
+        reader: R,
+        source: usize,
+        hash_column: usize,
+        name_column: Option<usize>,
+    ) -> Result<()> {
+        let mut csv_reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(reader);
+
+        for (index, record) in csv_reader.records().enumerate() {
+            // The header was line 1
+            let line = index + 2;
+            let record = record.map_err(|e| invalid_line(path, line, &e.to_string()))?;
+            let Some(hash) = record.get(hash_column) else {
+                return Err(invalid_line(path, line, "missing hash column"));
+            };
+            let digest = self
+                .parse_digest(hash)
+                .map_err(|e| invalid_line(path, line, &e))?;
+            let name = name_column
+                .and_then(|column| record.get(column))
+                .filter(|name| !name.is_empty())
+                .map(str::to_string);
+            self.insert(digest, name, source);
+        }
+        Ok(())
+    }
+
+    /// Read one digest per line, optionally followed by whitespace and a name
+    fn load_list<R: BufRead>(&mut self, path: &Path, reader: R, source: usize) -> Result<()> {
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (hash, name) = match line.split_once(char::is_whitespace) {
+                // md5sum marks binary mode with '*' before the name
+                Some((hash, name)) => (hash, Some(name.trim_start().trim_start_matches('*'))),
+                None => (line, None),
+            };
+            let digest = self
+                .parse_digest(hash)
+                .map_err(|e| invalid_line(path, index + 1, &e))?;
+            self.insert(
+                digest,
+                name.filter(|n| !n.is_empty()).map(str::to_string),
+                source,
+            );
+        }
+        Ok(())
+    }
+
+    /// Parse a hex digest of the configured algorithm
+    fn parse_digest(&self, hash: &str) -> std::result::Result<Vec<u8>, String> {
+        let hash = hash.trim();
+        let digest = hex::decode(hash).map_err(|_| format!("'{}' is not a hex digest", hash))?;
+        if digest.len() != self.algorithm.digest_len() {
+            return Err(format!(
+                "'{}' is not a {} digest ({} hex digits expected)",
+                hash,
+                self.algorithm.name(),
+                self.algorithm.digest_len() * 2
+            ));
+        }
+        Ok(digest)
+    }
+
+    fn insert(&mut self, digest: Vec<u8>, name: Option<String>, source: usize) {
+        self.hashes
+            .entry(digest)
+            .or_insert(KnownBlock { name, source });
+    }
+
+    /// Look up a digest
+    fn get(&self, digest: &[u8]) -> Option<(&Option<String>, &str)> {
+        self.hashes
+            .get(digest)
+            .map(|known| (&known.name, self.sources[known.source].as_str()))
+    }
+}
+
+/// Column name in lower case without `-` and `_` separators ("SHA-1" -> "sha1")
+fn normalize_column(column: &str) -> String {
+    let column = column.trim().to_lowercase();
+    if column.starts_with("sha") || column.starts_with("md") {
+        column.replace(['-', '_'], "")
+    } else {
+        column
+    }
+}
+
+fn invalid_line(path: &Path, line: usize, message: &str) -> BingrepError {
+    BingrepError::InvalidArgument(format!(
+        "hash set {} line {}: {}",
+        path.display(),
+        line,
+        message
+    ))
+}
+
+/// Hashes aligned blocks and matches them against a hash set
+pub struct BlockHasher<'h> {
+    known: &'h KnownHashes,
+    block_size: u64,
+}
+
+impl<'h> BlockHasher<'h> {
+    /// Create a hasher for blocks of `block_size` bytes
+    pub fn new(known: &'h KnownHashes, block_size: u64) -> Result<Self> {
+        if block_size == 0 {
+            return Err(BingrepError::InvalidArgument(
+                "--block-size must be greater than 0".to_string(),
+            ));
+        }
+        Ok(Self { known, block_size })
+    }
+
+    /// Hash every block from the current position of `reader` to the end
+    ///
+    /// Blocks start at the current position, which should be the alignment
+    /// origin (e.g. the start of a partition). A partial block at the end of
+    /// the input is not hashed. Chunks are rounded to whole blocks and
+    /// hashed in parallel when `parallel` is set.
+    pub fn scan<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        file_path: &str,
+        chunk_size: usize,
+        parallel: bool,
+    ) -> Result<Vec<BlockHashMatch>> {
+        let block_size = self.block_size as usize;
+        let chunk_size = (chunk_size / block_size).max(1) * block_size;
+        let algorithm = self.known.algorithm;
+
+        scan_chunks(reader, chunk_size, 0, 0, parallel, |chunk| {
+            let mut matches = Vec::new();
+            for (index, block) in chunk.data.chunks_exact(block_size).enumerate() {
+                let digest = algorithm.digest(block);
+                if let Some((name, source)) = self.known.get(&digest) {
+                    matches.push(BlockHashMatch {
+                        file_path: file_path.to_string(),
+                        offset: chunk.data_offset + (index * block_size) as u64,
+                        length: self.block_size,
+                        algorithm: algorithm.name().to_string(),
+                        hash: hex::encode(&digest),
+                        known_file: name.clone(),
+                        hashset: source.to_string(),
+                    });
+                }
+            }
+            matches
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+
+    fn write_file(dir: &Path, name: &str, content: &str) -> std::path::PathBuf {
+        let path = dir.join(name);
+        File::create(&path)
+            .unwrap()
+            .write_all(content.as_bytes())
+            .unwrap();
+        path
+    }
+
+    #[test]
+    fn test_load_list_and_nsrl_csv() {
+        let dir = tempfile::tempdir().unwrap();
+        let block = vec![0x41u8; 512];
+        let md5 = hex::encode(HashAlgorithm::Md5.digest(&block));
+        let sha1 = hex::encode(HashAlgorithm::Sha1.digest(&block)).to_uppercase();
+
+        let list = write_file(
+            dir.path(),
+            "list.txt",
+            &format!("# sector hashes\n{}  *report.doc\n\n", md5),
+        );
+        let mut known = KnownHashes::new(HashAlgorithm::Md5);
+        known.load(&list).unwrap();
+        assert_eq!(known.len(), 1);
+        let (name, _) = known.get(&HashAlgorithm::Md5.digest(&block)).unwrap();
+        assert_eq!(name.as_deref(), Some("report.doc"));
+
+        let nsrl = write_file(
+            dir.path(),
+            "NSRLFile.txt",
+            &format!(
+                "\"SHA-1\",\"MD5\",\"CRC32\",\"FileName\",\"FileSize\"\n\"{}\",\"{}\",\"00000000\",\"setup.exe\",512\n",
+                sha1, md5
+            ),
+        );
+        let mut known = KnownHashes::new(HashAlgorithm::Sha1);
+        known.load(&nsrl).unwrap();
+        let (name, source) = known.get(&HashAlgorithm::Sha1.digest(&block)).unwrap();
+        assert_eq!(name.as_deref(), Some("setup.exe"));
+        assert!(source.ends_with("NSRLFile.txt"));
+
+        // SHA-256 is not a column of this file, so it is read as a list and rejected
+        let mut known = KnownHashes::new(HashAlgorithm::Sha256);
+        assert!(known.load(&nsrl).is_err());
+    }
+
+    #[test]
+    fn test_scan_reports_aligned_blocks_across_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let block = b"known block data".repeat(32);
+        let list = write_file(
+            dir.path(),
+            "list.txt",
+            &hex::encode(HashAlgorithm::Sha256.digest(&block)),
+        );
+        let mut known = KnownHashes::new(HashAlgorithm::Sha256);
+        known.load(&list).unwrap();
+
+        let mut data = vec![0u8; 512 * 8];
+        data[512..1024].copy_from_slice(&block);
+        data[512 * 6..512 * 7].copy_from_slice(&block);
+        // Not aligned to a block boundary
+        data[2000..2512].copy_from_slice(&block);
+
+        let hasher = BlockHasher::new(&known, 512).unwrap();
+        for parallel in [false, true] {
+            let matches = hasher
+                .scan(
+                    &mut Cursor::new(data.clone()),
+                    "unalloc.bin",
+                    1000,
+                    parallel,
+                )
+                .unwrap();
+            let offsets: Vec<u64> = matches.iter().map(|m| m.offset).collect();
+            assert_eq!(offsets, vec![512, 3072]);
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use std::io::{Read, Result};
+use std::io::{ErrorKind, Read, Result};
 
 /// Buffer manager for efficient memory reuse during file processing
 ///
@@ -43,12 +43,27 @@ impl BufferManager {
         &mut self.extra_buffer
     }
 
+    /// Size of the main buffer
+    pub fn main_len(&self) -> usize {
+        self.main_buffer.len()
+    }
+
     /// Read data into main buffer
     ///
-    /// Reads data from the given reader into the main buffer and returns
-    /// the number of bytes read.
+    /// Fills the main buffer from the given reader and returns the number of
+    /// bytes read. Fewer bytes than the buffer size means the reader is at
+    /// end of input, never that a single `read` call came back short.
     pub fn read_into_main<R: Read>(&mut self, reader: &mut R) -> Result<usize> {
-        reader.read(&mut self.main_buffer)
+        let mut filled = 0;
+        while filled < self.main_buffer.len() {
+            match reader.read(&mut self.main_buffer[filled..]) {
+                Ok(0) => break,
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(filled)
     }
 
     /// Read data into extra buffer
@@ -117,6 +132,26 @@ mod tests {
         assert_eq!(&manager.main_buffer[..bytes_read], b"Hello Worl");
     }
 
+    #[test]
+    fn test_read_into_main_fills_across_short_reads() {
+        // A reader that hands out at most 3 bytes per call
+        struct Trickle<'a>(&'a [u8]);
+        impl Read for Trickle<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+                let n = buf.len().min(self.0.len()).min(3);
+                buf[..n].copy_from_slice(&self.0[..n]);
+                self.0 = &self.0[n..];
+                Ok(n)
+            }
+        }
+
+        let mut manager = BufferManager::new(10, 5);
+        let mut reader = Trickle(b"Hello World");
+        assert_eq!(manager.read_into_main(&mut reader).unwrap(), 10);
+        assert_eq!(&manager.main_buffer[..10], b"Hello Worl");
+        assert_eq!(manager.read_into_main(&mut reader).unwrap(), 1);
+    }
+
     #[test]
     fn test_extra_buffer_resize() {
         let mut manager = BufferManager::new(10, 5);
@@ -0,0 +1,287 @@
+//! Resumable searches (`--checkpoint`, `--resume`)
+//!
+//! A checkpoint records how far a search got: the offset up to which every
+//! match has been reported, the last reported match (so matches found again
+//! in overlapping buffers are not repeated), and the number of matches so
+//! far. It also identifies the input (path, size, modification time) and the
+//! patterns, so a resume against a changed input or different search is
+//! refused instead of silently producing wrong results.
+
+use crate::alignment::Alignment;
+use crate::error::{BingrepError, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+/// Version of the checkpoint file format
+pub const CHECKPOINT_VERSION: u32 = 1;
+
+/// Minimum time between two checkpoint writes during a scan
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Identity of a searched input
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputIdentity {
+    /// Path as given on the command line
+    pub path: String,
+    /// File size in bytes
+    pub size: u64,
+    /// Modification time in seconds since the Unix epoch
+    pub modified: u64,
+}
+
+impl InputIdentity {
+    /// Read the identity of the file at `path`
+    pub fn of(path: &str) -> Result<Self> {
+        let metadata = fs::metadata(path)?;
+        let modified = metadata
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        Ok(Self {
+            path: path.to_string(),
+            size: metadata.len(),
+            modified,
+        })
+    }
+}
+
+/// Saved state of a search
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub version: u32,
+    pub input: InputIdentity,
+    /// SHA-256 over the patterns and matching options, see [`pattern_hash`]
+    pub pattern_hash: String,
+    /// Offset up to which all matches have been reported; scanning resumes here
+    pub offset: u64,
+    /// Offset of the last reported match, if any
+    pub last_match: Option<u64>,
+    /// Matches reported so far
+    pub matches: usize,
+}
+
+impl Checkpoint {
+    /// Start state of a new search beginning at `offset`
+    pub fn new(input: InputIdentity, pattern_hash: String, offset: u64) -> Self {
+        Self {
+            version: CHECKPOINT_VERSION,
+            input,
+            pattern_hash,
+            offset,
+            last_match: None,
+            matches: 0,
+        }
+    }
+
+    /// Read a checkpoint file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let data = fs::read(path)?;
+        let checkpoint: Self = serde_json::from_slice(&data).map_err(|e| {
+            BingrepError::InvalidArgument(format!(
+                "{} is not a valid checkpoint file: {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+        if checkpoint.version != CHECKPOINT_VERSION {
+            return Err(BingrepError::InvalidArgument(format!(
+                "{} has checkpoint version {}, expected {}",
+                path.display(),
+                checkpoint.version,
+                CHECKPOINT_VERSION
+            )));
+        }
+        Ok(checkpoint)
+    }
+
+    /// Write the checkpoint to `path`
+    ///
+    /// The file is written next to its destination and renamed over it, so
+    /// an interruption never leaves a truncated checkpoint behind.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let mut temp_name = path.as_os_str().to_owned();
+        temp_name.push(".tmp");
+        let temp_path = PathBuf::from(temp_name);
+
+        let data =
+            serde_json::to_vec_pretty(self).map_err(|e| BingrepError::Output(e.to_string()))?;
+        fs::write(&temp_path, data)?;
+        fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+
+    /// Check that this checkpoint belongs to the given input and patterns
+    pub fn check_resumable(&self, input: &InputIdentity, pattern_hash: &str) -> Result<()> {
+        if self.input.size != input.size || self.input.modified != input.modified {
+            return Err(BingrepError::InvalidArgument(format!(
+                "cannot resume: {} changed since the checkpoint was written \
+                 (size {} -> {}, modified {} -> {})",
+                input.path, self.input.size, input.size, self.input.modified, input.modified
+            )));
+        }
+        if self.pattern_hash != pattern_hash {
+            return Err(BingrepError::InvalidArgument(
+                "cannot resume: the checkpoint was written for a different pattern or alignment"
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Hash the patterns and the options that decide what matches
+pub fn pattern_hash(expressions: &[&str], alignment: Option<&Alignment>) -> String {
+    let mut hasher = Sha256::new();
+    for expression in expressions {
+        hasher.update(expression.as_bytes());
+        hasher.update([0]);
+    }
+    if let Some(alignment) = alignment {
+        hasher.update(format!("{:?}", alignment).as_bytes());
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// Records the progress of a running search into a checkpoint file
+///
+/// Matches counted by the scanner start at zero for every run; the matches
+/// of earlier runs (when resuming) are added on top.
+#[derive(Debug)]
+pub struct Checkpointer {
+    path: Option<PathBuf>,
+    state: Checkpoint,
+    /// Matches reported by earlier runs
+    base_matches: usize,
+    last_write: Instant,
+}
+
+impl Checkpointer {
+    /// Track a search starting from `state`, writing to `path` if given
+    ///
+    /// Without a path (a resume that should not be recorded) the state is
+    /// only used to restart the scan.
+    pub fn new(path: Option<PathBuf>, state: Checkpoint) -> Self {
+        Self {
+            path,
+            base_matches: state.matches,
+            state,
+            last_write: Instant::now(),
+        }
+    }
+
+    /// Offset the scan starts from
+    pub fn offset(&self) -> u64 {
+        self.state.offset
+    }
+
+    /// Last match reported before the scan started or was last recorded
+    pub fn last_match(&self) -> Option<u64> {
+        self.state.last_match
+    }
+
+    /// Matches reported by earlier runs
+    pub fn previous_matches(&self) -> usize {
+        self.base_matches
+    }
+
+    /// Record progress, writing the file at most every few seconds
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - Offset up to which all matches have been reported
+    /// * `last_match` - Offset of the last reported match
+    /// * `matches` - Matches reported by this run
+    pub fn record(&mut self, offset: u64, last_match: Option<u64>, matches: usize) -> Result<()> {
+        self.update(offset, last_match, matches);
+        if self.last_write.elapsed() >= CHECKPOINT_INTERVAL {
+            self.write()?;
+        }
+        Ok(())
+    }
+
+    /// Record the final state of the scan and write it
+    pub fn finish(&mut self, offset: u64, last_match: Option<u64>, matches: usize) -> Result<()> {
+        self.update(offset, last_match, matches);
+        self.write()
+    }
+
+    fn update(&mut self, offset: u64, last_match: Option<u64>, matches: usize) {
+        self.state.offset = offset;
+        self.state.last_match = last_match.or(self.state.last_match);
+        self.state.matches = self.base_matches + matches;
+    }
+
+    fn write(&mut self) -> Result<()> {
+        if let Some(path) = &self.path {
+            self.state.save(path)?;
+        }
+        self.last_write = Instant::now();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn identity() -> InputIdentity {
+        InputIdentity {
+            path: "image.dd".to_string(),
+            size: 4096,
+            modified: 1_700_000_000,
+        }
+    }
+
+    #[test]
+    fn test_checkpoint_roundtrip_and_identity() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("scan.ckpt");
+        let hash = pattern_hash(&["MARK"], None);
+
+        let mut checkpointer = Checkpointer::new(
+            Some(path.clone()),
+            Checkpoint::new(identity(), hash.clone(), 0),
+        );
+        checkpointer.finish(2048, Some(1000), 3).unwrap();
+
+        let loaded = Checkpoint::load(&path).unwrap();
+        assert_eq!(loaded.offset, 2048);
+        assert_eq!(loaded.last_match, Some(1000));
+        assert_eq!(loaded.matches, 3);
+        assert!(loaded.check_resumable(&identity(), &hash).is_ok());
+
+        let changed = InputIdentity {
+            size: 8192,
+            ..identity()
+        };
+        assert!(loaded.check_resumable(&changed, &hash).is_err());
+        assert!(loaded
+            .check_resumable(&identity(), &pattern_hash(&["OTHER"], None))
+            .is_err());
+    }
+
+    #[test]
+    fn test_resumed_matches_add_to_previous() {
+        let mut state = Checkpoint::new(identity(), pattern_hash(&["MARK"], None), 0);
+        state.matches = 5;
+        state.last_match = Some(100);
+
+        let mut checkpointer = Checkpointer::new(None, state);
+        assert_eq!(checkpointer.previous_matches(), 5);
+
+        // No new match keeps the previous last match
+        checkpointer.finish(4096, None, 0).unwrap();
+        assert_eq!(checkpointer.last_match(), Some(100));
+        checkpointer.finish(4096, Some(3000), 2).unwrap();
+        assert_eq!(checkpointer.state.matches, 7);
+        assert_eq!(checkpointer.last_match(), Some(3000));
+    }
+}
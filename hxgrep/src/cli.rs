@@ -1,4 +1,5 @@
-use clap::{Parser, ValueEnum};
+use clap::builder::ArgPredicate;
+use clap::{Args, Parser, Subcommand, ValueEnum};
 
 #[derive(Parser)]
 #[command(name = "hxgrep")]
@@ -13,10 +14,77 @@ Example 01 파일 내용을 HEX값으로 출력:
     hxgrep \"path_to_file.txt\" -n 10    (10줄만 출력)
 
 Example 02 파일 내용을 정규표현식으로 검색:
-    hxgrep \"path_to_file.txt\" -e \"\\x00\\x00\\x00\\x01\\x67\" -w 100")]
+    hxgrep \"path_to_file.txt\" -e \"\\x00\\x00\\x00\\x01\\x67\" -w 100
+
+Example 03 엔트로피 분석 (암호화/압축 영역 탐지):
+    hxgrep entropy \"disk.dd\" --window 4096 --high 7.5 --low 0.5
+
+Example 04 문자열 추출 (ASCII, UTF-16LE):
+    hxgrep strings \"disk.dd\" -n 6 --encoding ascii,utf16le --filter \"(?i)password\"
+
+Example 05 바이너리 치환 (미리보기 후 백업과 함께 적용):
+    hxgrep \"firmware.bin\" -e \"DEBUG=(\\x00)\" --replace \"DEBUG=\\x01\" --dry-run
+    hxgrep \"firmware.bin\" -e \"DEBUG=(\\x00)\" --replace \"DEBUG=\\x01\" --same-length --backup
+
+Example 06 두 펌웨어 비교 (삽입/삭제 인식):
+    hxgrep diff \"fw_v1.bin\" \"fw_v2.bin\" --mode insert
+
+Example 07 바이트 통계 (1MB 구간별):
+    hxgrep stats \"blob.bin\" --range-size 1048576 -p
+
+Example 08 hex 덤프를 바이너리로 복원:
+    hxgrep \"firmware.bin\" > dump.txt
+    hxgrep reverse dump.txt -o firmware_restored.bin
+
+Example 09 캡처 그룹 추출 (PNG 청크 길이 필드):
+    hxgrep \"image.png\" -e \"(?-u)(?P<magic>\\x89PNG)(?P<len>.{4})\" --capture len
+
+Example 10 디렉터리 재귀 검색 (필터 적용):
+    hxgrep \"evidence/\" -r -e \"(?-u)\\x4D\\x5A\\x90\\x00\" --include \"*.bin\" --exclude \"tmp\" --max-size 104857600
+
+Example 11 find 결과를 파일 목록으로 사용:
+    find /mnt/evidence -name \"*.dat\" -print0 | hxgrep --files-from - -0 -e \"FILE0\" -c
+
+Example 12 중단된 검색 이어서 하기:
+    hxgrep \"disk.img\" -e \"FILE0\" -p --checkpoint scan.ckpt > hits.txt
+    hxgrep \"disk.img\" -e \"FILE0\" -p --resume scan.ckpt >> hits.txt
+
+Example 13 n-gram 색인으로 반복 검색 가속:
+    hxgrep index \"disk.E01\" -o disk.hxidx
+    hxgrep \"disk.E01\" -e \"(?i)password=\\w+\" --index disk.hxidx
+
+Example 14 섹터 해시로 알려진 파일 조각 찾기:
+    hxgrep blockhash \"unalloc.bin\" --hashset known_sectors.txt --algorithm md5 --block-size 4096 -p
+
+Example 15 입력 해시와 매칭 영역 해시 기록 (E01은 저장된 해시와 비교):
+    hxgrep \"disk.E01\" -e \"\\xFF\\xD8\\xFF\" --hash md5,sha1 --hash-matches=1048576 -f json
+
+Example 16 포렌식 이미지 메타데이터 확인 (텍스트 또는 JSON):
+    hxgrep info \"disk.E01\"
+    hxgrep info \"vm.vmdk\" -f json
+
+Example 17 감사 로그 기록과 검증:
+    hxgrep \"disk.dd\" -e \"FILE0\" --hash sha256 --audit-log case42.audit
+    hxgrep verify-audit case42.audit
+
+Example 18 HTML 보고서 생성 (사건 정보, 입력 해시, 패턴별 매칭 표, 매칭 주변 hex/ASCII):
+    hxgrep report \"disk.E01\" -e \"FILE0\" -e \"\\xFF\\xD8\\xFF\" --case 2024-017 --examiner Lee --hash md5,sha1 -o report.html")]
 pub struct Cli {
-    /// 입력 파일 경로 또는 glob 패턴 (예: "*.bin", "data/**/*.txt")
-    pub file_path: Option<String>,
+    /// 하위 명령 (entropy, strings 등)
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// 입력 파일 경로 또는 glob 패턴 (예: "*.bin", "data/**/*.txt", 여러 개 지정 시 다중 파일 모드)
+    #[arg(value_name = "PATH")]
+    pub file_paths: Vec<String>,
+
+    /// 검색할 파일 목록을 읽을 파일 (-: stdin, 한 줄에 하나, 다중 파일 모드 포함)
+    #[arg(long = "files-from", value_name = "FILE")]
+    pub files_from: Option<String>,
+
+    /// --files-from 목록이 줄바꿈 대신 NUL 문자로 구분됨 (find -print0 용)
+    #[arg(short = '0', long = "null", requires = "files_from")]
+    pub null_separated: bool,
 
     /// 정규표현식 패턴 (예: -e "\x00\x00\x00\x01\x67")
     #[arg(short = 'e', long = "regex")]
@@ -43,32 +111,597 @@ pub struct Cli {
     pub hide_offset: bool,
 
     /// 병렬 처리 사용 (큰 파일에서 성능 향상)
-    #[arg(short = 'p', long = "parallel")]
+    #[arg(short = 'p', long = "parallel", global = true)]
     pub parallel: bool,
 
     /// 청크 크기 (병렬 처리 시, 바이트 단위, 기본값: 1MB)
-    #[arg(long = "chunk-size", default_value = "1048576")]
+    #[arg(long = "chunk-size", default_value = "1048576", global = true)]
     pub chunk_size: usize,
 
     /// 다중 파일 모드 (glob 패턴 또는 여러 파일 처리)
-    #[arg(short = 'm', long = "multi")]
+    #[arg(
+        short = 'm',
+        long = "multi",
+        default_value_if("recursive", ArgPredicate::IsPresent, "true")
+    )]
     pub multi_file: bool,
 
+    /// 디렉터리를 재귀적으로 검색 (다중 파일 모드 포함)
+    #[arg(short = 'r', long = "recursive")]
+    pub recursive: bool,
+
+    /// 이 glob과 일치하는 파일만 검색 (여러 번 지정 가능, 예: --include "*.bin")
+    #[arg(long = "include", value_name = "GLOB", requires = "recursive")]
+    pub include: Vec<String>,
+
+    /// 이 glob과 일치하는 파일/디렉터리 제외 (여러 번 지정 가능)
+    #[arg(long = "exclude", value_name = "GLOB", requires = "recursive")]
+    pub exclude: Vec<String>,
+
+    /// 이 크기(바이트)보다 작은 파일 제외
+    #[arg(long = "min-size", value_name = "BYTES", requires = "recursive")]
+    pub min_size: Option<u64>,
+
+    /// 이 크기(바이트)보다 큰 파일 제외
+    #[arg(long = "max-size", value_name = "BYTES", requires = "recursive")]
+    pub max_size: Option<u64>,
+
+    /// 최대 디렉터리 깊이 (1: 지정한 디렉터리 바로 아래 파일만)
+    #[arg(long = "max-depth", value_name = "N", requires = "recursive")]
+    pub max_depth: Option<usize>,
+
+    /// 심볼릭 링크를 따라감 (기본: 링크 건너뜀)
+    #[arg(long = "follow", requires = "recursive")]
+    pub follow: bool,
+
+    /// .gitignore, .ignore 규칙에 해당하는 파일 제외
+    #[arg(long = "gitignore", requires = "recursive")]
+    pub gitignore: bool,
+
     /// 전체 파일에 대한 전역 제한 (0: 무제한)
     #[arg(long = "global-limit", default_value = "0")]
     pub global_limit: usize,
 
+    /// 매칭 줄 앞에 파일 이름 출력 (입력이 여러 개면 기본값)
+    #[arg(short = 'H', long = "with-filename")]
+    pub with_filename: bool,
+
+    /// 매칭 줄 앞에 파일 이름을 출력하지 않음
+    #[arg(long = "no-filename", conflicts_with = "with_filename")]
+    pub no_filename: bool,
+
+    /// 병렬 다중 파일 모드(-m -p)의 파일별 출력 순서
+    #[arg(long = "file-order", value_enum, default_value = "input")]
+    pub file_order: FileOrder,
+
+    /// 병렬 처리에 사용할 스레드 수 (0: CPU 코어 수)
+    #[arg(long = "threads", default_value = "0", global = true)]
+    pub threads: usize,
+
     /// 출력 형식 (hex, json, csv, plain)
-    #[arg(short = 'f', long = "format", default_value = "hex")]
+    #[arg(short = 'f', long = "format", default_value = "hex", global = true)]
     pub output_format: String,
 
-    /// 진행률 표시 (대용량 파일 처리 시)
-    #[arg(long = "progress")]
+    /// stderr에 진행률 표시 (퍼센트, 처리 속도, ETA; stderr가 터미널일 때만)
+    #[arg(long = "progress", global = true)]
     pub show_progress: bool,
 
+    /// 진행률 출력 형식 (bar: 진행 막대, json: 한 줄에 하나씩 JSON 이벤트, json은 --progress 없이도 출력)
+    #[arg(long = "progress-format", value_enum, default_value = "bar", global = true)]
+    pub progress_format: ProgressFormat,
+
+    /// JSON 진행률 이벤트를 쓸 파일 디스크립터 (기본값: stderr)
+    #[arg(long = "progress-fd", value_name = "FD", global = true)]
+    pub progress_fd: Option<i32>,
+
+    /// 정렬 단위 (바이트, 해당 경계에서 시작하는 매칭만 출력, 예: --align 512)
+    #[arg(long = "align")]
+    pub align: Option<u64>,
+
+    /// 정렬 경계 내 오프셋 (offset % align == align-offset 인 매칭만 출력)
+    #[arg(long = "align-offset", default_value = "0")]
+    pub align_offset: u64,
+
+    /// LBA 계산에 사용할 섹터 크기 (기본값: 512)
+    #[arg(long = "sector-size")]
+    pub sector_size: Option<u64>,
+
+    /// 클러스터 번호 계산에 사용할 클러스터 크기
+    #[arg(long = "cluster-size")]
+    pub cluster_size: Option<u64>,
+
     /// 색상 출력 설정 (always, never, auto)
-    #[arg(long = "color", default_value = "auto")]
+    #[arg(long = "color", default_value = "auto", global = true)]
     pub color: ColorChoice,
+
+    /// 오프셋 진법 (hex, dec, oct, 기본값: hex; reverse에서는 표기 없는 오프셋의 진법)
+    #[arg(long = "offset-radix", value_enum, global = true)]
+    pub offset_radix: Option<OffsetRadix>,
+
+    /// 오프셋 표기 (suffix: 0010h, prefix: 0x0010, none: 0010)
+    #[arg(long = "offset-style", value_enum, default_value = "suffix", global = true)]
+    pub offset_notation: OffsetNotation,
+
+    /// 16진수를 소문자로 출력
+    #[arg(long = "lowercase", global = true)]
+    pub lowercase: bool,
+
+    /// N바이트 단위로 묶어서 출력 (1, 2, 4, 8)
+    #[arg(short = 'g', long = "group", default_value = "1", global = true)]
+    pub group_size: usize,
+
+    /// 묶음 내 바이트 표시 순서 (big, little, 기본값: big; reverse에서는 묶음 덤프의 바이트 순서)
+    #[arg(long = "endian", value_enum, global = true)]
+    pub endian: Option<WordEndian>,
+
+    /// 매칭 목록 대신 파일별 매칭 개수만 출력
+    #[arg(short = 'c', long = "count", requires = "expression")]
+    pub count: bool,
+
+    /// 매칭이 있는 파일 이름만 출력
+    #[arg(
+        short = 'l',
+        long = "files-with-matches",
+        requires = "expression",
+        conflicts_with_all = ["count", "files_without_match"]
+    )]
+    pub files_with_matches: bool,
+
+    /// 매칭이 없는 파일 이름만 출력
+    #[arg(
+        short = 'L',
+        long = "files-without-match",
+        requires = "expression",
+        conflicts_with = "count"
+    )]
+    pub files_without_match: bool,
+
+    /// 아무것도 출력하지 않고 첫 매칭에서 종료 (종료 코드로만 결과 확인)
+    #[arg(short = 'q', long = "quiet", requires = "expression")]
+    pub quiet: bool,
+
+    /// 매칭된 바이트만 출력 (-w 너비 대신 매칭 길이만큼)
+    #[arg(short = 'o', long = "only-matching", requires = "expression")]
+    pub only_matching: bool,
+
+    /// 지정한 캡처 그룹만 출력 (번호 또는 이름, 여러 번 지정 가능)
+    #[arg(long = "capture", value_name = "NAME|N", value_delimiter = ',', requires = "expression")]
+    pub capture: Vec<String>,
+
+    /// 매칭 위치의 바이트를 해석하여 표시할 형식 (쉼표로 구분, all: 전체)
+    #[arg(long = "inspect", value_delimiter = ',', requires = "expression")]
+    pub inspect: Vec<InspectType>,
+
+    /// 매칭된 부분을 치환할 바이트 (\xHH, $1, ${name} 지원, -e 필요)
+    #[arg(long = "replace", requires = "expression")]
+    pub replace: Option<String>,
+
+    /// 치환 결과의 길이가 원본 매칭과 같아야 함
+    #[arg(long = "same-length", requires = "replace")]
+    pub same_length: bool,
+
+    /// 최대 치환 횟수 (0: 무제한)
+    #[arg(long = "max-replacements", default_value = "0", requires = "replace")]
+    pub max_replacements: usize,
+
+    /// 파일을 수정하지 않고 변경 내용만 출력
+    #[arg(long = "dry-run", requires = "replace")]
+    pub dry_run: bool,
+
+    /// 수정 전 원본을 <파일>.bak으로 백업
+    #[arg(long = "backup", requires = "replace")]
+    pub backup: bool,
+
+    /// 검색 위치와 매칭 수를 주기적으로 기록할 체크포인트 파일 (단일 입력 hex 검색)
+    #[arg(long = "checkpoint", value_name = "FILE", requires = "expression")]
+    pub checkpoint: Option<String>,
+
+    /// 체크포인트 파일의 위치부터 검색 재개 (이전 매칭은 다시 출력하지 않음, --checkpoint가 없으면 같은 파일에 계속 기록)
+    #[arg(
+        long = "resume",
+        value_name = "FILE",
+        requires = "expression",
+        conflicts_with = "position"
+    )]
+    pub resume: Option<String>,
+
+    /// `hxgrep index`로 만든 n-gram 색인을 사용해 후보 블록만 검색 (입력이 바뀌면 오류)
+    #[arg(long = "index", value_name = "FILE", requires = "expression")]
+    pub index: Option<String>,
+
+    /// 입력 전체의 해시를 검색과 같은 읽기 과정에서 계산해 stderr에 출력 (쉼표로 구분, E01은 저장된 해시와 비교)
+    #[arg(long = "hash", value_name = "ALGORITHMS", value_enum, value_delimiter = ',')]
+    pub hash: Vec<HashAlgorithm>,
+
+    /// 매칭마다 --hash 알고리즘의 해시를 첨부 (값 없음: 매칭된 바이트, =N: 매칭 시작부터 N바이트 영역)
+    #[arg(
+        long = "hash-matches",
+        value_name = "BYTES",
+        num_args = 0..=1,
+        default_missing_value = "0",
+        require_equals = true,
+        requires = "hash",
+        requires = "expression"
+    )]
+    pub hash_matches: Option<usize>,
+
+    /// 실행 기록(시각, 인자, 입력 정보와 해시, 패턴, 매칭 수, 오류, 종료 상태)을 해시 체인으로 연결해 추가할 감사 로그 파일
+    #[arg(long = "audit-log", value_name = "FILE", global = true)]
+    pub audit_log: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// 슬라이딩/고정 윈도우 엔트로피 분석 (암호화/압축 영역, 와이핑 영역 탐지)
+    Entropy(EntropyArgs),
+
+    /// 출력 가능한 문자열 추출 (strings -t x 와 유사)
+    Strings(StringsArgs),
+
+    /// 두 파일(또는 포렌식 이미지)의 바이트 단위 비교
+    Diff(DiffArgs),
+
+    /// 바이트 히스토그램 및 통계 (0x00/0xFF/출력 가능 비율, 최장 반복, 엔트로피)
+    Stats(StatsArgs),
+
+    /// hex 덤프 출력을 바이너리로 복원 (xxd -r 과 유사)
+    Reverse(ReverseArgs),
+
+    /// n-gram 색인 생성 (--index로 같은 이미지의 반복 검색 가속)
+    Index(IndexArgs),
+
+    /// 정렬된 블록마다 해시를 계산해 해시 세트와 비교 (비할당 영역의 알려진 파일 조각 탐지)
+    Blockhash(BlockhashArgs),
+
+    /// 포렌식 이미지의 획득 메타데이터 출력 (E01 사건 정보/저장된 해시, VMDK 디스크립터/익스텐트)
+    Info(InfoArgs),
+
+    /// 감사 로그(--audit-log)의 해시 체인 검증 (수정, 삭제, 순서 변경 탐지)
+    VerifyAudit(VerifyAuditArgs),
+
+    /// 검색 결과를 외부 자원 없는 단일 HTML 보고서로 출력 (사건 정보, 입력 해시, 패턴별 매칭 표)
+    Report(ReportArgs),
+}
+
+#[derive(Args)]
+pub struct EntropyArgs {
+    /// 입력 파일 경로 ("-": stdin)
+    pub file_path: String,
+
+    /// 윈도우 크기 (바이트 단위, 기본값: 4096)
+    #[arg(long = "window", default_value = "4096")]
+    pub window: usize,
+
+    /// 윈도우 이동 간격 (기본값: 윈도우 크기, 즉 고정 윈도우)
+    #[arg(long = "step")]
+    pub step: Option<usize>,
+
+    /// 이 값 이상인 영역을 고엔트로피(암호화/압축)로 보고
+    #[arg(long = "high")]
+    pub high: Option<f64>,
+
+    /// 이 값 이하인 영역을 저엔트로피(와이핑/빈 영역)로 보고
+    #[arg(long = "low")]
+    pub low: Option<f64>,
+
+    /// 블록별 엔트로피 맵 출력
+    #[arg(long = "map")]
+    pub map: bool,
+
+    /// 엔트로피 맵 한 줄에 표시할 블록 수
+    #[arg(long = "map-width", default_value = "64")]
+    pub map_width: usize,
+}
+
+#[derive(Args)]
+pub struct StringsArgs {
+    /// 입력 파일 경로 ("-": stdin)
+    pub file_path: String,
+
+    /// 최소 문자열 길이 (문자 수, 기본값: 4)
+    #[arg(short = 'n', long = "min-len", default_value = "4")]
+    pub min_len: usize,
+
+    /// 추출할 인코딩 (쉼표로 구분, utf8은 ascii를 포함)
+    #[arg(
+        long = "encoding",
+        value_delimiter = ',',
+        default_value = "ascii,utf16le"
+    )]
+    pub encodings: Vec<StringEncoding>,
+
+    /// 디코딩된 문자열에 적용할 정규표현식 필터
+    #[arg(long = "filter")]
+    pub filter: Option<String>,
+}
+
+#[derive(Args)]
+pub struct DiffArgs {
+    /// 기준 파일 경로 (포렌식 이미지 지원, "-": stdin)
+    pub file_a: String,
+
+    /// 비교 대상 파일 경로 (포렌식 이미지 지원)
+    pub file_b: String,
+
+    /// 비교 방식 (aligned: 같은 오프셋끼리 비교, insert: 삽입/삭제 인식)
+    #[arg(long = "mode", value_enum, default_value = "aligned")]
+    pub mode: DiffMode,
+
+    /// 차이 범위 대신 요약만 출력
+    #[arg(long = "summary")]
+    pub summary: bool,
+
+    /// 동일한 바이트가 N개 이하로 끼어 있는 차이 범위를 하나로 병합 (aligned)
+    #[arg(long = "merge-gap", default_value = "0")]
+    pub merge_gap: usize,
+
+    /// 삽입/삭제 후 재동기화 지점을 찾을 최대 거리 (insert)
+    #[arg(long = "resync-window", default_value = "4096")]
+    pub resync_window: usize,
+
+    /// 재동기화로 인정할 최소 연속 일치 바이트 수 (insert)
+    #[arg(long = "sync-len", default_value = "16")]
+    pub sync_len: usize,
+
+    /// 차이 범위마다 출력할 최대 바이트 수
+    #[arg(long = "max-bytes", default_value = "16")]
+    pub max_bytes: usize,
+}
+
+#[derive(Args)]
+pub struct StatsArgs {
+    /// 입력 파일 경로 (포렌식 이미지 지원, "-": stdin)
+    pub file_path: String,
+
+    /// 지정한 크기의 구간별 통계도 함께 출력
+    #[arg(long = "range-size")]
+    pub range_size: Option<u64>,
+
+    /// 요약 대신 256개 바이트 값별 히스토그램 출력
+    #[arg(long = "histogram")]
+    pub histogram: bool,
+}
+
+#[derive(Args)]
+pub struct ReverseArgs {
+    /// hex 덤프 파일 경로 ("-": stdin)
+    #[arg(default_value = "-")]
+    pub input: String,
+
+    /// 출력 파일 경로 (생략 시 stdout)
+    #[arg(short = 'o', long = "output")]
+    pub output: Option<String>,
+
+    /// 기존 출력 파일을 자르지 않고 덤프의 오프셋 위치에 덮어쓰기
+    #[arg(long = "patch", requires = "output")]
+    pub patch: bool,
+
+    /// 입력 덤프 형식
+    #[arg(long = "input-format", value_enum, default_value = "auto")]
+    pub input_format: DumpFormat,
+}
+
+#[derive(Args)]
+pub struct IndexArgs {
+    /// 입력 파일 경로 (포렌식 이미지 지원)
+    pub file_path: String,
+
+    /// 색인 파일 경로 (기본값: <입력 파일>.hxidx)
+    #[arg(short = 'o', long = "output")]
+    pub output: Option<String>,
+
+    /// n-gram 길이 (바이트 단위, 2~4)
+    #[arg(
+        long = "ngram",
+        default_value = "3",
+        value_parser = clap::value_parser!(u8).range(2..=4)
+    )]
+    pub ngram: u8,
+
+    /// 색인 블록 크기 (바이트 단위, 작을수록 후보 범위가 좁아지고 색인이 커짐)
+    #[arg(long = "block-size", default_value = "65536")]
+    pub block_size: u64,
+}
+
+#[derive(Args)]
+pub struct BlockhashArgs {
+    /// 입력 파일 경로 (포렌식 이미지 지원, "-": stdin)
+    pub file_path: String,
+
+    /// 해시 세트 파일 (16진수 해시 목록 또는 NSRL 형식 CSV, 여러 번 지정 가능)
+    #[arg(long = "hashset", value_name = "FILE", required = true)]
+    pub hashsets: Vec<String>,
+
+    /// 해시 알고리즘
+    #[arg(long = "algorithm", value_enum, default_value = "md5")]
+    pub algorithm: HashAlgorithm,
+
+    /// 블록 크기 (바이트 단위, 기본값: 4096)
+    #[arg(long = "block-size", default_value = "4096")]
+    pub block_size: u64,
+
+    /// 블록 정렬 기준 오프셋 (예: 파티션 시작 위치)
+    #[arg(long = "block-offset", default_value = "0")]
+    pub block_offset: u64,
+}
+
+#[derive(Args)]
+pub struct InfoArgs {
+    /// 포렌식 이미지 경로 (E01은 첫 세그먼트, VMDK는 디스크립터 또는 익스텐트 파일)
+    pub file_path: String,
+}
+
+#[derive(Args)]
+pub struct VerifyAuditArgs {
+    /// 검증할 감사 로그 파일
+    pub log_path: String,
+}
+
+#[derive(Args)]
+pub struct ReportArgs {
+    /// 입력 파일 경로 (포렌식 이미지 지원, "-": stdin, 여러 개 지정 가능)
+    #[arg(required = true)]
+    pub file_paths: Vec<String>,
+
+    /// 검색할 정규표현식 (여러 번 지정 시 패턴마다 매칭 표 생성)
+    #[arg(short = 'e', long = "regex", value_name = "REGEX", required = true)]
+    pub patterns: Vec<String>,
+
+    /// 보고서 파일 경로 (생략 시 stdout)
+    #[arg(short = 'o', long = "output")]
+    pub output: Option<String>,
+
+    /// 보고서 제목
+    #[arg(long = "title", default_value = "hxgrep search report")]
+    pub title: String,
+
+    /// 사건 번호
+    #[arg(long = "case")]
+    pub case_number: Option<String>,
+
+    /// 조사자 이름
+    #[arg(long = "examiner")]
+    pub examiner: Option<String>,
+
+    /// 입력 해시 알고리즘 (쉼표로 구분, E01은 저장된 MD5/SHA-1과 비교)
+    #[arg(long = "hash", value_delimiter = ',', default_value = "sha256")]
+    pub hash: Vec<HashAlgorithm>,
+
+    /// 매칭 앞뒤로 표시할 문맥 바이트 수
+    #[arg(long = "context", default_value = "32")]
+    pub context: u64,
+
+    /// 패턴과 입력마다 보고할 최대 매칭 수 (0: 제한 없음)
+    #[arg(short = 'n', long = "limit", default_value = "0")]
+    pub limit: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ValueEnum)]
+pub enum HashAlgorithm {
+    /// MD5
+    Md5,
+    /// SHA-1
+    Sha1,
+    /// SHA-256
+    Sha256,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DumpFormat {
+    /// 첫 데이터 줄로 형식 자동 판별
+    Auto,
+    /// hxgrep 기본 출력 ("0010h : 41 42 ...")
+    Hex,
+    /// hxgrep plain 출력 ("file:16 41 42 ...")
+    Plain,
+    /// xxd 출력 ("00000010: 4142 ...  AB")
+    Xxd,
+    /// hexdump -C 출력 ("00000010  41 42 ...  |AB|")
+    Hexdump,
+    /// 오프셋 없는 hex 바이트 (--hideoffset, xxd -p)
+    Raw,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DiffMode {
+    /// 같은 오프셋의 바이트끼리 비교
+    Aligned,
+    /// 삽입/삭제를 인식하여 재동기화
+    Insert,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StringEncoding {
+    /// 7비트 ASCII
+    Ascii,
+    /// UTF-8
+    Utf8,
+    /// UTF-16 리틀 엔디언
+    Utf16le,
+    /// UTF-16 빅 엔디언
+    Utf16be,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum InspectType {
+    /// 부호 없는 8비트 정수
+    U8,
+    /// 부호 있는 8비트 정수
+    I8,
+    /// 부호 없는 16비트 정수 (LE/BE)
+    U16,
+    /// 부호 있는 16비트 정수 (LE/BE)
+    I16,
+    /// 부호 없는 32비트 정수 (LE/BE)
+    U32,
+    /// 부호 있는 32비트 정수 (LE/BE)
+    I32,
+    /// 부호 없는 64비트 정수 (LE/BE)
+    U64,
+    /// 부호 있는 64비트 정수 (LE/BE)
+    I64,
+    /// 32비트 부동소수점 (LE/BE)
+    F32,
+    /// 64비트 부동소수점 (LE/BE)
+    F64,
+    /// Unix 시간 (32비트 초, LE/BE)
+    Unix,
+    /// Windows FILETIME (64비트, 1601년 기준 100ns 단위)
+    Filetime,
+    /// HFS/HFS+ 시간 (32비트 초, 1904년 기준, LE/BE)
+    Hfs,
+    /// MS-DOS 날짜/시간 (FAT, ZIP)
+    Dos,
+    /// GUID (Windows 바이트 순서)
+    Guid,
+    /// UTF-16 문자열 (LE/BE)
+    Utf16,
+    /// 모든 형식
+    All,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OffsetRadix {
+    /// 16진수
+    Hex,
+    /// 10진수
+    Dec,
+    /// 8진수
+    Oct,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OffsetNotation {
+    /// 접미사 (16진수 h, 8진수 o)
+    Suffix,
+    /// 접두사 (16진수 0x, 8진수 0o)
+    Prefix,
+    /// 숫자만 출력
+    None,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum WordEndian {
+    /// 빅 엔디언 (파일 순서 그대로)
+    Big,
+    /// 리틀 엔디언 (묶음 내 바이트 역순, xxd -e)
+    Little,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FileOrder {
+    /// 입력 순서대로 출력 (앞 파일이 끝날 때까지 대기)
+    Input,
+    /// 처리가 끝난 파일부터 출력
+    Completion,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ProgressFormat {
+    /// stderr 진행 막대 (stderr가 터미널일 때만 표시)
+    Bar,
+    /// start, progress, finish, error JSON 이벤트 (한 줄에 하나)
+    Json,
 }
 
 #[derive(Debug, Clone, ValueEnum)]
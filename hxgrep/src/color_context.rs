@@ -6,7 +6,15 @@ use std::sync::OnceLock;
 static COLOR_CONTEXT: OnceLock<ColorChoice> = OnceLock::new();
 
 /// Set the global color choice
+///
+/// `always` and `never` also override the terminal detection of the
+/// `colored` crate, so forced colors survive piping.
 pub fn set_color_choice(color: ColorChoice) {
+    match color {
+        ColorChoice::Always => colored::control::set_override(true),
+        ColorChoice::Never => colored::control::set_override(false),
+        ColorChoice::Auto => {}
+    }
     COLOR_CONTEXT.set(color).ok();
 }
 
@@ -1,3 +1,4 @@
+use crate::alignment::Alignment;
 use crate::cli::Cli;
 use crate::error::{BingrepError, Result};
 
@@ -8,6 +9,9 @@ pub struct Config {
     pub buffer_padding: usize,
     pub max_line_width: usize,
     pub min_line_width: usize,
+    pub alignment: Option<Alignment>,
+    /// Prefix match lines with the file name (`-H`)
+    pub with_filename: bool,
 }
 
 impl Default for Config {
@@ -17,11 +21,22 @@ impl Default for Config {
             buffer_padding: 1024,   // To handle patterns across buffer boundaries
             max_line_width: 8192,   // Maximum bytes per line
             min_line_width: 1,      // Minimum bytes per line
+            alignment: None,        // No sector/cluster alignment by default
+            with_filename: false,   // File names only with -H or several inputs
         }
     }
 }
 
 impl Config {
+    /// Build a configuration from CLI parameters, validating them first
+    pub fn from_cli(cli: &Cli) -> Result<Self> {
+        let mut config = Self::default();
+        config.validate_cli(cli)?;
+        config.alignment = Alignment::from_cli(cli)?;
+        config.with_filename = !cli.no_filename && (cli.with_filename || cli.multi_file);
+        Ok(config)
+    }
+
     /// Validate all input parameters from CLI
     pub fn validate_cli(&self, cli: &Cli) -> Result<()> {
         // Validate line width
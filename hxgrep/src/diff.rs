@@ -0,0 +1,687 @@
+//! Byte-level comparison of two inputs
+//!
+//! Both inputs are streamed through small buffers, so firmware dumps and
+//! forensic images can be compared without loading them into memory. The
+//! aligned mode compares bytes at equal offsets; the insert mode resynchronizes
+//! after inserted or deleted bytes by searching for a common run of bytes.
+
+use crate::cli::DiffMode;
+use crate::error::{BingrepError, Result};
+use crate::output::OutputFormatter;
+use crate::structured_output::StructuredRecord;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{ErrorKind, Read};
+
+/// Kind of a differing range
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffKind {
+    /// Bytes differ on both sides
+    Changed,
+    /// Bytes only present in the second input
+    Inserted,
+    /// Bytes only present in the first input
+    Deleted,
+}
+
+/// A range where the two inputs differ
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DiffRange {
+    /// Kind of difference
+    pub kind: DiffKind,
+    /// Offset of the range in the first input
+    pub offset_a: u64,
+    /// Length of the range in the first input
+    pub length_a: u64,
+    /// Offset of the range in the second input
+    pub offset_b: u64,
+    /// Length of the range in the second input
+    pub length_b: u64,
+    /// Leading bytes of the range in the first input (hex)
+    pub bytes_a: String,
+    /// Leading bytes of the range in the second input (hex)
+    pub bytes_b: String,
+    #[serde(skip)]
+    preview_a: Vec<u8>,
+    #[serde(skip)]
+    preview_b: Vec<u8>,
+}
+
+impl DiffRange {
+    fn new(
+        offset_a: u64,
+        length_a: u64,
+        preview_a: Vec<u8>,
+        offset_b: u64,
+        length_b: u64,
+        preview_b: Vec<u8>,
+    ) -> Self {
+        let kind = match (length_a, length_b) {
+            (0, _) => DiffKind::Inserted,
+            (_, 0) => DiffKind::Deleted,
+            _ => DiffKind::Changed,
+        };
+
+        Self {
+            kind,
+            offset_a,
+            length_a,
+            offset_b,
+            length_b,
+            bytes_a: OutputFormatter::format_bytes_as_hex(&preview_a, " "),
+            bytes_b: OutputFormatter::format_bytes_as_hex(&preview_b, " "),
+            preview_a,
+            preview_b,
+        }
+    }
+
+    /// Build a range from complete byte slices, keeping at most `max_bytes` per side
+    fn from_slices(offset_a: u64, a: &[u8], offset_b: u64, b: &[u8], max_bytes: usize) -> Self {
+        Self::new(
+            offset_a,
+            a.len() as u64,
+            a[..a.len().min(max_bytes)].to_vec(),
+            offset_b,
+            b.len() as u64,
+            b[..b.len().min(max_bytes)].to_vec(),
+        )
+    }
+
+    /// Which displayed bytes of `preview` to highlight
+    ///
+    /// Equal-length ranges highlight only the bytes that differ; for
+    /// insertions, deletions and resized ranges every byte is highlighted.
+    fn highlight_mask(&self, preview: &[u8], other: &[u8]) -> Vec<bool> {
+        if self.length_a == self.length_b {
+            preview
+                .iter()
+                .enumerate()
+                .map(|(i, byte)| other.get(i) != Some(byte))
+                .collect()
+        } else {
+            vec![true; preview.len()]
+        }
+    }
+
+    /// Render the range as `-`/`+` hex lines, optionally colored
+    pub fn render(&self, hex_offset_length: usize, use_color: bool) -> String {
+        let mut lines = Vec::new();
+
+        let sides = [
+            ("-", self.offset_a, self.length_a, &self.preview_a, &self.preview_b),
+            ("+", self.offset_b, self.length_b, &self.preview_b, &self.preview_a),
+        ];
+        for (sign, offset, length, preview, other) in sides {
+            if length == 0 {
+                continue;
+            }
+            let mut hex_data = OutputFormatter::format_bytes_as_hex(preview, " ");
+            let mut mask = self.highlight_mask(preview, other);
+            if length > preview.len() as u64 {
+                hex_data.push_str(" ...");
+                mask.push(false);
+            }
+            let line = OutputFormatter::format_line_with_highlight(
+                offset,
+                &hex_data,
+                hex_offset_length,
+                &mask,
+                use_color,
+            );
+            // Insert the sign between the offset and the bytes
+            lines.push(line.replacen(" : ", &format!(" : {} ", sign), 1));
+        }
+
+        lines.join("\n")
+    }
+}
+
+impl StructuredRecord for DiffRange {
+    fn offset(&self) -> u64 {
+        self.offset_a.max(self.offset_b)
+    }
+
+    fn hex_line(&self, hex_offset_length: usize) -> String {
+        self.render(hex_offset_length, false)
+    }
+
+    fn plain_line(&self) -> String {
+        format!(
+            "{:?} {}+{} {}+{}",
+            self.kind, self.offset_a, self.length_a, self.offset_b, self.length_b
+        )
+        .to_lowercase()
+    }
+}
+
+/// Overall comparison result
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct DiffSummary {
+    /// Path of the first input
+    pub file_a: String,
+    /// Path of the second input
+    pub file_b: String,
+    /// Size of the first input in bytes
+    pub size_a: u64,
+    /// Size of the second input in bytes
+    pub size_b: u64,
+    /// Number of differing ranges
+    pub ranges: u64,
+    /// Total length of differing ranges in the first input
+    pub changed_bytes_a: u64,
+    /// Total length of differing ranges in the second input
+    pub changed_bytes_b: u64,
+    /// Whether both inputs are byte-for-byte identical
+    pub identical: bool,
+}
+
+impl DiffSummary {
+    fn add(&mut self, range: &DiffRange) {
+        self.ranges += 1;
+        self.changed_bytes_a += range.length_a;
+        self.changed_bytes_b += range.length_b;
+    }
+}
+
+impl StructuredRecord for DiffSummary {
+    fn offset(&self) -> u64 {
+        0
+    }
+
+    fn hex_line(&self, _hex_offset_length: usize) -> String {
+        if self.identical {
+            format!(
+                "{} and {} are identical ({} bytes)",
+                self.file_a, self.file_b, self.size_a
+            )
+        } else {
+            format!(
+                "{} ({} bytes) vs {} ({} bytes): {} differing range(s), {} / {} byte(s) changed",
+                self.file_a,
+                self.size_a,
+                self.file_b,
+                self.size_b,
+                self.ranges,
+                self.changed_bytes_a,
+                self.changed_bytes_b
+            )
+        }
+    }
+
+    fn plain_line(&self) -> String {
+        format!(
+            "{} {} {} {} {} {} {}",
+            self.file_a,
+            self.file_b,
+            self.size_a,
+            self.size_b,
+            self.ranges,
+            self.changed_bytes_a,
+            self.changed_bytes_b
+        )
+    }
+}
+
+/// Options for [`Differ`]
+#[derive(Debug, Clone)]
+pub struct DiffOptions {
+    /// Comparison mode
+    pub mode: DiffMode,
+    /// Merge aligned ranges separated by at most this many equal bytes
+    pub merge_gap: usize,
+    /// Maximum distance searched for a resynchronization point (insert mode)
+    pub resync_window: usize,
+    /// Number of equal bytes required to resynchronize (insert mode)
+    pub sync_len: usize,
+    /// Maximum number of bytes kept per side for display
+    pub max_bytes: usize,
+    /// Read size for each input
+    pub chunk_size: usize,
+}
+
+/// Buffered input that tracks the absolute offset of unconsumed data
+struct DiffSource<R: Read> {
+    reader: R,
+    buffer: Vec<u8>,
+    pos: usize,
+    offset: u64,
+    eof: bool,
+}
+
+impl<R: Read> DiffSource<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: Vec::new(),
+            pos: 0,
+            offset: 0,
+            eof: false,
+        }
+    }
+
+    fn data(&self) -> &[u8] {
+        &self.buffer[self.pos..]
+    }
+
+    /// Read until at least `min_len` bytes are buffered or the input ends
+    fn fill(&mut self, min_len: usize, chunk_size: usize) -> Result<()> {
+        while !self.eof && self.data().len() < min_len {
+            if self.pos > 0 {
+                self.buffer.drain(..self.pos);
+                self.pos = 0;
+            }
+
+            let old_len = self.buffer.len();
+            self.buffer.resize(old_len + chunk_size, 0);
+            let read = loop {
+                match self.reader.read(&mut self.buffer[old_len..]) {
+                    Ok(n) => break n,
+                    Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                    Err(e) => {
+                        self.buffer.truncate(old_len);
+                        return Err(e.into());
+                    }
+                }
+            };
+            self.buffer.truncate(old_len + read);
+            self.eof = read == 0;
+        }
+        Ok(())
+    }
+
+    fn consume(&mut self, n: usize) {
+        self.pos += n;
+        self.offset += n as u64;
+    }
+}
+
+/// Differing range being built in aligned mode
+struct PendingRange {
+    start: u64,
+    length: u64,
+    preview_a: Vec<u8>,
+    preview_b: Vec<u8>,
+    /// Equal bytes seen since the last difference
+    gap: Vec<u8>,
+}
+
+impl PendingRange {
+    fn push(&mut self, a: u8, b: u8, max_bytes: usize) {
+        if self.preview_a.len() < max_bytes {
+            self.preview_a.push(a);
+            self.preview_b.push(b);
+        }
+        self.length += 1;
+    }
+
+    fn into_range(self) -> DiffRange {
+        DiffRange::new(
+            self.start,
+            self.length,
+            self.preview_a,
+            self.start,
+            self.length,
+            self.preview_b,
+        )
+    }
+}
+
+/// Streaming comparison of two inputs
+pub struct Differ {
+    options: DiffOptions,
+}
+
+impl Differ {
+    /// Create a new differ
+    pub fn new(options: DiffOptions) -> Result<Self> {
+        if options.chunk_size == 0 {
+            return Err(BingrepError::InvalidArgument(
+                "--chunk-size must be greater than 0".to_string(),
+            ));
+        }
+        if options.sync_len == 0 || options.resync_window == 0 {
+            return Err(BingrepError::InvalidArgument(
+                "--sync-len and --resync-window must be greater than 0".to_string(),
+            ));
+        }
+        Ok(Self { options })
+    }
+
+    /// Compare `a` and `b`, passing each differing range to `on_range` in order
+    pub fn diff<A: Read, B: Read, F: FnMut(DiffRange)>(
+        &self,
+        file_a: &str,
+        a: A,
+        file_b: &str,
+        b: B,
+        mut on_range: F,
+    ) -> Result<DiffSummary> {
+        let mut a = DiffSource::new(a);
+        let mut b = DiffSource::new(b);
+        let mut summary = DiffSummary {
+            file_a: file_a.to_string(),
+            file_b: file_b.to_string(),
+            ..Default::default()
+        };
+
+        {
+            let mut emit = |range: DiffRange| {
+                summary.add(&range);
+                on_range(range);
+            };
+
+            match self.options.mode {
+                DiffMode::Aligned => self.diff_aligned(&mut a, &mut b, &mut emit)?,
+                DiffMode::Insert => self.diff_insert(&mut a, &mut b, &mut emit)?,
+            }
+
+            // Whatever is left exists in only one of the inputs
+            let offset_b = b.offset;
+            self.emit_tail(&mut a, offset_b, true, &mut emit)?;
+            let offset_a = a.offset;
+            self.emit_tail(&mut b, offset_a, false, &mut emit)?;
+        }
+
+        summary.size_a = a.offset;
+        summary.size_b = b.offset;
+        summary.identical = summary.ranges == 0;
+        Ok(summary)
+    }
+
+    fn diff_aligned<A: Read, B: Read>(
+        &self,
+        a: &mut DiffSource<A>,
+        b: &mut DiffSource<B>,
+        emit: &mut dyn FnMut(DiffRange),
+    ) -> Result<()> {
+        let chunk_size = self.options.chunk_size;
+        let max_bytes = self.options.max_bytes;
+        let mut pending: Option<PendingRange> = None;
+
+        loop {
+            a.fill(1, chunk_size)?;
+            b.fill(1, chunk_size)?;
+            let n = a.data().len().min(b.data().len());
+            if n == 0 {
+                break;
+            }
+
+            for (i, (&x, &y)) in a.data()[..n].iter().zip(&b.data()[..n]).enumerate() {
+                if x != y {
+                    match pending.as_mut() {
+                        Some(range) => {
+                            for byte in std::mem::take(&mut range.gap) {
+                                range.push(byte, byte, max_bytes);
+                            }
+                            range.push(x, y, max_bytes);
+                        }
+                        None => {
+                            let mut range = PendingRange {
+                                start: a.offset + i as u64,
+                                length: 0,
+                                preview_a: Vec::new(),
+                                preview_b: Vec::new(),
+                                gap: Vec::new(),
+                            };
+                            range.push(x, y, max_bytes);
+                            pending = Some(range);
+                        }
+                    }
+                } else if let Some(range) = pending.as_mut() {
+                    if range.gap.len() < self.options.merge_gap {
+                        range.gap.push(x);
+                    } else {
+                        emit(pending.take().unwrap().into_range());
+                    }
+                }
+            }
+
+            a.consume(n);
+            b.consume(n);
+        }
+
+        if let Some(range) = pending {
+            emit(range.into_range());
+        }
+        Ok(())
+    }
+
+    fn diff_insert<A: Read, B: Read>(
+        &self,
+        a: &mut DiffSource<A>,
+        b: &mut DiffSource<B>,
+        emit: &mut dyn FnMut(DiffRange),
+    ) -> Result<()> {
+        let chunk_size = self.options.chunk_size;
+        let window = self.options.resync_window;
+        let lookahead = window + self.options.sync_len;
+
+        loop {
+            a.fill(lookahead, chunk_size)?;
+            b.fill(lookahead, chunk_size)?;
+            let (data_a, data_b) = (a.data(), b.data());
+            if data_a.is_empty() || data_b.is_empty() {
+                break;
+            }
+
+            let same = data_a
+                .iter()
+                .zip(data_b)
+                .take_while(|(x, y)| x == y)
+                .count();
+            if same > 0 {
+                a.consume(same);
+                b.consume(same);
+                continue;
+            }
+
+            let at_eof = a.eof && b.eof;
+            let (skip_a, skip_b) =
+                match find_resync(data_a, data_b, window, self.options.sync_len, at_eof) {
+                    Some(point) => point,
+                    // Both inputs are fully buffered; the rest differs entirely
+                    None if at_eof => (data_a.len(), data_b.len()),
+                    None => {
+                        let n = window.min(data_a.len()).min(data_b.len());
+                        (n, n)
+                    }
+                };
+
+            emit(DiffRange::from_slices(
+                a.offset,
+                &data_a[..skip_a],
+                b.offset,
+                &data_b[..skip_b],
+                self.options.max_bytes,
+            ));
+            a.consume(skip_a);
+            b.consume(skip_b);
+        }
+
+        Ok(())
+    }
+
+    /// Emit the remaining bytes of `source` as a deletion (`in_a`) or insertion
+    fn emit_tail<R: Read>(
+        &self,
+        source: &mut DiffSource<R>,
+        other_offset: u64,
+        in_a: bool,
+        emit: &mut dyn FnMut(DiffRange),
+    ) -> Result<()> {
+        let start = source.offset;
+        let mut preview = Vec::new();
+
+        loop {
+            source.fill(1, self.options.chunk_size)?;
+            let data = source.data();
+            if data.is_empty() {
+                break;
+            }
+            let wanted = self.options.max_bytes.saturating_sub(preview.len());
+            preview.extend_from_slice(&data[..wanted.min(data.len())]);
+            let n = data.len();
+            source.consume(n);
+        }
+
+        let length = source.offset - start;
+        if length > 0 {
+            emit(if in_a {
+                DiffRange::new(start, length, preview, other_offset, 0, Vec::new())
+            } else {
+                DiffRange::new(other_offset, 0, Vec::new(), start, length, preview)
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Find the closest point where `a[skip_a..]` and `b[skip_b..]` agree again
+///
+/// Requires `sync_len` equal bytes within `window` of both starts; at the end
+/// of both inputs a shorter equal suffix is accepted as well. Returns the
+/// candidate with the smallest `skip_a + skip_b`.
+fn find_resync(
+    a: &[u8],
+    b: &[u8],
+    window: usize,
+    sync_len: usize,
+    at_eof: bool,
+) -> Option<(usize, usize)> {
+    let mut best: Option<(usize, usize)> = None;
+
+    if a.len() >= sync_len && b.len() >= sync_len {
+        let mut positions: HashMap<&[u8], usize> = HashMap::new();
+        for skip_b in 0..=window.min(b.len() - sync_len) {
+            positions
+                .entry(&b[skip_b..skip_b + sync_len])
+                .or_insert(skip_b);
+        }
+
+        for skip_a in 0..=window.min(a.len() - sync_len) {
+            if let Some((best_a, best_b)) = best {
+                if skip_a >= best_a + best_b {
+                    break;
+                }
+            }
+            if let Some(&skip_b) = positions.get(&a[skip_a..skip_a + sync_len]) {
+                match best {
+                    Some((best_a, best_b)) if best_a + best_b <= skip_a + skip_b => {}
+                    _ => best = Some((skip_a, skip_b)),
+                }
+            }
+        }
+    }
+
+    if best.is_none() && at_eof {
+        // Accept a common suffix shorter than sync_len
+        for skip_a in 0..a.len().min(window + 1) {
+            let suffix = &a[skip_a..];
+            if suffix.len() >= sync_len || suffix.len() > b.len() {
+                continue;
+            }
+            let skip_b = b.len() - suffix.len();
+            if skip_b <= window && &b[skip_b..] == suffix {
+                best = Some((skip_a, skip_b));
+                break;
+            }
+        }
+    }
+
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(mode: DiffMode, a: &[u8], b: &[u8], merge_gap: usize) -> (Vec<DiffRange>, DiffSummary) {
+        let differ = Differ::new(DiffOptions {
+            mode,
+            merge_gap,
+            resync_window: 64,
+            sync_len: 4,
+            max_bytes: 16,
+            chunk_size: 3,
+        })
+        .unwrap();
+        let mut ranges = Vec::new();
+        let summary = differ.diff("a", a, "b", b, |r| ranges.push(r)).unwrap();
+        (ranges, summary)
+    }
+
+    #[test]
+    fn test_aligned_ranges_and_tail() {
+        let (ranges, summary) = run(DiffMode::Aligned, b"ABCDEFGH", b"AXCDEYYHIJ", 0);
+
+        let spans: Vec<_> = ranges
+            .iter()
+            .map(|r| (r.kind, r.offset_a, r.length_a, r.offset_b, r.length_b))
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                (DiffKind::Changed, 1, 1, 1, 1),
+                (DiffKind::Changed, 5, 2, 5, 2),
+                (DiffKind::Inserted, 8, 0, 8, 2),
+            ]
+        );
+        assert_eq!(ranges[1].bytes_a, "46 47");
+        assert_eq!(ranges[1].bytes_b, "59 59");
+        assert_eq!(summary.size_a, 8);
+        assert_eq!(summary.size_b, 10);
+        assert_eq!(summary.ranges, 3);
+        assert!(!summary.identical);
+    }
+
+    #[test]
+    fn test_aligned_merge_gap() {
+        let (ranges, _) = run(DiffMode::Aligned, b"AAAAAAAA", b"AXAXAAAX", 1);
+        assert_eq!(ranges.len(), 2);
+        assert_eq!((ranges[0].offset_a, ranges[0].length_a), (1, 3));
+        assert_eq!(ranges[0].bytes_b, "58 41 58");
+        assert_eq!((ranges[1].offset_a, ranges[1].length_a), (7, 1));
+    }
+
+    #[test]
+    fn test_insert_mode_resynchronizes() {
+        let a = b"HEADER--payload-data-0123456789";
+        let b = b"HEADER--NEWpayload-data-0123456789";
+        let (ranges, summary) = run(DiffMode::Insert, a, b, 0);
+        assert_eq!(ranges.len(), 1);
+        assert_eq!(ranges[0].kind, DiffKind::Inserted);
+        assert_eq!((ranges[0].offset_b, ranges[0].length_b), (8, 3));
+        assert_eq!(ranges[0].bytes_b, "4E 45 57");
+        assert_eq!(summary.changed_bytes_b, 3);
+
+        let (ranges, _) = run(DiffMode::Insert, b, a, 0);
+        assert_eq!(ranges.len(), 1);
+        assert_eq!(ranges[0].kind, DiffKind::Deleted);
+    }
+
+    #[test]
+    fn test_insert_mode_short_suffix_at_eof() {
+        let (ranges, _) = run(DiffMode::Insert, b"0123456789X", b"0123456789YZX", 0);
+        assert_eq!(ranges.len(), 1);
+        assert_eq!(ranges[0].kind, DiffKind::Inserted);
+        assert_eq!(ranges[0].bytes_b, "59 5A");
+    }
+
+    #[test]
+    fn test_identical_inputs() {
+        let (ranges, summary) = run(DiffMode::Insert, b"same bytes", b"same bytes", 0);
+        assert!(ranges.is_empty());
+        assert!(summary.identical);
+        assert_eq!(summary.size_a, 10);
+    }
+
+    #[test]
+    fn test_render_marks_sides() {
+        let range = DiffRange::from_slices(0x10, b"AB", 0x10, b"AC", 16);
+        assert_eq!(range.render(2, false), "10h : - 41 42\n10h : + 41 43");
+
+        let inserted = DiffRange::from_slices(4, b"", 6, b"XYZ", 2);
+        assert_eq!(inserted.render(1, false), "6h : + 58 59 ...");
+    }
+}
@@ -0,0 +1,460 @@
+//! Entropy scanning
+//!
+//! Computes Shannon entropy over fixed or sliding windows to locate encrypted
+//! or compressed regions (high entropy) and wiped areas (low entropy).
+
+use crate::error::{BingrepError, Result};
+use crate::output::OutputFormatter;
+use crate::parallel::{scan_chunks, Chunk};
+use crate::structured_output::StructuredRecord;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Seek};
+
+/// Characters used for the entropy map, from lowest to highest entropy
+const MAP_RAMP: &[u8] = b" .:-=+*#%@";
+
+/// Calculate the Shannon entropy of `data` in bits per byte (0.0 - 8.0)
+pub fn shannon_entropy(data: &[u8]) -> f64 {
+    let mut counts = [0u64; 256];
+    for &b in data {
+        counts[b as usize] += 1;
+    }
+
+    entropy_from_counts(&counts, data.len() as u64)
+}
+
+/// Shannon entropy in bits per byte from a byte histogram of `total` bytes
+pub fn entropy_from_counts(counts: &[u64; 256], total: u64) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+
+    let len = total as f64;
+    counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / len;
+            p * (1.0 / p).log2()
+        })
+        .sum()
+}
+
+/// Classification of a window relative to the configured thresholds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntropyClass {
+    /// At or above the high threshold (encrypted or compressed data)
+    High,
+    /// At or below the low threshold (wiped or sparse data)
+    Low,
+}
+
+impl EntropyClass {
+    fn as_str(&self) -> &'static str {
+        match self {
+            EntropyClass::High => "high",
+            EntropyClass::Low => "low",
+        }
+    }
+}
+
+/// Entropy of a single window
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EntropyBlock {
+    /// File path of the source file
+    pub file_path: String,
+    /// Byte offset of the window
+    pub offset: u64,
+    /// Number of bytes in the window
+    pub length: usize,
+    /// Shannon entropy in bits per byte
+    pub entropy: f64,
+    /// Threshold classification, if any
+    pub class: Option<EntropyClass>,
+}
+
+/// Contiguous run of windows with the same classification
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EntropyRegion {
+    /// File path of the source file
+    pub file_path: String,
+    /// Byte offset where the region starts
+    pub offset: u64,
+    /// Length of the region in bytes
+    pub length: u64,
+    /// Threshold classification of the region
+    pub class: EntropyClass,
+    /// Mean entropy over the windows in the region
+    pub mean_entropy: f64,
+    /// Minimum window entropy in the region
+    pub min_entropy: f64,
+    /// Maximum window entropy in the region
+    pub max_entropy: f64,
+    /// Number of windows merged into the region
+    pub block_count: usize,
+}
+
+impl StructuredRecord for EntropyBlock {
+    fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    fn hex_line(&self, hex_offset_length: usize) -> String {
+        let line = format!(
+            "{} : {:.4}",
+            OutputFormatter::format_offset(self.offset, hex_offset_length),
+            self.entropy
+        );
+        match self.class {
+            Some(class) => OutputFormatter::append_annotation(&line, class.as_str()),
+            None => line,
+        }
+    }
+
+    fn plain_line(&self) -> String {
+        format!("{}:{} {:.4}", self.file_path, self.offset, self.entropy)
+    }
+}
+
+impl StructuredRecord for EntropyRegion {
+    fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    fn hex_line(&self, hex_offset_length: usize) -> String {
+        format!(
+            "{} - {} : {} entropy, {} bytes (avg {:.4}, min {:.4}, max {:.4})",
+            OutputFormatter::format_offset(self.offset, hex_offset_length),
+            OutputFormatter::format_offset(self.offset + self.length, hex_offset_length),
+            self.class.as_str(),
+            self.length,
+            self.mean_entropy,
+            self.min_entropy,
+            self.max_entropy
+        )
+    }
+
+    fn plain_line(&self) -> String {
+        format!(
+            "{}:{} {} {} {:.4}",
+            self.file_path,
+            self.offset,
+            self.length,
+            self.class.as_str(),
+            self.mean_entropy
+        )
+    }
+}
+
+/// Windowed entropy scanner
+pub struct EntropyScanner {
+    window: usize,
+    step: usize,
+    high: Option<f64>,
+    low: Option<f64>,
+}
+
+impl EntropyScanner {
+    /// Create a new scanner
+    ///
+    /// # Arguments
+    ///
+    /// * `window` - Window size in bytes
+    /// * `step` - Distance between window starts (equal to `window` for fixed windows)
+    pub fn new(window: usize, step: usize) -> Result<Self> {
+        if window == 0 {
+            return Err(BingrepError::InvalidArgument(
+                "--window must be greater than 0".to_string(),
+            ));
+        }
+        if step == 0 || step > window {
+            return Err(BingrepError::InvalidArgument(format!(
+                "--step must be between 1 and the window size ({})",
+                window
+            )));
+        }
+
+        Ok(Self {
+            window,
+            step,
+            high: None,
+            low: None,
+        })
+    }
+
+    /// Set the high/low thresholds used to classify windows
+    pub fn with_thresholds(mut self, high: Option<f64>, low: Option<f64>) -> Self {
+        self.high = high;
+        self.low = low;
+        self
+    }
+
+    /// Whether any threshold is configured
+    pub fn has_thresholds(&self) -> bool {
+        self.high.is_some() || self.low.is_some()
+    }
+
+    /// Classify an entropy value against the thresholds
+    pub fn classify(&self, entropy: f64) -> Option<EntropyClass> {
+        match (self.high, self.low) {
+            (Some(high), _) if entropy >= high => Some(EntropyClass::High),
+            (_, Some(low)) if entropy <= low => Some(EntropyClass::Low),
+            _ => None,
+        }
+    }
+
+    /// Scan a reader from its current position to the end
+    ///
+    /// The input is read in chunks of roughly `chunk_size` bytes, which are
+    /// evaluated in parallel when `parallel` is set.
+    pub fn scan<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        file_path: &str,
+        chunk_size: usize,
+        parallel: bool,
+    ) -> Result<Vec<EntropyBlock>> {
+        let origin = reader.stream_position()?;
+        // Chunks hold whole steps; sliding windows reach into the next chunk
+        let chunk_size = (chunk_size / self.step).max(1) * self.step;
+        let overlap = self.window - self.step;
+
+        scan_chunks(reader, chunk_size, 0, overlap, parallel, |chunk| {
+            self.scan_chunk(chunk, origin, file_path)
+        })
+    }
+
+    /// Evaluate the windows starting inside the owned range of a chunk
+    ///
+    /// Like [`Self::scan_buffer`], a window cut off by the end of the input
+    /// is only kept when the window before it did not reach the end.
+    fn scan_chunk(&self, chunk: &Chunk, origin: u64, file_path: &str) -> Vec<EntropyBlock> {
+        let (window, step) = (self.window as u64, self.step as u64);
+        let data_end = chunk.data_offset + chunk.data.len() as u64;
+
+        chunk
+            .owned
+            .clone()
+            .step_by(self.step)
+            .take_while(|&start| {
+                start + window <= data_end || start == origin || start + window - step < data_end
+            })
+            .map(|start| {
+                let from = (start - chunk.data_offset) as usize;
+                let to = (from + self.window).min(chunk.data.len());
+                let entropy = shannon_entropy(&chunk.data[from..to]);
+                EntropyBlock {
+                    file_path: file_path.to_string(),
+                    offset: start,
+                    length: to - from,
+                    entropy,
+                    class: self.classify(entropy),
+                }
+            })
+            .collect()
+    }
+
+    /// Scan an in-memory buffer starting at absolute offset `base_offset`
+    ///
+    /// When `at_eof` is set, a final partial window covers the tail of the data.
+    pub fn scan_buffer(
+        &self,
+        data: &[u8],
+        base_offset: u64,
+        file_path: &str,
+        at_eof: bool,
+        parallel: bool,
+    ) -> Vec<EntropyBlock> {
+        let mut starts = Vec::new();
+        let mut start = 0;
+        while start < data.len() {
+            let full = start + self.window <= data.len();
+            if !full && !at_eof {
+                break;
+            }
+            starts.push(start);
+            if !full || start + self.window == data.len() {
+                break;
+            }
+            start += self.step;
+        }
+
+        let evaluate = |&start: &usize| {
+            let end = (start + self.window).min(data.len());
+            let entropy = shannon_entropy(&data[start..end]);
+            EntropyBlock {
+                file_path: file_path.to_string(),
+                offset: base_offset + start as u64,
+                length: end - start,
+                entropy,
+                class: self.classify(entropy),
+            }
+        };
+
+        if parallel {
+            starts.par_iter().map(evaluate).collect()
+        } else {
+            starts.iter().map(evaluate).collect()
+        }
+    }
+
+    /// Merge consecutive classified windows into regions
+    pub fn merge_regions(&self, blocks: &[EntropyBlock]) -> Vec<EntropyRegion> {
+        let mut regions: Vec<EntropyRegion> = Vec::new();
+        let mut entropy_sum = 0.0;
+
+        for block in blocks {
+            let Some(class) = block.class else {
+                continue;
+            };
+            let block_end = block.offset + block.length as u64;
+
+            if let Some(region) = regions.last_mut() {
+                let region_end = region.offset + region.length;
+                if region.class == class && block.offset <= region_end {
+                    region.length = block_end.max(region_end) - region.offset;
+                    region.min_entropy = region.min_entropy.min(block.entropy);
+                    region.max_entropy = region.max_entropy.max(block.entropy);
+                    region.block_count += 1;
+                    entropy_sum += block.entropy;
+                    region.mean_entropy = entropy_sum / region.block_count as f64;
+                    continue;
+                }
+            }
+
+            entropy_sum = block.entropy;
+            regions.push(EntropyRegion {
+                file_path: block.file_path.clone(),
+                offset: block.offset,
+                length: block.length as u64,
+                class,
+                mean_entropy: block.entropy,
+                min_entropy: block.entropy,
+                max_entropy: block.entropy,
+                block_count: 1,
+            });
+        }
+
+        regions
+    }
+}
+
+/// Render a compact entropy map with one character per window
+///
+/// Each row shows `columns` windows prefixed with the offset of the first one.
+pub fn render_map(blocks: &[EntropyBlock], columns: usize, hex_offset_length: usize) -> Vec<String> {
+    let columns = columns.max(1);
+
+    blocks
+        .chunks(columns)
+        .map(|row| {
+            let cells: String = row
+                .iter()
+                .map(|block| {
+                    let level = (block.entropy / 8.0 * MAP_RAMP.len() as f64) as usize;
+                    MAP_RAMP[level.min(MAP_RAMP.len() - 1)] as char
+                })
+                .collect();
+            format!(
+                "{} : |{}|",
+                OutputFormatter::format_offset(row[0].offset, hex_offset_length),
+                cells
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_shannon_entropy() {
+        assert_eq!(shannon_entropy(&[]), 0.0);
+        assert_eq!(shannon_entropy(&[0u8; 1024]), 0.0);
+
+        let all_bytes: Vec<u8> = (0..=255).collect();
+        assert!((shannon_entropy(&all_bytes) - 8.0).abs() < 1e-9);
+
+        assert!((shannon_entropy(b"ABAB") - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_fixed_windows_cover_tail() {
+        let data = vec![0u8; 10];
+        let scanner = EntropyScanner::new(4, 4).unwrap();
+        let blocks = scanner.scan_buffer(&data, 0, "test.bin", true, false);
+
+        let offsets: Vec<u64> = blocks.iter().map(|b| b.offset).collect();
+        assert_eq!(offsets, vec![0, 4, 8]);
+        assert_eq!(blocks[2].length, 2);
+    }
+
+    #[test]
+    fn test_sliding_scan_matches_across_chunks() {
+        let data: Vec<u8> = (0..5000u32).map(|i| (i * 7 % 251) as u8).collect();
+        let scanner = EntropyScanner::new(256, 64).unwrap();
+
+        let whole = scanner.scan_buffer(&data, 0, "test.bin", true, false);
+        let whole_offsets: Vec<u64> = whole.iter().map(|b| b.offset).collect();
+
+        // Each read is 1152 bytes advancing by 960; 4992 bytes ends exactly on a read
+        for len in [5000, 4992, 4800, 1000, 100, 0] {
+            let expected: Vec<u64> = scanner
+                .scan_buffer(&data[..len], 0, "test.bin", true, false)
+                .iter()
+                .map(|b| b.offset)
+                .collect();
+            let chunked: Vec<u64> = scanner
+                .scan(&mut Cursor::new(&data[..len]), "test.bin", 1000, true)
+                .unwrap()
+                .iter()
+                .map(|b| b.offset)
+                .collect();
+            assert_eq!(expected, chunked);
+        }
+        // The partial tail window starts right after the last full one
+        assert_eq!(whole_offsets.last(), Some(&4800));
+
+        // Fixed windows, from a position other than the start
+        let scanner = EntropyScanner::new(300, 300).unwrap();
+        let mut reader = Cursor::new(&data);
+        reader.set_position(700);
+        let chunked: Vec<u64> = scanner
+            .scan(&mut reader, "test.bin", 1000, false)
+            .unwrap()
+            .iter()
+            .map(|b| b.offset)
+            .collect();
+        let expected: Vec<u64> = (700..5000).step_by(300).collect();
+        assert_eq!(chunked, expected);
+    }
+
+    #[test]
+    fn test_merge_regions() {
+        let mut data = vec![0u8; 1024];
+        data.extend((0..=255u8).cycle().take(1024));
+        let scanner = EntropyScanner::new(256, 256)
+            .unwrap()
+            .with_thresholds(Some(7.5), Some(0.5));
+
+        let blocks = scanner.scan_buffer(&data, 0, "test.bin", true, false);
+        let regions = scanner.merge_regions(&blocks);
+
+        assert_eq!(regions.len(), 2);
+        assert_eq!(regions[0].class, EntropyClass::Low);
+        assert_eq!((regions[0].offset, regions[0].length), (0, 1024));
+        assert_eq!(regions[1].class, EntropyClass::High);
+        assert_eq!((regions[1].offset, regions[1].length), (1024, 1024));
+        assert_eq!(regions[1].block_count, 4);
+    }
+
+    #[test]
+    fn test_invalid_scanner_parameters() {
+        assert!(EntropyScanner::new(0, 0).is_err());
+        assert!(EntropyScanner::new(16, 32).is_err());
+    }
+}
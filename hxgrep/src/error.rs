@@ -9,6 +9,13 @@ pub enum BingrepError {
     RegexCompilation(String),
     GlobPattern(String),
     GlobPath(String),
+    InvalidArgument(String),
+    Output(String),
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
 }
 
 impl fmt::Display for BingrepError {
@@ -29,6 +36,13 @@ impl fmt::Display for BingrepError {
             BingrepError::RegexCompilation(msg) => write!(f, "Regex compilation error: {}", msg),
             BingrepError::GlobPattern(msg) => write!(f, "Glob pattern error: {}", msg),
             BingrepError::GlobPath(msg) => write!(f, "Glob path error: {}", msg),
+            BingrepError::InvalidArgument(msg) => write!(f, "Invalid argument: {}", msg),
+            BingrepError::Output(msg) => write!(f, "Output error: {}", msg),
+            BingrepError::Parse {
+                line,
+                column,
+                message,
+            } => write!(f, "Parse error at line {}, column {}: {}", line, column, message),
         }
     }
 }
@@ -66,4 +80,27 @@ impl From<glob::GlobError> for BingrepError {
     }
 }
 
+impl From<Box<dyn std::error::Error>> for BingrepError {
+    fn from(err: Box<dyn std::error::Error>) -> Self {
+        // Writer failures stay I/O errors so a closed pipe is still recognised
+        let err = match err.downcast::<io::Error>() {
+            Ok(err) => return BingrepError::Io(*err),
+            Err(err) => err,
+        };
+        let err = match err.downcast::<serde_json::Error>() {
+            Ok(err) if err.is_io() => return BingrepError::Io(io::Error::from(*err)),
+            Ok(err) => return BingrepError::Output(err.to_string()),
+            Err(err) => err,
+        };
+        match err.downcast::<csv::Error>() {
+            Ok(err) if err.is_io_error() => match err.into_kind() {
+                csv::ErrorKind::Io(err) => BingrepError::Io(err),
+                kind => BingrepError::Output(format!("{:?}", kind)),
+            },
+            Ok(err) => BingrepError::Output(err.to_string()),
+            Err(err) => BingrepError::Output(err.to_string()),
+        }
+    }
+}
+
 pub type Result<T> = std::result::Result<T, BingrepError>;
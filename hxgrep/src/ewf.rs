@@ -0,0 +1,603 @@
+//! Expert Witness Format (E01) metadata
+//!
+//! Reads the section structure of EWF segment files (`.E01`, `.E02`, ...)
+//! directly, independently of the image reader, to get at the metadata the
+//! acquisition tool stored alongside the media data: the media geometry,
+//! the case information of the `header` sections and the MD5 and SHA-1 of
+//! the acquired media.
+
+use crate::cli::HashAlgorithm;
+use crate::error::{BingrepError, Result};
+use crate::hashing::RegionHashes;
+use crate::inspector::format_timestamp;
+use flate2::read::ZlibDecoder;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// Signature at the start of every EWF (E01) segment file
+const EVF_SIGNATURE: &[u8; 8] = b"EVF\x09\x0d\x0a\xff\x00";
+
+/// Size of the segment file header
+const FILE_HEADER_LEN: u64 = 13;
+
+/// Size of a section descriptor
+const SECTION_DESCRIPTOR_LEN: u64 = 76;
+
+/// Size of the EnCase (E01) volume section data; SMART (S01) ones are smaller
+const E01_VOLUME_LEN: u64 = 1052;
+
+/// Largest compressed `header` section that is read
+const MAX_HEADER_LEN: u64 = 16 * 1024 * 1024;
+
+/// A section of an EWF segment file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EwfSection {
+    /// Section type ("header", "volume", "table", "hash", "digest", ...)
+    pub kind: String,
+    /// Offset of the section descriptor in the segment file
+    pub offset: u64,
+    /// Size of the section including its descriptor
+    pub size: u64,
+}
+
+impl EwfSection {
+    /// Offset of the section data, after the descriptor
+    pub fn data_offset(&self) -> u64 {
+        self.offset + SECTION_DESCRIPTOR_LEN
+    }
+}
+
+/// Media hashes stored by the acquisition tool
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct StoredHashes {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub md5: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha1: Option<String>,
+}
+
+/// A segment file of an image
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EwfSegment {
+    pub path: String,
+    pub size: u64,
+}
+
+/// Media geometry from the `volume` (or `disk`) section
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct EwfMedia {
+    /// Media type ("fixed", "removable", ...), E01 only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media_type: Option<String>,
+    pub sector_count: u64,
+    pub sector_size: u32,
+    pub sectors_per_chunk: u32,
+    pub chunk_count: u32,
+    /// Compression level ("none", "fast", "best"), E01 only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compression: Option<String>,
+}
+
+impl EwfMedia {
+    /// Size of the acquired media in bytes
+    pub fn media_size(&self) -> u64 {
+        self.sector_count * u64::from(self.sector_size)
+    }
+
+    /// Size of a chunk in bytes
+    pub fn chunk_size(&self) -> u64 {
+        u64::from(self.sectors_per_chunk) * u64::from(self.sector_size)
+    }
+
+    fn parse(data: &[u8]) -> Option<Self> {
+        let u32_at = |at: usize| Some(u32::from_le_bytes(data.get(at..at + 4)?.try_into().ok()?));
+        let mut media = EwfMedia {
+            chunk_count: u32_at(4)?,
+            sectors_per_chunk: u32_at(8)?,
+            sector_size: u32_at(12)?,
+            ..EwfMedia::default()
+        };
+
+        if data.len() as u64 >= E01_VOLUME_LEN {
+            media.sector_count = u64::from_le_bytes(data[16..24].try_into().ok()?);
+            media.media_type = Some(
+                match data[0] {
+                    0x00 => "removable",
+                    0x01 => "fixed",
+                    0x03 => "optical",
+                    0x0e => "logical",
+                    0x10 => "memory",
+                    _ => "unknown",
+                }
+                .to_string(),
+            );
+            media.compression = Some(
+                match data[52] {
+                    0 => "none",
+                    1 => "fast",
+                    2 => "best",
+                    _ => "unknown",
+                }
+                .to_string(),
+            );
+        } else {
+            media.sector_count = u64::from(u32_at(16)?);
+        }
+        Some(media)
+    }
+}
+
+/// Case information recorded by the examiner at acquisition
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct EwfCase {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub case_number: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub evidence_number: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub examiner: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub acquisition_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub acquisition_software: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub acquisition_os: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub serial_number: Option<String>,
+    /// Compression recorded in the header ("none", "fast", "best")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compression: Option<String>,
+}
+
+impl EwfCase {
+    /// Case information from the `key -> value` table of a header section
+    ///
+    /// `header2` sections store dates as Unix timestamps, `header` sections
+    /// as "year month day hour minute second" in the examiner's local time.
+    fn from_values(values: &HashMap<String, String>) -> Self {
+        let get = |key: &str| values.get(key).filter(|value| !value.is_empty()).cloned();
+        let date = |key: &str| get(key).map(|value| format_header_date(&value));
+
+        EwfCase {
+            case_number: get("c"),
+            evidence_number: get("n"),
+            description: get("a"),
+            examiner: get("e"),
+            notes: get("t"),
+            acquisition_date: date("m"),
+            system_date: date("u"),
+            acquisition_software: get("av"),
+            acquisition_os: get("ov"),
+            model: get("md"),
+            serial_number: get("sn"),
+            compression: get("r").map(|level| {
+                match level.as_str() {
+                    "n" => "none",
+                    "f" => "fast",
+                    "b" => "best",
+                    other => other,
+                }
+                .to_string()
+            }),
+        }
+    }
+}
+
+/// Acquisition metadata of an EWF image
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct EwfInfo {
+    pub segments: Vec<EwfSegment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media: Option<EwfMedia>,
+    pub case: EwfCase,
+    pub stored_hashes: StoredHashes,
+}
+
+impl EwfInfo {
+    /// Read the metadata of the image starting with segment `first`
+    pub fn read(first: &Path) -> Result<Self> {
+        let mut info = EwfInfo::default();
+        let mut header = None;
+        let mut header2 = None;
+
+        for path in segment_paths(first) {
+            info.segments.push(EwfSegment {
+                path: path.display().to_string(),
+                size: std::fs::metadata(&path)?.len(),
+            });
+
+            for section in read_sections(&path)? {
+                let data_len = section.size.saturating_sub(SECTION_DESCRIPTOR_LEN);
+                match section.kind.as_str() {
+                    "volume" | "disk" if info.media.is_none() => {
+                        let data = read_section_data(&path, &section, data_len.min(E01_VOLUME_LEN) as usize)?;
+                        info.media = EwfMedia::parse(&data);
+                    }
+                    "header" if header.is_none() && data_len <= MAX_HEADER_LEN => {
+                        let data = read_section_data(&path, &section, data_len as usize)?;
+                        header = header_values(&data, false);
+                    }
+                    "header2" if header2.is_none() && data_len <= MAX_HEADER_LEN => {
+                        let data = read_section_data(&path, &section, data_len as usize)?;
+                        header2 = header_values(&data, true);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        // header2 holds the same table as header, in UTF-16 and with more fields
+        if let Some(values) = header2.or(header) {
+            info.case = EwfCase::from_values(&values);
+        }
+        info.stored_hashes = stored_hashes(first)?;
+        Ok(info)
+    }
+}
+
+/// Result of comparing a computed digest with the stored one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HashCheck {
+    pub algorithm: HashAlgorithm,
+    pub stored: String,
+    pub computed: String,
+}
+
+impl HashCheck {
+    /// Whether the computed digest equals the stored one
+    pub fn is_match(&self) -> bool {
+        self.stored.eq_ignore_ascii_case(&self.computed)
+    }
+}
+
+impl StoredHashes {
+    /// Compare with the digests computed over the media data
+    ///
+    /// Only algorithms that are both stored and computed are compared.
+    pub fn check(&self, computed: &RegionHashes) -> Vec<HashCheck> {
+        [
+            (HashAlgorithm::Md5, &self.md5),
+            (HashAlgorithm::Sha1, &self.sha1),
+        ]
+        .into_iter()
+        .filter_map(|(algorithm, stored)| {
+            Some(HashCheck {
+                algorithm,
+                stored: stored.clone()?,
+                computed: computed.get(algorithm)?.to_string(),
+            })
+        })
+        .collect()
+    }
+}
+
+/// Segment files of an image, starting with the given first segment
+///
+/// Segments are numbered `.E01` to `.E99`, then `.EAA`, `.EAB`, ... and
+/// are collected until the first missing one.
+pub fn segment_paths(first: &Path) -> Vec<PathBuf> {
+    let Some(extension) = first.extension().and_then(|ext| ext.to_str()) else {
+        return vec![first.to_path_buf()];
+    };
+    let lowercase = extension.starts_with('e');
+
+    let mut paths = vec![first.to_path_buf()];
+    for number in 2.. {
+        let Some(extension) = segment_extension(number) else {
+            break;
+        };
+        let extension = if lowercase {
+            extension.to_lowercase()
+        } else {
+            extension
+        };
+        let path = first.with_extension(extension);
+        if !path.exists() {
+            break;
+        }
+        paths.push(path);
+    }
+    paths
+}
+
+/// Extension of segment `number` (1-based), `None` past the last one
+fn segment_extension(number: u32) -> Option<String> {
+    if number < 100 {
+        return Some(format!("E{:02}", number));
+    }
+    let index = number - 100;
+    let first = b'E' + (index / (26 * 26)) as u8;
+    if first > b'Z' {
+        return None;
+    }
+    let second = b'A' + ((index / 26) % 26) as u8;
+    let third = b'A' + (index % 26) as u8;
+    Some(String::from_utf8_lossy(&[first, second, third]).into_owned())
+}
+
+/// Read the section list of one segment file
+pub fn read_sections(path: &Path) -> Result<Vec<EwfSection>> {
+    let mut file = File::open(path)?;
+    let mut header = [0u8; FILE_HEADER_LEN as usize];
+    file.read_exact(&mut header).map_err(|_| not_ewf(path))?;
+    if &header[..8] != EVF_SIGNATURE {
+        return Err(not_ewf(path));
+    }
+
+    let file_len = file.metadata()?.len();
+    let mut sections = Vec::new();
+    let mut offset = FILE_HEADER_LEN;
+    let mut descriptor = [0u8; SECTION_DESCRIPTOR_LEN as usize];
+
+    while offset + SECTION_DESCRIPTOR_LEN <= file_len {
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut descriptor)?;
+
+        let kind_end = descriptor[..16].iter().position(|&b| b == 0).unwrap_or(16);
+        let kind = String::from_utf8_lossy(&descriptor[..kind_end]).into_owned();
+        let next = u64::from_le_bytes(descriptor[16..24].try_into().unwrap());
+        let size = u64::from_le_bytes(descriptor[24..32].try_into().unwrap());
+        let last = kind == "done" || kind == "next" || next <= offset;
+
+        sections.push(EwfSection { kind, offset, size });
+        if last {
+            break;
+        }
+        offset = next;
+    }
+
+    Ok(sections)
+}
+
+/// Read `len` bytes of section data
+pub fn read_section_data(path: &Path, section: &EwfSection, len: usize) -> Result<Vec<u8>> {
+    let mut file = File::open(path)?;
+    file.seek(SeekFrom::Start(section.data_offset()))?;
+    let mut data = vec![0u8; len];
+    file.read_exact(&mut data)?;
+    Ok(data)
+}
+
+/// Media hashes stored in the `hash` and `digest` sections of an image
+///
+/// The sections are usually in the last segment; all segments are searched.
+/// Hashes that are all zeros were not computed and are left out.
+pub fn stored_hashes(first: &Path) -> Result<StoredHashes> {
+    let mut hashes = StoredHashes::default();
+
+    for path in segment_paths(first) {
+        for section in read_sections(&path)? {
+            match section.kind.as_str() {
+                "hash" => {
+                    let data = read_section_data(&path, &section, 16)?;
+                    hashes.md5 = hashes.md5.or(stored_digest(&data));
+                }
+                "digest" => {
+                    let data = read_section_data(&path, &section, 36)?;
+                    hashes.md5 = stored_digest(&data[..16]).or(hashes.md5);
+                    hashes.sha1 = stored_digest(&data[16..36]).or(hashes.sha1);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Ok(hashes)
+}
+
+/// Decompress a `header`/`header2` section into its `key -> value` table
+///
+/// The text has a category line ("main"), a tab separated line of keys and
+/// a line of values below it.
+fn header_values(data: &[u8], utf16: bool) -> Option<HashMap<String, String>> {
+    let mut decompressed = Vec::new();
+    ZlibDecoder::new(data).read_to_end(&mut decompressed).ok()?;
+
+    let text = if utf16 {
+        let units: Vec<u16> = decompressed
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        String::from_utf16_lossy(&units)
+    } else {
+        decompressed.iter().map(|&b| b as char).collect()
+    };
+    let text = text.trim_start_matches('\u{feff}');
+
+    let lines: Vec<&str> = text.lines().map(|line| line.trim_end_matches('\r')).collect();
+    let main = lines.iter().position(|line| *line == "main")?;
+    let keys = lines.get(main + 1)?.split('\t');
+    let values = lines.get(main + 2)?.split('\t');
+    Some(
+        keys.zip(values)
+            .map(|(key, value)| (key.to_string(), value.trim().to_string()))
+            .collect(),
+    )
+}
+
+/// Format a header date: a Unix timestamp or "year month day hour minute second"
+fn format_header_date(value: &str) -> String {
+    if let Ok(seconds) = value.parse::<i64>() {
+        return format_timestamp(seconds);
+    }
+    let parts: Vec<u32> = value
+        .split_whitespace()
+        .filter_map(|part| part.parse().ok())
+        .collect();
+    match parts[..] {
+        [year, month, day, hour, minute, second] => format!(
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            year, month, day, hour, minute, second
+        ),
+        _ => value.to_string(),
+    }
+}
+
+fn stored_digest(data: &[u8]) -> Option<String> {
+    data.iter().any(|&b| b != 0).then(|| hex::encode(data))
+}
+
+fn not_ewf(path: &Path) -> BingrepError {
+    BingrepError::InvalidArgument(format!(
+        "{} is not an EWF (E01) segment file",
+        path.display()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    /// Minimal segment file with the given sections and their data
+    fn segment_file(sections: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let mut file = EVF_SIGNATURE.to_vec();
+        file.extend_from_slice(&[1, 1, 0, 0, 0]);
+
+        for (kind, data) in sections {
+            let offset = file.len() as u64;
+            let size = SECTION_DESCRIPTOR_LEN + data.len() as u64;
+            let next = if *kind == "done" { offset } else { offset + size };
+
+            let mut descriptor = vec![0u8; SECTION_DESCRIPTOR_LEN as usize];
+            descriptor[..kind.len()].copy_from_slice(kind.as_bytes());
+            descriptor[16..24].copy_from_slice(&next.to_le_bytes());
+            descriptor[24..32].copy_from_slice(&size.to_le_bytes());
+            file.extend_from_slice(&descriptor);
+            file.extend_from_slice(data);
+        }
+        file
+    }
+
+    #[test]
+    fn test_segment_extensions() {
+        assert_eq!(segment_extension(2).as_deref(), Some("E02"));
+        assert_eq!(segment_extension(99).as_deref(), Some("E99"));
+        assert_eq!(segment_extension(100).as_deref(), Some("EAA"));
+        assert_eq!(segment_extension(127).as_deref(), Some("EBB"));
+    }
+
+    #[test]
+    fn test_stored_hashes_across_segments() {
+        let dir = tempfile::tempdir().unwrap();
+        let md5 = [0x11u8; 16];
+        let mut digest = vec![0x22u8; 16];
+        digest.extend_from_slice(&[0x33u8; 20]);
+        digest.extend_from_slice(&[0u8; 44]);
+
+        let first = dir.path().join("image.E01");
+        File::create(&first)
+            .unwrap()
+            .write_all(&segment_file(&[("header", vec![0; 8]), ("next", vec![])]))
+            .unwrap();
+        File::create(dir.path().join("image.E02"))
+            .unwrap()
+            .write_all(&segment_file(&[
+                ("hash", [&md5[..], &[0u8; 20]].concat()),
+                ("digest", digest),
+                ("done", vec![]),
+            ]))
+            .unwrap();
+
+        assert_eq!(segment_paths(&first).len(), 2);
+        let sections = read_sections(&dir.path().join("image.E02")).unwrap();
+        let kinds: Vec<&str> = sections.iter().map(|s| s.kind.as_str()).collect();
+        assert_eq!(kinds, vec!["hash", "digest", "done"]);
+
+        // The digest section takes precedence over the hash section
+        let hashes = stored_hashes(&first).unwrap();
+        assert_eq!(hashes.md5, Some("22".repeat(16)));
+        assert_eq!(hashes.sha1, Some("33".repeat(20)));
+
+        let computed = RegionHashes {
+            length: 512,
+            sha1: Some("33".repeat(20)),
+            sha256: Some("44".repeat(32)),
+            ..RegionHashes::default()
+        };
+        let checks = hashes.check(&computed);
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].algorithm, HashAlgorithm::Sha1);
+        assert!(checks[0].is_match());
+        let computed = RegionHashes {
+            md5: Some("00".repeat(16)),
+            ..computed
+        };
+        assert!(!hashes.check(&computed)[0].is_match());
+
+        let not_ewf = dir.path().join("raw.E01");
+        File::create(&not_ewf).unwrap().write_all(&[0u8; 64]).unwrap();
+        assert!(stored_hashes(&not_ewf).is_err());
+    }
+
+    fn zlib(data: &[u8]) -> Vec<u8> {
+        let mut encoder =
+            flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_read_info() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let header2 = "3\nmain\na\tc\tn\te\tt\tav\tov\tm\tu\tp\n\
+                       USB stick\tCASE-7\tEV01\tKim\t\t7.1\tWindows\t1700000000\t1700000100\t0\n\n";
+        let mut utf16 = vec![0xff, 0xfe];
+        for unit in header2.encode_utf16() {
+            utf16.extend_from_slice(&unit.to_le_bytes());
+        }
+        let header = "1\nmain\nc\tn\ta\te\tt\tm\tu\tp\tr\nOLD\t1\t\t\t\t2023 11 14 22 13 20\t\t0\tb\n";
+
+        let mut volume = vec![0u8; E01_VOLUME_LEN as usize];
+        volume[0] = 0x01;
+        volume[4..8].copy_from_slice(&16u32.to_le_bytes());
+        volume[8..12].copy_from_slice(&64u32.to_le_bytes());
+        volume[12..16].copy_from_slice(&512u32.to_le_bytes());
+        volume[16..24].copy_from_slice(&1000u64.to_le_bytes());
+        volume[52] = 2;
+
+        let first = dir.path().join("usb.E01");
+        File::create(&first)
+            .unwrap()
+            .write_all(&segment_file(&[
+                ("header2", zlib(&utf16)),
+                ("header", zlib(header.as_bytes())),
+                ("volume", volume),
+                ("hash", vec![0xab; 16]),
+                ("done", vec![]),
+            ]))
+            .unwrap();
+
+        let info = EwfInfo::read(&first).unwrap();
+        assert_eq!(info.segments.len(), 1);
+        let media = info.media.unwrap();
+        assert_eq!(media.media_type.as_deref(), Some("fixed"));
+        assert_eq!(media.media_size(), 512_000);
+        assert_eq!(media.chunk_size(), 32_768);
+        assert_eq!(media.compression.as_deref(), Some("best"));
+        assert_eq!(info.case.case_number.as_deref(), Some("CASE-7"));
+        assert_eq!(info.case.examiner.as_deref(), Some("Kim"));
+        assert_eq!(info.case.description.as_deref(), Some("USB stick"));
+        assert_eq!(info.case.notes, None);
+        assert_eq!(
+            info.case.acquisition_date.as_deref(),
+            Some("2023-11-14 22:13:20 UTC")
+        );
+        assert_eq!(info.stored_hashes.md5, Some("ab".repeat(16)));
+
+        // header sections store local dates as separate fields
+        assert_eq!(format_header_date("2023 11 14 22 13 20"), "2023-11-14 22:13:20");
+    }
+}
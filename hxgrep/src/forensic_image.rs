@@ -30,7 +30,7 @@ impl ForensicImageReader {
             )))?;
 
         // Try to create the exhume_body reader
-        let body = match std::panic::catch_unwind(|| {
+        let mut body = match std::panic::catch_unwind(|| {
             exhume_body::Body::new(path_str.to_string(), "auto")
         }) {
             Ok(body) => body,
@@ -42,8 +42,11 @@ impl ForensicImageReader {
             }
         };
 
-        // Get the size if possible
-        let size = 0; // TODO: Get actual size from exhume_body
+        // The media size is where the end of the image seeks to; 0 if unknown
+        let size = body
+            .seek(SeekFrom::End(0))
+            .and_then(|size| body.seek(SeekFrom::Start(0)).map(|_| size))
+            .unwrap_or(0);
 
         Ok(ForensicImageReader {
             body,
@@ -132,9 +135,9 @@ pub fn is_vmdk_file<P: AsRef<Path>>(path: P) -> bool {
 /// Get the format name for a forensic image file
 pub fn get_format_name<P: AsRef<Path>>(path: P) -> Option<&'static str> {
     let path = path.as_ref();
-    if is_e01_file(&path) {
+    if is_e01_file(path) {
         Some("E01/EWF")
-    } else if is_vmdk_file(&path) {
+    } else if is_vmdk_file(path) {
         Some("VMDK")
     } else {
         None
@@ -0,0 +1,294 @@
+//! Digest algorithms (MD5, SHA-1, SHA-256)
+//!
+//! Shared by block hashing and `--hash`. Whole-input digests are computed by
+//! [`HashingReader`] while a search reads its input, so hashing does not
+//! need a second pass; digests of match windows are [`RegionHashes`].
+
+use crate::cli::HashAlgorithm;
+use md5::Md5;
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::io::{self, Read, Seek, SeekFrom};
+
+/// Buffer size for bytes read only to complete a digest
+const GAP_BUFFER: usize = 1024 * 1024;
+
+impl HashAlgorithm {
+    /// Name used in output records and hash set headers
+    pub fn name(&self) -> &'static str {
+        match self {
+            HashAlgorithm::Md5 => "md5",
+            HashAlgorithm::Sha1 => "sha1",
+            HashAlgorithm::Sha256 => "sha256",
+        }
+    }
+
+    /// Length of a digest in bytes
+    pub fn digest_len(&self) -> usize {
+        match self {
+            HashAlgorithm::Md5 => 16,
+            HashAlgorithm::Sha1 => 20,
+            HashAlgorithm::Sha256 => 32,
+        }
+    }
+
+    /// Digest of `data`
+    pub fn digest(&self, data: &[u8]) -> Vec<u8> {
+        match self {
+            HashAlgorithm::Md5 => Md5::digest(data).to_vec(),
+            HashAlgorithm::Sha1 => Sha1::digest(data).to_vec(),
+            HashAlgorithm::Sha256 => Sha256::digest(data).to_vec(),
+        }
+    }
+}
+
+/// Digests of a region of the input
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegionHashes {
+    /// Number of bytes hashed
+    pub length: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub md5: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha1: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+}
+
+impl RegionHashes {
+    /// Digests of `data` for each of `algorithms`
+    pub fn of(algorithms: &[HashAlgorithm], data: &[u8]) -> Self {
+        let mut hasher = MultiHasher::new(algorithms);
+        hasher.update(data);
+        hasher.finalize()
+    }
+
+    /// Hex digest for `algorithm`, if it was computed
+    pub fn get(&self, algorithm: HashAlgorithm) -> Option<&str> {
+        match algorithm {
+            HashAlgorithm::Md5 => self.md5.as_deref(),
+            HashAlgorithm::Sha1 => self.sha1.as_deref(),
+            HashAlgorithm::Sha256 => self.sha256.as_deref(),
+        }
+    }
+
+    /// Computed digests in MD5, SHA-1, SHA-256 order
+    pub fn digests(&self) -> Vec<(HashAlgorithm, &str)> {
+        [
+            HashAlgorithm::Md5,
+            HashAlgorithm::Sha1,
+            HashAlgorithm::Sha256,
+        ]
+        .into_iter()
+        .filter_map(|algorithm| self.get(algorithm).map(|hash| (algorithm, hash)))
+        .collect()
+    }
+}
+
+/// Render digests as text lines below a match
+pub fn render_hashes(hashes: &RegionHashes) -> Vec<String> {
+    hashes
+        .digests()
+        .into_iter()
+        .map(|(algorithm, hash)| format!("    {:<8} {}", algorithm.name(), hash))
+        .collect()
+}
+
+/// Running digest of one algorithm
+enum DigestState {
+    Md5(Md5),
+    Sha1(Sha1),
+    Sha256(Sha256),
+}
+
+/// Running digests of several algorithms over the same bytes
+struct MultiHasher {
+    states: Vec<DigestState>,
+    length: u64,
+}
+
+impl MultiHasher {
+    fn new(algorithms: &[HashAlgorithm]) -> Self {
+        let mut states = Vec::new();
+        for algorithm in [
+            HashAlgorithm::Md5,
+            HashAlgorithm::Sha1,
+            HashAlgorithm::Sha256,
+        ] {
+            if !algorithms.contains(&algorithm) {
+                continue;
+            }
+            states.push(match algorithm {
+                HashAlgorithm::Md5 => DigestState::Md5(Md5::new()),
+                HashAlgorithm::Sha1 => DigestState::Sha1(Sha1::new()),
+                HashAlgorithm::Sha256 => DigestState::Sha256(Sha256::new()),
+            });
+        }
+        Self { states, length: 0 }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        for state in &mut self.states {
+            match state {
+                DigestState::Md5(hasher) => hasher.update(data),
+                DigestState::Sha1(hasher) => hasher.update(data),
+                DigestState::Sha256(hasher) => hasher.update(data),
+            }
+        }
+        self.length += data.len() as u64;
+    }
+
+    fn finalize(self) -> RegionHashes {
+        let mut hashes = RegionHashes {
+            length: self.length,
+            ..RegionHashes::default()
+        };
+        for state in self.states {
+            match state {
+                DigestState::Md5(hasher) => hashes.md5 = Some(hex::encode(hasher.finalize())),
+                DigestState::Sha1(hasher) => hashes.sha1 = Some(hex::encode(hasher.finalize())),
+                DigestState::Sha256(hasher) => hashes.sha256 = Some(hex::encode(hasher.finalize())),
+            }
+        }
+        hashes
+    }
+}
+
+/// Reader that hashes the whole input while it is being read
+///
+/// Bytes are hashed in order the first time a read reaches them; bytes read
+/// again (overlapping buffers) are not hashed twice. Regions the reader
+/// skips, such as the start before `-p` or the rest after `-n` stopped the
+/// search, are read once more only to complete the digest.
+pub struct HashingReader<R> {
+    inner: R,
+    hasher: Option<MultiHasher>,
+    position: u64,
+    hashed_to: u64,
+}
+
+impl<R: Read + Seek> HashingReader<R> {
+    /// Wrap `inner`, hashing with `algorithms` (none: plain pass-through)
+    pub fn new(mut inner: R, algorithms: &[HashAlgorithm]) -> io::Result<Self> {
+        let position = inner.stream_position()?;
+        Ok(Self {
+            inner,
+            hasher: (!algorithms.is_empty()).then(|| MultiHasher::new(algorithms)),
+            position,
+            hashed_to: 0,
+        })
+    }
+
+    /// Hash the rest of the input and return the digests of all of it
+    ///
+    /// Returns `None` when no algorithm was selected.
+    pub fn finish(&mut self) -> io::Result<Option<RegionHashes>> {
+        if self.hasher.is_none() {
+            return Ok(None);
+        }
+        let end = self.inner.seek(SeekFrom::End(0))?;
+        self.inner.seek(SeekFrom::Start(self.position))?;
+        self.hash_up_to(end)?;
+        Ok(self.hasher.take().map(MultiHasher::finalize))
+    }
+
+    /// Read and hash the bytes between the hashed prefix and `end`
+    fn hash_up_to(&mut self, end: u64) -> io::Result<()> {
+        let Some(hasher) = self.hasher.as_mut() else {
+            return Ok(());
+        };
+        if self.hashed_to >= end {
+            return Ok(());
+        }
+
+        self.inner.seek(SeekFrom::Start(self.hashed_to))?;
+        let mut buffer = vec![0u8; GAP_BUFFER.min((end - self.hashed_to) as usize)];
+        while self.hashed_to < end {
+            let len = ((end - self.hashed_to) as usize).min(buffer.len());
+            let bytes_read = self.inner.read(&mut buffer[..len])?;
+            if bytes_read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..bytes_read]);
+            self.hashed_to += bytes_read as u64;
+        }
+        self.inner.seek(SeekFrom::Start(self.position))?;
+        Ok(())
+    }
+}
+
+impl<R: Read + Seek> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.position > self.hashed_to {
+            self.hash_up_to(self.position)?;
+        }
+
+        let bytes_read = self.inner.read(buf)?;
+        let end = self.position + bytes_read as u64;
+        if let Some(hasher) = self.hasher.as_mut() {
+            if self.position <= self.hashed_to && end > self.hashed_to {
+                let start = (self.hashed_to - self.position) as usize;
+                hasher.update(&buf[start..bytes_read]);
+                self.hashed_to = end;
+            }
+        }
+        self.position = end;
+        Ok(bytes_read)
+    }
+}
+
+impl<R: Read + Seek> Seek for HashingReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.position = self.inner.seek(pos)?;
+        Ok(self.position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_digests() {
+        assert_eq!(
+            hex::encode(HashAlgorithm::Md5.digest(b"abc")),
+            "900150983cd24fb0d6963f7d28e17f72"
+        );
+        assert_eq!(
+            hex::encode(HashAlgorithm::Sha1.digest(b"abc")),
+            "a9993e364706816aba3e25717850c26c9cd0d89d"
+        );
+        assert_eq!(
+            hex::encode(HashAlgorithm::Sha256.digest(b"abc")),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            HashAlgorithm::Sha1.digest(b"").len(),
+            HashAlgorithm::Sha1.digest_len()
+        );
+    }
+
+    #[test]
+    fn test_hashing_reader_hashes_each_byte_once() {
+        let data: Vec<u8> = (0..10_000u32).map(|i| (i % 251) as u8).collect();
+        let algorithms = [HashAlgorithm::Sha256, HashAlgorithm::Md5];
+        let expected = RegionHashes::of(&algorithms, &data);
+        assert_eq!(expected.length, 10_000);
+        assert!(expected.sha1.is_none());
+
+        // Overlapping reads, a skipped start and an early stop
+        let mut reader = HashingReader::new(io::Cursor::new(data.clone()), &algorithms).unwrap();
+        let mut buffer = [0u8; 3000];
+        reader.seek(SeekFrom::Start(1000)).unwrap();
+        reader.read_exact(&mut buffer).unwrap();
+        reader.seek(SeekFrom::Start(2500)).unwrap();
+        reader.read_exact(&mut buffer).unwrap();
+        assert_eq!(&buffer[..], &data[2500..5500]);
+        assert_eq!(reader.finish().unwrap(), Some(expected));
+
+        let mut plain = HashingReader::new(io::Cursor::new(data), &[]).unwrap();
+        plain.read_exact(&mut buffer).unwrap();
+        assert_eq!(plain.finish().unwrap(), None);
+    }
+}
@@ -0,0 +1,477 @@
+//! Self-contained HTML report of search results (`report` subcommand)
+//!
+//! The report is rendered from the same [`BinaryMatch`] records as the JSON
+//! and CSV output. Styles are inlined and the hex/ASCII context of each hit
+//! is a `<details>` element, so the file opens in any browser without
+//! scripts or network access.
+
+use crate::ewf::HashCheck;
+use crate::hashing::RegionHashes;
+use crate::image_info::ImageInfo;
+use crate::structured_output::BinaryMatch;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+
+/// Bytes per line of the context dump
+const LINE_BYTES: u64 = 16;
+
+/// Matched bytes shown in the context of a long match
+const MAX_CONTEXT_MATCH: u64 = 256;
+
+/// Matched bytes shown in the data column of the hit tables
+const MAX_DATA_BYTES: usize = 32;
+
+const STYLE: &str = "\
+body{font-family:-apple-system,'Segoe UI',Helvetica,Arial,sans-serif;margin:2em;color:#1f2328}
+h1{font-size:1.6em;border-bottom:2px solid #d0d7de;padding-bottom:.3em}
+h2{font-size:1.25em;margin-top:2em}
+table{border-collapse:collapse;margin:.5em 0;font-size:.9em}
+th,td{border:1px solid #d0d7de;padding:.3em .6em;text-align:left;vertical-align:top}
+th{background:#f6f8fa}
+table.fields th{width:11em}
+code,pre{font-family:Consolas,'DejaVu Sans Mono',monospace;font-size:.95em}
+pre{margin:.4em 0;line-height:1.35}
+td.num{text-align:right}
+.dec{color:#656d76}
+.ok{color:#1a7f37;font-weight:bold}
+.bad{color:#cf222e;font-weight:bold}
+.note{color:#656d76}
+mark{background:#ffd33d;color:inherit}
+summary{cursor:pointer;color:#0969da}
+@media print{details{display:block}summary{display:none}}
+";
+
+/// Bytes around a hit, starting at a line boundary
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HitContext {
+    pub offset: u64,
+    pub data: Vec<u8>,
+}
+
+impl HitContext {
+    /// Read `context` bytes before and after the match `m` from `reader`
+    ///
+    /// The range is widened to whole lines; of a long match only the first
+    /// [`MAX_CONTEXT_MATCH`] bytes are included.
+    pub fn read<R: Read + Seek>(reader: &mut R, m: &BinaryMatch, context: u64) -> io::Result<Self> {
+        let start = m.offset.saturating_sub(context) / LINE_BYTES * LINE_BYTES;
+        let end = m.offset + (m.length as u64).min(MAX_CONTEXT_MATCH) + context;
+        let end = end.div_ceil(LINE_BYTES) * LINE_BYTES;
+
+        reader.seek(SeekFrom::Start(start))?;
+        let mut data = Vec::new();
+        reader.take(end - start).read_to_end(&mut data)?;
+        Ok(Self {
+            offset: start,
+            data,
+        })
+    }
+}
+
+/// A match of one of the report's patterns
+#[derive(Debug, Clone)]
+pub struct ReportHit {
+    /// Index into [`HtmlReport::patterns`]
+    pub pattern: usize,
+    pub record: BinaryMatch,
+    pub context: HitContext,
+}
+
+/// An input that was searched
+#[derive(Debug, Clone)]
+pub struct ReportInput {
+    pub path: String,
+    pub size: u64,
+    pub hashes: Option<RegionHashes>,
+    /// Comparison with the hashes stored in an E01 image
+    pub hash_checks: Vec<HashCheck>,
+    /// Acquisition metadata of a forensic image
+    pub image: Option<ImageInfo>,
+}
+
+/// Search results of one or more patterns over one or more inputs
+#[derive(Debug, Clone)]
+pub struct HtmlReport {
+    pub title: String,
+    pub case_number: Option<String>,
+    pub examiner: Option<String>,
+    /// Time the report was generated (RFC 3339)
+    pub generated: String,
+    pub command_line: String,
+    pub patterns: Vec<String>,
+    pub inputs: Vec<ReportInput>,
+    /// Hits in input order, each input in offset order
+    pub hits: Vec<ReportHit>,
+    /// Hits kept per pattern and input (0: unlimited)
+    pub limit: usize,
+}
+
+impl HtmlReport {
+    /// Write the report as one HTML document
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(self.render().as_bytes())
+    }
+
+    /// Render the report as one HTML document
+    pub fn render(&self) -> String {
+        let mut html = String::new();
+        html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+        html.push_str(&format!("<title>{}</title>\n", escape(&self.title)));
+        html.push_str(&format!("<style>\n{}</style>\n</head>\n<body>\n", STYLE));
+        html.push_str(&format!("<h1>{}</h1>\n", escape(&self.title)));
+
+        self.render_case(&mut html);
+        self.render_inputs(&mut html);
+        self.render_patterns(&mut html);
+        for pattern in 0..self.patterns.len() {
+            self.render_hits(&mut html, pattern);
+        }
+
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+
+    fn render_case(&self, html: &mut String) {
+        html.push_str("<h2>Case</h2>\n<table class=\"fields\">\n");
+        let fields = [
+            ("Case number", self.case_number.clone()),
+            ("Examiner", self.examiner.clone()),
+            ("Generated", Some(self.generated.clone())),
+            (
+                "Tool",
+                Some(format!("hxgrep {}", env!("CARGO_PKG_VERSION"))),
+            ),
+        ];
+        for (label, value) in fields {
+            if let Some(value) = value {
+                html.push_str(&format!(
+                    "<tr><th>{}</th><td>{}</td></tr>\n",
+                    label,
+                    escape(&value)
+                ));
+            }
+        }
+        html.push_str(&format!(
+            "<tr><th>Command line</th><td><code>{}</code></td></tr>\n</table>\n",
+            escape(&self.command_line)
+        ));
+    }
+
+    fn render_inputs(&self, html: &mut String) {
+        html.push_str("<h2>Inputs</h2>\n<table>\n");
+        html.push_str(
+            "<tr><th>#</th><th>Input</th><th>Size (bytes)</th><th>Hits</th><th>Hashes</th></tr>\n",
+        );
+        for (index, input) in self.inputs.iter().enumerate() {
+            let hits = self
+                .hits
+                .iter()
+                .filter(|hit| hit.record.file_path == input.path)
+                .count();
+            html.push_str(&format!(
+                "<tr><td class=\"num\">{}</td><td><code>{}</code></td><td class=\"num\">{}</td><td class=\"num\">{}</td><td>{}</td></tr>\n",
+                index + 1,
+                escape(&input.path),
+                input.size,
+                hits,
+                render_input_hashes(input)
+            ));
+        }
+        html.push_str("</table>\n");
+
+        for input in &self.inputs {
+            let Some(image) = &input.image else {
+                continue;
+            };
+            html.push_str(&format!(
+                "<details>\n<summary>Image metadata: {}</summary>\n<table class=\"fields\">\n",
+                escape(&input.path)
+            ));
+            for (label, value) in image.fields() {
+                html.push_str(&format!(
+                    "<tr><th>{}</th><td>{}</td></tr>\n",
+                    escape(&label),
+                    escape(&value)
+                ));
+            }
+            html.push_str("</table>\n</details>\n");
+        }
+    }
+
+    fn render_patterns(&self, html: &mut String) {
+        html.push_str(
+            "<h2>Patterns</h2>\n<table>\n<tr><th>#</th><th>Pattern</th><th>Hits</th></tr>\n",
+        );
+        for (index, pattern) in self.patterns.iter().enumerate() {
+            let hits = self.hits.iter().filter(|hit| hit.pattern == index).count();
+            let note = if self.limited(index) {
+                format!(
+                    " <span class=\"note\">(first {} per input)</span>",
+                    self.limit
+                )
+            } else {
+                String::new()
+            };
+            html.push_str(&format!(
+                "<tr><td class=\"num\"><a href=\"#pattern-{0}\">{0}</a></td><td><code>{1}</code></td><td class=\"num\">{2}{3}</td></tr>\n",
+                index + 1,
+                escape(pattern),
+                hits,
+                note
+            ));
+        }
+        html.push_str("</table>\n");
+    }
+
+    fn render_hits(&self, html: &mut String, pattern: usize) {
+        html.push_str(&format!(
+            "<h2 id=\"pattern-{}\">Pattern {}: <code>{}</code></h2>\n",
+            pattern + 1,
+            pattern + 1,
+            escape(&self.patterns[pattern])
+        ));
+        let hits: Vec<_> = self
+            .hits
+            .iter()
+            .filter(|hit| hit.pattern == pattern)
+            .collect();
+        if hits.is_empty() {
+            html.push_str("<p class=\"note\">No hits.</p>\n");
+            return;
+        }
+
+        html.push_str("<table>\n<tr><th>#</th>");
+        if self.inputs.len() > 1 {
+            html.push_str("<th>Input</th>");
+        }
+        html.push_str("<th>Offset</th><th>Length</th><th>Data</th><th>Context</th></tr>\n");
+        for (index, hit) in hits.iter().enumerate() {
+            let m = &hit.record;
+            html.push_str(&format!("<tr><td class=\"num\">{}</td>", index + 1));
+            if self.inputs.len() > 1 {
+                html.push_str(&format!("<td><code>{}</code></td>", escape(&m.file_path)));
+            }
+            html.push_str(&format!(
+                "<td><code>0x{:X}</code><br><span class=\"dec\">{}</span></td><td class=\"num\">{}</td><td>{}</td>",
+                m.offset,
+                m.offset,
+                m.length,
+                render_data(m)
+            ));
+            html.push_str(&format!(
+                "<td><details><summary>hex/ASCII</summary><pre>{}</pre></details></td></tr>\n",
+                render_context(&hit.context, m.offset..m.offset + m.length as u64)
+            ));
+        }
+        html.push_str("</table>\n");
+    }
+
+    /// Whether the hits of `pattern` were cut off at the limit in some input
+    fn limited(&self, pattern: usize) -> bool {
+        self.limit > 0
+            && self.inputs.iter().any(|input| {
+                let hits = self
+                    .hits
+                    .iter()
+                    .filter(|hit| hit.pattern == pattern && hit.record.file_path == input.path);
+                hits.count() >= self.limit
+            })
+    }
+}
+
+/// Digests of an input with the result of the E01 comparison
+fn render_input_hashes(input: &ReportInput) -> String {
+    let Some(hashes) = &input.hashes else {
+        return String::new();
+    };
+    let lines: Vec<String> = hashes
+        .digests()
+        .into_iter()
+        .map(|(algorithm, hash)| {
+            let check = input
+                .hash_checks
+                .iter()
+                .find(|check| check.algorithm == algorithm);
+            let status = match check {
+                Some(check) if check.is_match() => {
+                    " <span class=\"ok\">matches the hash stored in the image</span>".to_string()
+                }
+                Some(check) => format!(
+                    " <span class=\"bad\">MISMATCH: the image stores <code>{}</code></span>",
+                    escape(&check.stored)
+                ),
+                None => String::new(),
+            };
+            format!(
+                "{} <code>{}</code>{}",
+                algorithm.name().to_uppercase(),
+                hash,
+                status
+            )
+        })
+        .collect();
+    lines.join("<br>")
+}
+
+/// Matched bytes as hex with their ASCII form, shortened for the table
+fn render_data(m: &BinaryMatch) -> String {
+    let bytes = m.bytes();
+    let shown: Vec<String> = bytes
+        .iter()
+        .take(MAX_DATA_BYTES)
+        .map(|b| format!("{:02X}", b))
+        .collect();
+    let mut html = format!("<code>{}", shown.join(" "));
+    if bytes.len() > MAX_DATA_BYTES {
+        html.push_str(" &hellip;");
+    }
+    html.push_str("</code>");
+    if let Some(ascii) = &m.ascii_data {
+        html.push_str(&format!("<br><code>{}</code>", escape(ascii)));
+    }
+    html
+}
+
+/// Dump the context as `offset  hex  ascii` lines with the match marked
+fn render_context(context: &HitContext, highlight: Range<u64>) -> String {
+    let mut html = String::new();
+    for (line, bytes) in context.data.chunks(LINE_BYTES as usize).enumerate() {
+        let line_offset = context.offset + line as u64 * LINE_BYTES;
+        let cell = |index: usize| highlight.contains(&(line_offset + index as u64));
+
+        let hex: Vec<_> = bytes
+            .iter()
+            .enumerate()
+            .map(|(index, b)| {
+                let separator = match index {
+                    0 => "",
+                    8 => "  ",
+                    _ => " ",
+                };
+                (separator, format!("{:02X}", b), cell(index))
+            })
+            .collect();
+        let ascii: Vec<_> = bytes
+            .iter()
+            .enumerate()
+            .map(|(index, &b)| {
+                let c = if b.is_ascii_graphic() || b == b' ' {
+                    b as char
+                } else {
+                    '.'
+                };
+                ("", escape(&c.to_string()), cell(index))
+            })
+            .collect();
+
+        html.push_str(&format!("{:08X}  ", line_offset));
+        push_cells(&mut html, &hex);
+        // Pad a short last line so the ASCII column stays aligned
+        let missing = LINE_BYTES as usize - bytes.len();
+        html.push_str(&" ".repeat(missing * 3 + usize::from(bytes.len() <= 8)));
+        html.push_str("  ");
+        push_cells(&mut html, &ascii);
+        html.push('\n');
+    }
+    html
+}
+
+/// Append `(separator, text, highlighted)` cells, wrapping highlighted runs in `<mark>`
+fn push_cells(html: &mut String, cells: &[(&str, String, bool)]) {
+    let mut marked = false;
+    for (separator, text, highlighted) in cells {
+        if marked && !highlighted {
+            html.push_str("</mark>");
+            marked = false;
+        }
+        html.push_str(separator);
+        if *highlighted && !marked {
+            html.push_str("<mark>");
+            marked = true;
+        }
+        html.push_str(text);
+    }
+    if marked {
+        html.push_str("</mark>");
+    }
+}
+
+/// Escape text for HTML element content and attribute values
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_context_marks_the_match() {
+        let mut data = vec![b'.'; 64];
+        data[20..25].copy_from_slice(b"FILE0");
+        let m = BinaryMatch::new("a.bin".to_string(), 20, "46 49 4C 45 30".to_string(), 5);
+
+        let context = HitContext::read(&mut Cursor::new(&data), &m, 8).unwrap();
+        assert_eq!(context.offset, 0);
+        assert_eq!(context.data.len(), 48);
+
+        let lines: Vec<_> = render_context(&context, 20..25)
+            .lines()
+            .map(str::to_string)
+            .collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[1],
+            "00000010  2E 2E 2E 2E <mark>46 49 4C 45  30</mark> 2E 2E 2E 2E 2E 2E 2E  ....<mark>FILE0</mark>......."
+        );
+    }
+
+    #[test]
+    fn test_render_escapes_and_stays_offline() {
+        let m = BinaryMatch::new("<evil>.bin".to_string(), 3, "3C 61 3E".to_string(), 3);
+        let report = HtmlReport {
+            title: "Case <1>".to_string(),
+            case_number: Some("2024-017".to_string()),
+            examiner: None,
+            generated: "2024-03-05T09:30:00.000Z".to_string(),
+            command_line: "hxgrep report <evil>.bin -e <a>".to_string(),
+            patterns: vec!["<a>".to_string(), "unused".to_string()],
+            inputs: vec![ReportInput {
+                path: "<evil>.bin".to_string(),
+                size: 6,
+                hashes: None,
+                hash_checks: Vec::new(),
+                image: None,
+            }],
+            hits: vec![ReportHit {
+                pattern: 0,
+                context: HitContext {
+                    offset: 0,
+                    data: b"xyz<a>".to_vec(),
+                },
+                record: m,
+            }],
+            limit: 1,
+        };
+
+        let html = report.render();
+        assert!(html.contains("<h1>Case &lt;1&gt;</h1>"));
+        assert!(html.contains("<code>&lt;evil&gt;.bin</code>"));
+        assert!(html.contains("xyz<mark>&lt;a&gt;</mark>"));
+        assert!(html.contains("(first 1 per input)"));
+        assert!(html.contains("<p class=\"note\">No hits.</p>"));
+        assert!(!html.contains("<script") && !html.contains("http"));
+    }
+}
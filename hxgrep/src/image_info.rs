@@ -0,0 +1,170 @@
+//! Acquisition metadata of forensic images (`info` subcommand)
+//!
+//! Combines the EWF section metadata of [`crate::ewf`] and the VMDK
+//! descriptor of [`crate::vmdk`] into one record that is printed as text or
+//! serialized as JSON.
+
+use crate::error::{BingrepError, Result};
+use crate::ewf::EwfInfo;
+use crate::forensic_image::{is_e01_file, is_vmdk_file};
+use crate::vmdk::VmdkInfo;
+use serde::Serialize;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// Width of the label column in text output
+const LABEL_WIDTH: usize = 20;
+
+/// Metadata of a forensic image, by format
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "format", rename_all = "lowercase")]
+pub enum ImageInfo {
+    Ewf(Box<EwfInfo>),
+    Vmdk(VmdkInfo),
+}
+
+impl ImageInfo {
+    /// Read the metadata of the image at `path`
+    ///
+    /// The format is chosen by extension, or by signature for images with
+    /// other names.
+    pub fn read(path: &str) -> Result<Self> {
+        let path = Path::new(path);
+        if is_e01_file(path) {
+            return Ok(ImageInfo::Ewf(Box::new(EwfInfo::read(path)?)));
+        }
+        if is_vmdk_file(path) {
+            return Ok(ImageInfo::Vmdk(VmdkInfo::read(path)?));
+        }
+
+        let mut signature = Vec::new();
+        File::open(path)?.take(21).read_to_end(&mut signature)?;
+        if signature.starts_with(b"EVF\x09\x0d\x0a\xff\x00") {
+            Ok(ImageInfo::Ewf(Box::new(EwfInfo::read(path)?)))
+        } else if signature.starts_with(b"KDMV") || signature.starts_with(b"# Disk DescriptorFile") {
+            Ok(ImageInfo::Vmdk(VmdkInfo::read(path)?))
+        } else {
+            Err(BingrepError::InvalidArgument(format!(
+                "{} is not an EWF (E01) or VMDK image",
+                path.display()
+            )))
+        }
+    }
+
+    /// Render the metadata as `label: value` text lines
+    pub fn render(&self) -> Vec<String> {
+        self.fields()
+            .into_iter()
+            .map(|(label, value)| {
+                // Continuation lines (segments, extents) have no label
+                let label = if label.is_empty() { label } else { format!("{}:", label) };
+                format!("{:<width$}{}", label, value, width = LABEL_WIDTH)
+            })
+            .collect()
+    }
+
+    /// The metadata as `(label, value)` pairs, in display order
+    ///
+    /// Segments and extents follow their count with an empty label.
+    pub fn fields(&self) -> Vec<(String, String)> {
+        let mut fields = Vec::new();
+        let mut field = |label: &str, value: Option<String>| {
+            if let Some(value) = value {
+                fields.push((label.to_string(), value));
+            }
+        };
+
+        match self {
+            ImageInfo::Ewf(info) => {
+                field("Format", Some("EWF (E01)".to_string()));
+                field("Segments", Some(info.segments.len().to_string()));
+                for segment in &info.segments {
+                    field("", Some(format!("{} ({} bytes)", segment.path, segment.size)));
+                }
+                if let Some(media) = &info.media {
+                    field("Media type", media.media_type.clone());
+                    field("Media size", Some(format!("{} bytes", media.media_size())));
+                    field("Sector size", Some(format!("{} bytes", media.sector_size)));
+                    field("Sectors", Some(media.sector_count.to_string()));
+                    field(
+                        "Chunk size",
+                        Some(format!("{} bytes ({} sectors)", media.chunk_size(), media.sectors_per_chunk)),
+                    );
+                    field("Chunks", Some(media.chunk_count.to_string()));
+                }
+                let compression = info.media.as_ref().and_then(|media| media.compression.clone());
+                field("Compression", compression.or_else(|| info.case.compression.clone()));
+
+                let case = &info.case;
+                field("Case number", case.case_number.clone());
+                field("Evidence number", case.evidence_number.clone());
+                field("Description", case.description.clone());
+                field("Examiner", case.examiner.clone());
+                field("Notes", case.notes.clone());
+                field("Acquired", case.acquisition_date.clone());
+                field("System date", case.system_date.clone());
+                field("Software", case.acquisition_software.clone());
+                field("OS", case.acquisition_os.clone());
+                field("Model", case.model.clone());
+                field("Serial number", case.serial_number.clone());
+
+                field("MD5", info.stored_hashes.md5.clone());
+                field("SHA1", info.stored_hashes.sha1.clone());
+                if info.stored_hashes.md5.is_none() && info.stored_hashes.sha1.is_none() {
+                    field("Stored hashes", Some("none".to_string()));
+                }
+            }
+            ImageInfo::Vmdk(info) => {
+                field("Format", Some("VMDK".to_string()));
+                field("Media size", Some(format!("{} bytes", info.media_size())));
+                if let Some(header) = &info.sparse_header {
+                    field("Sparse version", Some(header.version.to_string()));
+                    field("Capacity", Some(format!("{} sectors", header.capacity_sectors)));
+                    field("Grain size", Some(format!("{} sectors", header.grain_size_sectors)));
+                    field("Compression", Some(header.compression.clone()));
+                }
+                field("Version", info.version.clone());
+                field("Create type", info.create_type.clone());
+                field("CID", info.cid.clone());
+                field("Parent CID", info.parent_cid.clone());
+                field("Extents", Some(info.extents.len().to_string()));
+                for extent in &info.extents {
+                    let mut line = format!("{} {} {}", extent.access, extent.sectors, extent.kind);
+                    if let Some(file) = &extent.file {
+                        line.push_str(&format!(" \"{}\"", file));
+                    }
+                    if let Some(offset) = extent.offset {
+                        line.push_str(&format!(" {}", offset));
+                    }
+                    field("", Some(line));
+                }
+                for (key, value) in &info.disk_database {
+                    field(&format!("ddb.{}", key), Some(value.clone()));
+                }
+            }
+        }
+
+        fields
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_vmdk_and_json_tag() {
+        let info = ImageInfo::Vmdk(VmdkInfo::parse(
+            "# Disk DescriptorFile\ncreateType=\"monolithicFlat\"\nRW 2048 FLAT \"disk-flat.vmdk\" 0\n",
+        ));
+        let lines = info.render();
+        assert_eq!(lines[0], "Format:             VMDK");
+        assert!(lines.contains(&"Media size:         1048576 bytes".to_string()));
+        assert!(lines.contains(&"                    RW 2048 FLAT \"disk-flat.vmdk\" 0".to_string()));
+
+        let json = serde_json::to_value(&info).unwrap();
+        assert_eq!(json["format"], "vmdk");
+        assert_eq!(json["extents"][0]["kind"], "FLAT");
+    }
+}
@@ -0,0 +1,1059 @@
+//! Persistent n-gram index for repeated searches (`index`, `--index`)
+//!
+//! The index splits an input into fixed-size blocks and records, for every
+//! n-gram (2 to 4 bytes), the blocks it occurs in. A search with `--index`
+//! extracts the literal parts a match must contain from the regex, intersects
+//! the posting lists of their n-grams to find candidate blocks, and only
+//! verifies those regions with the regular regex engine. Patterns without a
+//! usable literal fall back to a full scan.
+//!
+//! Grams are hashed into a number of buckets that grows with the input, so
+//! near-random data does not produce one posting list per distinct gram.
+//! Buckets present in more than half of the blocks, and the longest lists
+//! beyond a budget of an eighth of the input size, are left out as too
+//! common; a lookup of such a bucket restricts nothing. This keeps the index
+//! at about a quarter of the input at most. Collisions and left out buckets
+//! only add candidates, which the regex verification removes again.
+//!
+//! # File layout
+//!
+//! * 8 bytes magic `HXGRPIDX`
+//! * u32 (little endian) length of the JSON [`IndexHeader`], then the header
+//! * Bucket directory: per bucket a u64 end offset of its posting list
+//!   (relative to the posting section; the list starts at the previous
+//!   entry's end), with the top bit set for buckets left out as too common
+//! * Posting section: per bucket, increasing block ids as LEB128 varint deltas
+//!
+//! While the index is built, (bucket, block) pairs are spilled to sorted run
+//! files next to the output and merged, so memory use does not grow with the
+//! input.
+//!
+//! The header identifies the input (size, modification time and a SHA-256
+//! over samples of its content), so an index is refused once the input
+//! changed instead of silently missing matches.
+
+use crate::checkpoint::InputIdentity;
+use crate::error::{BingrepError, Result};
+use crate::parallel::read_chunk;
+use crate::progress;
+use rayon::prelude::*;
+use regex::bytes::Regex;
+use regex_syntax::hir::literal::{ExtractKind, Extractor};
+use regex_syntax::hir::{Hir, HirKind};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+/// Magic bytes at the start of every index file
+const INDEX_MAGIC: &[u8; 8] = b"HXGRPIDX";
+
+/// Version of the index file format
+pub const INDEX_VERSION: u32 = 2;
+
+/// Size of one bucket directory entry in bytes
+const DIRECTORY_ENTRY_LEN: u64 = 8;
+
+/// Directory flag of buckets left out as too common
+const COMMON_FLAG: u64 = 1 << 63;
+
+/// Input bytes per gram bucket
+const BYTES_PER_BUCKET: u64 = 64;
+
+/// Fewest gram buckets of an index
+const MIN_BUCKETS: u64 = 1 << 10;
+
+/// Most gram buckets of an index
+const MAX_BUCKETS: u64 = 1 << 22;
+
+/// The posting section may take up to this fraction of the input size
+const POSTINGS_SHARE: u64 = 8;
+
+/// (bucket, block) pairs kept in memory before they are spilled to a run
+const RUN_PAIRS: usize = 1 << 22;
+
+/// Bytes hashed at each sample point of the input fingerprint
+const FINGERPRINT_SAMPLE: u64 = 64 * 1024;
+
+/// Evenly spaced sample points of the fingerprint, including start and end
+const FINGERPRINT_POINTS: u64 = 18;
+
+/// Most alternative literals a pattern may need before the index is skipped
+const MAX_ANCHOR_LITERALS: usize = 64;
+
+/// Header of an index file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexHeader {
+    pub version: u32,
+    /// Gram length in bytes
+    pub ngram: usize,
+    /// Block size in bytes
+    pub block_size: u64,
+    /// Identity of the indexed file
+    pub input: InputIdentity,
+    /// Size of the indexed data (the media size for forensic images)
+    pub input_size: u64,
+    /// SHA-256 over the input size and content samples, see [`fingerprint`]
+    pub fingerprint: String,
+    /// Number of blocks
+    pub blocks: u64,
+    /// Number of gram buckets (grams map to themselves if there are as
+    /// many buckets as possible grams)
+    pub buckets: u64,
+    /// Number of buckets with a posting list
+    pub grams: u64,
+    /// Number of buckets left out as too common
+    pub common: u64,
+}
+
+/// Posting list of one gram while the index is built
+#[derive(Debug, Default)]
+struct PostingList {
+    last: Option<u32>,
+    bytes: Vec<u8>,
+}
+
+impl PostingList {
+    /// Append a block id greater than all previous ones
+    fn push(&mut self, block: u32) {
+        let delta = block - self.last.map_or(0, |last| last);
+        write_varint(&mut self.bytes, u64::from(delta));
+        self.last = Some(block);
+    }
+}
+
+/// Builds index files
+#[derive(Debug, Clone)]
+pub struct IndexBuilder {
+    ngram: usize,
+    block_size: u64,
+}
+
+impl IndexBuilder {
+    /// Create a builder for `ngram`-byte grams over blocks of `block_size` bytes
+    pub fn new(ngram: usize, block_size: u64) -> Result<Self> {
+        if !(2..=4).contains(&ngram) {
+            return Err(BingrepError::InvalidArgument(format!(
+                "n-gram length must be between 2 and 4, got {}",
+                ngram
+            )));
+        }
+        if block_size < ngram as u64 {
+            return Err(BingrepError::InvalidArgument(format!(
+                "index block size must be at least {} bytes, got {}",
+                ngram, block_size
+            )));
+        }
+        Ok(Self { ngram, block_size })
+    }
+
+    /// Index all of `reader` and write the index to `out_path`
+    ///
+    /// Blocks are read in batches and their grams are collected in parallel
+    /// when `parallel` is set. The (bucket, block) pairs are spilled to
+    /// sorted runs next to `out_path` and merged into the posting lists.
+    pub fn build<R: Read + Seek, P: AsRef<Path>>(
+        &self,
+        reader: &mut R,
+        input: InputIdentity,
+        parallel: bool,
+        out_path: P,
+    ) -> Result<IndexHeader> {
+        let out_path = out_path.as_ref();
+        let input_size = reader.seek(SeekFrom::End(0))?;
+        let blocks = input_size.div_ceil(self.block_size);
+        if blocks > u64::from(u32::MAX) {
+            return Err(BingrepError::InvalidArgument(format!(
+                "{} blocks of {} bytes exceed the index limit; use a larger --block-size",
+                blocks, self.block_size
+            )));
+        }
+
+        let batch_size = if parallel {
+            rayon::current_num_threads().max(1) * 4
+        } else {
+            1
+        } as u64;
+        let lookahead = self.ngram - 1;
+        let buckets = bucket_count(self.ngram, input_size);
+        let mut runs = PairRuns::new(out_path);
+
+        for first in (0..blocks).step_by(batch_size as usize) {
+            let ids = first..(first + batch_size).min(blocks);
+            let mut batch = Vec::with_capacity(batch_size as usize);
+            for id in ids.clone() {
+                let len = self.block_size as usize + lookahead;
+                batch.push(read_chunk(reader, id * self.block_size, len)?);
+            }
+
+            let grams: Vec<Vec<u32>> = if parallel {
+                batch
+                    .par_iter()
+                    .map(|data| block_buckets(data, self.ngram, buckets))
+                    .collect()
+            } else {
+                batch
+                    .iter()
+                    .map(|data| block_buckets(data, self.ngram, buckets))
+                    .collect()
+            };
+            for (id, keys) in ids.zip(grams) {
+                for key in keys {
+                    runs.push(key, id as u32)?;
+                }
+            }
+
+            let batch_end = ((first + batch_size) * self.block_size).min(input_size);
+            progress::advance(batch_end - first * self.block_size);
+        }
+
+        let postings_path = temp_path(out_path, ".postings.tmp");
+        let result = (|| {
+            let mut lens = write_postings(&mut runs, &postings_path, buckets, blocks)?;
+            limit_postings(&mut lens, input_size / POSTINGS_SHARE);
+
+            let header = IndexHeader {
+                version: INDEX_VERSION,
+                ngram: self.ngram,
+                block_size: self.block_size,
+                input,
+                input_size,
+                fingerprint: fingerprint(reader, input_size)?,
+                blocks,
+                buckets,
+                grams: lens
+                    .iter()
+                    .filter(|&&len| len != 0 && len & COMMON_FLAG == 0)
+                    .count() as u64,
+                common: lens.iter().filter(|&&len| len & COMMON_FLAG != 0).count() as u64,
+            };
+            write_index(out_path, &header, &lens, &postings_path)?;
+            Ok(header)
+        })();
+        fs::remove_file(&postings_path).ok();
+        result
+    }
+}
+
+/// Number of gram buckets for an input of `input_size` bytes
+///
+/// One bucket per [`BYTES_PER_BUCKET`] input bytes, rounded down to a power
+/// of two, bounds the directory to an eighth of the input.
+fn bucket_count(ngram: usize, input_size: u64) -> u64 {
+    let wanted = (input_size / BYTES_PER_BUCKET).max(MIN_BUCKETS);
+    let buckets = 1u64 << (63 - wanted.leading_zeros());
+    buckets.min(1u64 << (8 * ngram)).min(MAX_BUCKETS)
+}
+
+/// Bucket of a gram among `buckets` (a power of two)
+fn gram_bucket(gram: &[u8], buckets: u64) -> u32 {
+    let key = gram_key(gram);
+    if buckets == 1u64 << (8 * gram.len()) {
+        key
+    } else {
+        // Fibonacci hashing: the top bits of the product mix all gram bytes
+        key.wrapping_mul(0x9e37_79b9) >> (32 - buckets.trailing_zeros())
+    }
+}
+
+/// Sorted, distinct buckets of the grams starting in a block
+///
+/// `data` holds the block followed by up to `ngram - 1` bytes of the next
+/// one, so grams crossing the block end are recorded for the block they
+/// start in.
+fn block_buckets(data: &[u8], ngram: usize, buckets: u64) -> Vec<u32> {
+    let mut keys: Vec<u32> = data
+        .windows(ngram)
+        .map(|gram| gram_bucket(gram, buckets))
+        .collect();
+    keys.sort_unstable();
+    keys.dedup();
+    keys
+}
+
+/// Key of a gram: its bytes as a big-endian number
+fn gram_key(gram: &[u8]) -> u32 {
+    gram.iter()
+        .fold(0, |key, &byte| (key << 8) | u32::from(byte))
+}
+
+/// `path` with `suffix` appended to its file name
+fn temp_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut name: OsString = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// (bucket, block) pairs of an index being built
+///
+/// Pairs are encoded as `bucket << 32 | block`, so their order is the order
+/// of the posting section. Once [`RUN_PAIRS`] pairs are collected they are
+/// sorted and written to a run file as varint deltas. Run files are removed
+/// when the runs are dropped.
+struct PairRuns {
+    base: PathBuf,
+    pairs: Vec<u64>,
+    runs: Vec<PathBuf>,
+}
+
+impl PairRuns {
+    fn new(base: &Path) -> Self {
+        Self {
+            base: base.to_path_buf(),
+            pairs: Vec::new(),
+            runs: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, bucket: u32, block: u32) -> Result<()> {
+        self.pairs
+            .push((u64::from(bucket) << 32) | u64::from(block));
+        if self.pairs.len() >= RUN_PAIRS {
+            self.spill()?;
+        }
+        Ok(())
+    }
+
+    /// Sort the collected pairs and write them to a new run file
+    fn spill(&mut self) -> Result<()> {
+        self.pairs.sort_unstable();
+        let mut bytes = Vec::with_capacity(self.pairs.len() * 2);
+        let mut last = 0;
+        for &pair in &self.pairs {
+            write_varint(&mut bytes, pair - last);
+            last = pair;
+        }
+
+        let path = temp_path(&self.base, &format!(".run{}.tmp", self.runs.len()));
+        self.runs.push(path.clone());
+        fs::write(&path, bytes)?;
+        self.pairs.clear();
+        Ok(())
+    }
+
+    /// Call `visit` with every pair in increasing order
+    fn merge<F: FnMut(u64) -> Result<()>>(&mut self, mut visit: F) -> Result<()> {
+        if self.runs.is_empty() {
+            self.pairs.sort_unstable();
+            return self.pairs.iter().try_for_each(|&pair| visit(pair));
+        }
+        if !self.pairs.is_empty() {
+            self.spill()?;
+        }
+
+        let mut readers = Vec::with_capacity(self.runs.len());
+        let mut heap = BinaryHeap::with_capacity(self.runs.len());
+        for (run, path) in self.runs.iter().enumerate() {
+            let mut reader = RunReader::new(File::open(path)?);
+            if let Some(pair) = reader.next_pair()? {
+                heap.push(Reverse((pair, run)));
+            }
+            readers.push(reader);
+        }
+        while let Some(Reverse((pair, run))) = heap.pop() {
+            visit(pair)?;
+            if let Some(next) = readers[run].next_pair()? {
+                heap.push(Reverse((next, run)));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for PairRuns {
+    fn drop(&mut self) {
+        for path in &self.runs {
+            fs::remove_file(path).ok();
+        }
+    }
+}
+
+/// Reads the pairs of one run file back
+struct RunReader {
+    reader: BufReader<File>,
+    last: u64,
+}
+
+impl RunReader {
+    fn new(file: File) -> Self {
+        Self {
+            reader: BufReader::new(file),
+            last: 0,
+        }
+    }
+
+    fn next_pair(&mut self) -> Result<Option<u64>> {
+        let (mut value, mut shift) = (0u64, 0);
+        let mut byte = [0u8; 1];
+        loop {
+            match self.reader.read_exact(&mut byte) {
+                Ok(()) => {}
+                Err(e) if e.kind() == ErrorKind::UnexpectedEof && shift == 0 => return Ok(None),
+                Err(e) => return Err(e.into()),
+            }
+            value |= u64::from(byte[0] & 0x7f) << shift;
+            if byte[0] & 0x80 == 0 {
+                self.last += value;
+                return Ok(Some(self.last));
+            }
+            shift += 7;
+        }
+    }
+}
+
+/// Merge the runs into posting lists written to `path`
+///
+/// Returns the posting list length of every bucket in bytes. Buckets found
+/// in more than half of the blocks are not written and carry
+/// [`COMMON_FLAG`] instead.
+fn write_postings(runs: &mut PairRuns, path: &Path, buckets: u64, blocks: u64) -> Result<Vec<u64>> {
+    let mut lens = vec![0u64; buckets as usize];
+    let mut out = BufWriter::new(File::create(path)?);
+    let mut current: Option<(u32, PostingList, u64)> = None;
+
+    let mut finish = |bucket: u32, list: PostingList, count: u64| -> Result<()> {
+        if count * 2 > blocks {
+            lens[bucket as usize] = COMMON_FLAG;
+        } else {
+            out.write_all(&list.bytes)?;
+            lens[bucket as usize] = list.bytes.len() as u64;
+        }
+        Ok(())
+    };
+
+    runs.merge(|pair| {
+        let (bucket, block) = ((pair >> 32) as u32, pair as u32);
+        match &mut current {
+            Some((current_bucket, list, count)) if *current_bucket == bucket => {
+                list.push(block);
+                *count += 1;
+            }
+            _ => {
+                if let Some((bucket, list, count)) = current.take() {
+                    finish(bucket, list, count)?;
+                }
+                let mut list = PostingList::default();
+                list.push(block);
+                current = Some((bucket, list, 1));
+            }
+        }
+        Ok(())
+    })?;
+    if let Some((bucket, list, count)) = current {
+        finish(bucket, list, count)?;
+    }
+    out.flush()?;
+    Ok(lens)
+}
+
+/// Leave out the longest posting lists until the rest fits in `budget` bytes
+///
+/// Long lists belong to common grams that barely narrow a search down, so
+/// they are the cheapest to lose. Their lengths are kept alongside
+/// [`COMMON_FLAG`] so the posting file can still be skipped over.
+fn limit_postings(lens: &mut [u64], budget: u64) {
+    let mut total: u64 = lens.iter().filter(|&&len| len & COMMON_FLAG == 0).sum();
+    if total <= budget {
+        return;
+    }
+
+    let mut longest: Vec<usize> = (0..lens.len())
+        .filter(|&bucket| lens[bucket] != 0 && lens[bucket] & COMMON_FLAG == 0)
+        .collect();
+    longest.sort_unstable_by_key(|&bucket| (Reverse(lens[bucket]), bucket));
+    for bucket in longest {
+        if total <= budget {
+            break;
+        }
+        total -= lens[bucket];
+        lens[bucket] |= COMMON_FLAG;
+    }
+}
+
+/// Write the header, bucket directory and posting lists to `path`
+///
+/// The posting lists are copied from the merged posting file at
+/// `postings_path`, skipping buckets left out as too common. The index is
+/// written next to its destination and renamed over it, so an interrupted
+/// build never leaves a truncated index behind.
+fn write_index(
+    path: &Path,
+    header: &IndexHeader,
+    lens: &[u64],
+    postings_path: &Path,
+) -> Result<()> {
+    let temp_path = temp_path(path, ".tmp");
+    let header_json =
+        serde_json::to_vec(header).map_err(|e| BingrepError::Output(e.to_string()))?;
+
+    let mut out = BufWriter::new(File::create(&temp_path)?);
+    out.write_all(INDEX_MAGIC)?;
+    out.write_all(&(header_json.len() as u32).to_le_bytes())?;
+    out.write_all(&header_json)?;
+
+    let mut end = 0u64;
+    for &len in lens {
+        if len & COMMON_FLAG != 0 {
+            out.write_all(&(end | COMMON_FLAG).to_le_bytes())?;
+        } else {
+            end += len;
+            out.write_all(&end.to_le_bytes())?;
+        }
+    }
+
+    let mut postings = BufReader::new(File::open(postings_path)?);
+    for &len in lens {
+        let mut list = (&mut postings).take(len & !COMMON_FLAG);
+        if len & COMMON_FLAG != 0 {
+            io::copy(&mut list, &mut io::sink())?;
+        } else {
+            io::copy(&mut list, &mut out)?;
+        }
+    }
+    out.flush()?;
+    drop(out);
+
+    fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
+/// SHA-256 over the input size and samples of its content
+///
+/// Samples of [`FINGERPRINT_SAMPLE`] bytes are taken at evenly spaced points
+/// from the start to the end of the input, so changes that keep the size
+/// and modification time (e.g. a re-acquired image) are still noticed
+/// without hashing the whole input.
+pub fn fingerprint<R: Read + Seek>(reader: &mut R, input_size: u64) -> Result<String> {
+    let mut hasher = Sha256::new();
+    hasher.update(input_size.to_le_bytes());
+
+    let last_start = input_size.saturating_sub(FINGERPRINT_SAMPLE);
+    for point in 0..FINGERPRINT_POINTS {
+        let offset = last_start * point / (FINGERPRINT_POINTS - 1);
+        hasher.update(read_chunk(reader, offset, FINGERPRINT_SAMPLE as usize)?);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// An index file opened for searching
+#[derive(Debug)]
+pub struct NgramIndex {
+    path: PathBuf,
+    file: File,
+    header: IndexHeader,
+    directory_start: u64,
+    postings_start: u64,
+}
+
+impl NgramIndex {
+    /// Open an index file and read its header
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let invalid = |reason: String| {
+            BingrepError::InvalidArgument(format!(
+                "{} is not a valid index file: {}",
+                path.display(),
+                reason
+            ))
+        };
+
+        let mut file = File::open(path)?;
+        let mut magic = [0u8; 12];
+        file.read_exact(&mut magic)
+            .map_err(|_| invalid("file too short".to_string()))?;
+        if &magic[..8] != INDEX_MAGIC {
+            return Err(invalid("bad magic".to_string()));
+        }
+
+        let header_len = u32::from_le_bytes(magic[8..].try_into().unwrap());
+        let mut header_json = vec![0u8; header_len as usize];
+        file.read_exact(&mut header_json)
+            .map_err(|_| invalid("truncated header".to_string()))?;
+        let header: IndexHeader =
+            serde_json::from_slice(&header_json).map_err(|e| invalid(e.to_string()))?;
+        if header.version != INDEX_VERSION {
+            return Err(invalid(format!(
+                "index version {}, expected {}",
+                header.version, INDEX_VERSION
+            )));
+        }
+
+        if !header.buckets.is_power_of_two() || header.buckets > MAX_BUCKETS {
+            return Err(invalid(format!("bad bucket count {}", header.buckets)));
+        }
+
+        let directory_start = 12 + u64::from(header_len);
+        let postings_start = directory_start + header.buckets * DIRECTORY_ENTRY_LEN;
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            header,
+            directory_start,
+            postings_start,
+        })
+    }
+
+    /// Header of the index
+    pub fn header(&self) -> &IndexHeader {
+        &self.header
+    }
+
+    /// Check that the index still describes the input read by `reader`
+    pub fn check_input<R: Read + Seek>(&self, input: &InputIdentity, reader: &mut R) -> Result<()> {
+        let indexed = &self.header.input;
+        let stale = |reason: String| {
+            BingrepError::InvalidArgument(format!(
+                "index {} is out of date: {} {} since it was built; rebuild it with `hxgrep index`",
+                self.path.display(),
+                input.path,
+                reason
+            ))
+        };
+
+        if indexed.size != input.size || indexed.modified != input.modified {
+            return Err(stale(format!(
+                "changed (size {} -> {}, modified {} -> {})",
+                indexed.size, input.size, indexed.modified, input.modified
+            )));
+        }
+        let input_size = reader.seek(SeekFrom::End(0))?;
+        if input_size != self.header.input_size {
+            return Err(stale(format!(
+                "changed size ({} -> {} bytes)",
+                self.header.input_size, input_size
+            )));
+        }
+        if fingerprint(reader, input_size)? != self.header.fingerprint {
+            return Err(stale("changed content".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Blocks containing `gram` (or a gram of the same bucket), in
+    /// increasing order
+    ///
+    /// Returns `None` for buckets left out as too common, which may occur
+    /// in any block.
+    pub fn postings(&mut self, gram: &[u8]) -> Result<Option<Vec<u32>>> {
+        let bucket = u64::from(gram_bucket(gram, self.header.buckets));
+        let mut entries = [0u8; 2 * DIRECTORY_ENTRY_LEN as usize];
+        let (first, entries) = if bucket == 0 {
+            (0, &mut entries[DIRECTORY_ENTRY_LEN as usize..])
+        } else {
+            (bucket - 1, &mut entries[..])
+        };
+        self.file.seek(SeekFrom::Start(
+            self.directory_start + first * DIRECTORY_ENTRY_LEN,
+        ))?;
+        self.file.read_exact(entries)?;
+
+        let end = u64::from_le_bytes(entries[entries.len() - 8..].try_into().unwrap());
+        if end & COMMON_FLAG != 0 {
+            return Ok(None);
+        }
+        let start = if entries.len() == 16 {
+            u64::from_le_bytes(entries[..8].try_into().unwrap()) & !COMMON_FLAG
+        } else {
+            0
+        };
+
+        let mut bytes = vec![0u8; (end - start) as usize];
+        self.file
+            .seek(SeekFrom::Start(self.postings_start + start))?;
+        self.file.read_exact(&mut bytes)?;
+        Ok(Some(decode_postings(&bytes)))
+    }
+
+    /// Ranges of match start offsets that may contain a match of `regex`
+    ///
+    /// Returns `None` when the pattern has no literal the index can use, in
+    /// which case the whole input has to be scanned. The ranges are sorted,
+    /// merged and clipped to the indexed input.
+    pub fn candidate_ranges(&mut self, regex: &Regex) -> Result<Option<Vec<Range<u64>>>> {
+        let Some(anchor) = LiteralAnchor::from_pattern(regex.as_str(), self.header.ngram) else {
+            return Ok(None);
+        };
+
+        let block_size = self.header.block_size;
+        let mut ranges = Vec::new();
+        for literal in &anchor.literals {
+            for block in self.literal_blocks(literal)? {
+                let literal_start = u64::from(block) * block_size;
+                let start = literal_start.saturating_sub(anchor.max_offset);
+                let end = (literal_start + block_size).saturating_sub(anchor.min_offset);
+                if start < end {
+                    ranges.push(start..end.min(self.header.input_size));
+                }
+            }
+        }
+
+        Ok(Some(merge_ranges(ranges)))
+    }
+
+    /// Blocks in which `literal` may start
+    ///
+    /// A block qualifies if every gram of the literal occurs in the block
+    /// it would fall into. Grams of buckets left out as too common do not
+    /// restrict the blocks.
+    fn literal_blocks(&mut self, literal: &[u8]) -> Result<Vec<u32>> {
+        let ngram = self.header.ngram;
+        let block_size = self.header.block_size as usize;
+        let mut blocks: Option<Vec<u32>> = None;
+
+        for shift in 0..=literal.len() - ngram {
+            let Some(postings) = self.postings(&literal[shift..shift + ngram])? else {
+                continue;
+            };
+            // The gram starts `shift` bytes after the literal, which is
+            // either in the same block as the literal start or the next one
+            let near = shift / block_size;
+            let far = shift.div_ceil(block_size);
+            let mut allowed = shifted(&postings, near as u32);
+            if far != near {
+                allowed = union(&allowed, &shifted(&postings, far as u32));
+            }
+            let narrowed = match blocks {
+                Some(blocks) => intersect(&blocks, &allowed),
+                None => allowed,
+            };
+            if narrowed.is_empty() {
+                return Ok(narrowed);
+            }
+            blocks = Some(narrowed);
+        }
+        Ok(blocks.unwrap_or_else(|| (0..self.header.blocks as u32).collect()))
+    }
+}
+
+/// Literals every match contains at a bounded distance from its start
+///
+/// If `literals` holds the alternatives, each match of the pattern contains
+/// one of them starting between `min_offset` and `max_offset` bytes after
+/// the start of the match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiteralAnchor {
+    pub literals: Vec<Vec<u8>>,
+    pub min_offset: u64,
+    pub max_offset: u64,
+}
+
+impl LiteralAnchor {
+    /// Find the most selective anchor of a pattern for `ngram`-byte grams
+    ///
+    /// The pattern is parsed the way the regex engine parses it. For every
+    /// element of its top-level concatenation whose preceding elements have
+    /// a bounded length, the prefix literals of the rest of the pattern are
+    /// candidates; the set whose shortest literal is longest wins. Returns
+    /// `None` if no set is finite with all literals at least `ngram` bytes.
+    pub fn from_pattern(pattern: &str, ngram: usize) -> Option<Self> {
+        let hir = regex_syntax::ParserBuilder::new()
+            .utf8(false)
+            .build()
+            .parse(pattern)
+            .ok()?;
+
+        let mut items = Vec::new();
+        flatten_concat(&hir, &mut items);
+
+        let extractor = {
+            let mut extractor = Extractor::new();
+            extractor.kind(ExtractKind::Prefix);
+            extractor
+        };
+
+        let mut best: Option<(usize, Self)> = None;
+        for position in 0..items.len() {
+            let prefix = Hir::concat(items[..position].to_vec());
+            let (Some(min_offset), Some(max_offset)) = (
+                prefix.properties().minimum_len(),
+                prefix.properties().maximum_len(),
+            ) else {
+                break;
+            };
+
+            let seq = extractor.extract(&Hir::concat(items[position..].to_vec()));
+            let Some(literals) = seq.literals() else {
+                continue;
+            };
+            if literals.is_empty() || literals.len() > MAX_ANCHOR_LITERALS {
+                continue;
+            }
+            let shortest = literals
+                .iter()
+                .map(|literal| literal.len())
+                .min()
+                .unwrap_or(0);
+            if shortest < ngram || best.as_ref().is_some_and(|(len, _)| *len >= shortest) {
+                continue;
+            }
+
+            best = Some((
+                shortest,
+                Self {
+                    literals: literals
+                        .iter()
+                        .map(|literal| literal.as_bytes().to_vec())
+                        .collect(),
+                    min_offset: min_offset as u64,
+                    max_offset: max_offset as u64,
+                },
+            ));
+        }
+
+        best.map(|(_, anchor)| anchor)
+    }
+}
+
+/// Collect the elements of nested concatenations and capture groups
+fn flatten_concat(hir: &Hir, items: &mut Vec<Hir>) {
+    match hir.kind() {
+        HirKind::Concat(subs) => subs.iter().for_each(|sub| flatten_concat(sub, items)),
+        HirKind::Capture(capture) => flatten_concat(&capture.sub, items),
+        _ => items.push(hir.clone()),
+    }
+}
+
+/// Merge sorted or unsorted ranges into sorted, non-overlapping ranges
+fn merge_ranges(mut ranges: Vec<Range<u64>>) -> Vec<Range<u64>> {
+    ranges.sort_unstable_by_key(|range| range.start);
+    let mut merged: Vec<Range<u64>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// Block ids of `postings` moved `by` blocks earlier
+fn shifted(postings: &[u32], by: u32) -> Vec<u32> {
+    postings
+        .iter()
+        .filter_map(|&block| block.checked_sub(by))
+        .collect()
+}
+
+/// Union of two increasing lists
+fn union(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut merged = Vec::with_capacity(a.len() + b.len());
+    merged.extend_from_slice(a);
+    merged.extend_from_slice(b);
+    merged.sort_unstable();
+    merged.dedup();
+    merged
+}
+
+/// Intersection of two increasing lists
+fn intersect(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (mut i, mut j) = (0, 0);
+    let mut common = Vec::new();
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                common.push(a[i]);
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    common
+}
+
+/// Append `value` as an unsigned LEB128 varint
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// Decode a posting list of varint deltas into block ids
+fn decode_postings(bytes: &[u8]) -> Vec<u32> {
+    let mut blocks = Vec::new();
+    let (mut block, mut value, mut shift) = (0u32, 0u32, 0);
+    for &byte in bytes {
+        value |= u32::from(byte & 0x7f) << shift;
+        if byte & 0x80 != 0 {
+            shift += 7;
+            continue;
+        }
+        block += value;
+        blocks.push(block);
+        value = 0;
+        shift = 0;
+    }
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn identity(size: u64) -> InputIdentity {
+        InputIdentity {
+            path: "image.dd".to_string(),
+            size,
+            modified: 1_700_000_000,
+        }
+    }
+
+    #[test]
+    fn test_posting_list_roundtrip() {
+        let mut list = PostingList::default();
+        for block in [0, 1, 200, 70_000, 4_000_000_000] {
+            list.push(block);
+        }
+        assert_eq!(
+            decode_postings(&list.bytes),
+            vec![0, 1, 200, 70_000, 4_000_000_000]
+        );
+    }
+
+    #[test]
+    fn test_pair_runs_merge_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut runs = PairRuns::new(&dir.path().join("data.hxidx"));
+        for (bucket, block) in [(7, 1), (2, 5), (7, 0)] {
+            runs.push(bucket, block).unwrap();
+        }
+        runs.spill().unwrap();
+        for (bucket, block) in [(2, 3), (900, 2), (0, 4)] {
+            runs.push(bucket, block).unwrap();
+        }
+        runs.spill().unwrap();
+        runs.push(2, 4).unwrap();
+
+        let mut merged = Vec::new();
+        runs.merge(|pair| {
+            merged.push(((pair >> 32) as u32, pair as u32));
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(
+            merged,
+            vec![(0, 4), (2, 3), (2, 4), (2, 5), (7, 0), (7, 1), (900, 2)]
+        );
+
+        // Run files are removed with the runs
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 3);
+        drop(runs);
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_limit_postings_drops_longest() {
+        let mut lens = vec![0, 40, COMMON_FLAG, 10, 40, 5];
+        limit_postings(&mut lens, 30);
+        assert_eq!(
+            lens,
+            vec![0, 40 | COMMON_FLAG, COMMON_FLAG, 10, 40 | COMMON_FLAG, 5]
+        );
+    }
+
+    #[test]
+    fn test_random_data_index_size() {
+        // Near-random data has about one distinct gram per byte
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let data: Vec<u8> = (0..2_000_000)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                (state >> 24) as u8
+            })
+            .collect();
+        let size = data.len() as u64;
+
+        let dir = tempfile::tempdir().unwrap();
+        for block_size in [65_536, 4096] {
+            let path = dir.path().join(format!("{}.hxidx", block_size));
+            let mut reader = Cursor::new(data.clone());
+            let header = IndexBuilder::new(3, block_size)
+                .unwrap()
+                .build(&mut reader, identity(size), false, &path)
+                .unwrap();
+            assert!(header.buckets < 1 << 24);
+
+            let index_size = fs::metadata(&path).unwrap().len();
+            assert!(
+                index_size < size / 4,
+                "{} byte index for {} byte input",
+                index_size,
+                size
+            );
+
+            // Still finds the blocks of a literal
+            let literal = data[1_500_000..1_500_008].to_vec();
+            let mut index = NgramIndex::open(&path).unwrap();
+            let blocks = index.literal_blocks(&literal).unwrap();
+            assert!(blocks.contains(&((1_500_000 / block_size) as u32)));
+        }
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_literal_anchor() {
+        // Bounded prefix: the literal starts 2 to 4 bytes into the match
+        let anchor = LiteralAnchor::from_pattern(r"\x00{2,4}MARKER\d+", 3).unwrap();
+        assert_eq!(anchor.literals, vec![b"MARKER".to_vec()]);
+        assert_eq!((anchor.min_offset, anchor.max_offset), (2, 4));
+
+        let anchor = LiteralAnchor::from_pattern("(PK|MZ)\\x03\\x04", 3).unwrap();
+        assert_eq!(
+            anchor.literals,
+            vec![b"PK\x03\x04".to_vec(), b"MZ\x03\x04".to_vec()]
+        );
+        assert_eq!((anchor.min_offset, anchor.max_offset), (0, 0));
+
+        // Too short, unbounded or without literals
+        assert!(LiteralAnchor::from_pattern("AB", 3).is_none());
+        assert!(LiteralAnchor::from_pattern(".*MARKER", 3).is_none());
+        assert!(LiteralAnchor::from_pattern(r"[\x00-\xff]{4}", 3).is_none());
+    }
+
+    #[test]
+    fn test_candidate_ranges_cover_matches() {
+        let block_size = 64u64;
+        let mut data = vec![0u8; 1024];
+        // One literal inside a block, one crossing a block boundary
+        data[100..106].copy_from_slice(b"MARKER");
+        data[638..644].copy_from_slice(b"MARKER");
+        // Grams of the literal that never occur in order
+        data[900..903].copy_from_slice(b"MAR");
+        data[960..963].copy_from_slice(b"KER");
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.hxidx");
+        let mut reader = Cursor::new(data.clone());
+        let header = IndexBuilder::new(3, block_size)
+            .unwrap()
+            .build(&mut reader, identity(1024), false, &path)
+            .unwrap();
+        assert_eq!(header.blocks, 16);
+
+        let mut index = NgramIndex::open(&path).unwrap();
+        assert!(index.check_input(&identity(1024), &mut reader).is_ok());
+
+        let regex = Regex::new("MARKER").unwrap();
+        let ranges = index.candidate_ranges(&regex).unwrap().unwrap();
+        assert_eq!(ranges, vec![64..128, 576..640]);
+
+        let regex = Regex::new(".*").unwrap();
+        assert!(index.candidate_ranges(&regex).unwrap().is_none());
+
+        // Same size and time, different content
+        data[0] = 1;
+        let mut changed = Cursor::new(data);
+        assert!(index.check_input(&identity(1024), &mut changed).is_err());
+    }
+}
@@ -0,0 +1,122 @@
+//! Input opening for the analysis subcommands
+//!
+//! Subcommands such as `entropy` accept the same inputs as the search mode:
+//! regular files, forensic images (E01, VMDK) and stdin (`-`). File lists for
+//! `--files-from` are read here as well.
+
+use crate::error::Result;
+use crate::forensic_image::{is_forensic_image, ForensicImageReader};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Cursor, Read, Seek};
+use std::path::{Path, PathBuf};
+
+/// Name under which stdin (`-`) is shown in output and records
+pub const STDIN_LABEL: &str = "(standard input)";
+
+/// Name of an input as shown in output and records
+///
+/// Only stdin is renamed, to [`STDIN_LABEL`]; paths are shown as given.
+pub fn input_label(path: &str) -> &str {
+    if path == "-" {
+        STDIN_LABEL
+    } else {
+        path
+    }
+}
+
+/// Any seekable byte source
+pub trait InputReader: Read + Seek {}
+
+impl<T: Read + Seek> InputReader for T {}
+
+/// Open `path` as a seekable reader
+///
+/// `-` reads all of stdin into memory, forensic images are opened through
+/// [`ForensicImageReader`] and everything else as a regular file.
+pub fn open_input<P: AsRef<Path>>(path: P) -> Result<Box<dyn InputReader>> {
+    let path = path.as_ref();
+    if path == Path::new("-") {
+        let mut data = Vec::new();
+        io::stdin().read_to_end(&mut data)?;
+        Ok(Box::new(Cursor::new(data)))
+    } else if is_forensic_image(path) {
+        Ok(Box::new(ForensicImageReader::new(path)?))
+    } else {
+        Ok(Box::new(File::open(path)?))
+    }
+}
+
+/// Read a list of paths from `source` (`-` for stdin)
+///
+/// Entries are separated by newlines, or by NUL bytes with `null_separated`
+/// (`find -print0`). Empty entries are skipped. Names are kept byte for byte,
+/// so paths that are not valid UTF-8 still open.
+pub fn read_path_list(source: &str, null_separated: bool) -> Result<Vec<PathBuf>> {
+    if source == "-" {
+        parse_path_list(io::stdin().lock(), null_separated)
+    } else {
+        parse_path_list(BufReader::new(File::open(source)?), null_separated)
+    }
+}
+
+fn parse_path_list<R: BufRead>(mut reader: R, null_separated: bool) -> Result<Vec<PathBuf>> {
+    let delimiter = if null_separated { b'\0' } else { b'\n' };
+    let mut paths = Vec::new();
+    let mut entry = Vec::new();
+
+    while reader.read_until(delimiter, &mut entry)? > 0 {
+        if entry.last() == Some(&delimiter) {
+            entry.pop();
+        }
+        if !null_separated && entry.last() == Some(&b'\r') {
+            entry.pop();
+        }
+        if !entry.is_empty() {
+            paths.push(path_from_bytes(entry.clone()));
+        }
+        entry.clear();
+    }
+
+    Ok(paths)
+}
+
+/// Turn the raw bytes of a listed name into a path
+#[cfg(unix)]
+fn path_from_bytes(bytes: Vec<u8>) -> PathBuf {
+    use std::os::unix::ffi::OsStringExt;
+    PathBuf::from(std::ffi::OsString::from_vec(bytes))
+}
+
+/// Turn the raw bytes of a listed name into a path
+///
+/// Paths are not byte strings outside unix; lists there are read as UTF-8.
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: Vec<u8>) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_path_list_newline() {
+        let paths = parse_path_list(&b"a.bin\r\n\ndir/b c.bin\n"[..], false).unwrap();
+        assert_eq!(paths, vec![PathBuf::from("a.bin"), PathBuf::from("dir/b c.bin")]);
+    }
+
+    #[test]
+    fn test_parse_path_list_null() {
+        let paths = parse_path_list(&b"line\nbreak.bin\0b.bin\0"[..], true).unwrap();
+        assert_eq!(paths, vec![PathBuf::from("line\nbreak.bin"), PathBuf::from("b.bin")]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_parse_path_list_keeps_non_utf8_names() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let paths = parse_path_list(&b"caf\xE9.bin\0"[..], true).unwrap();
+        assert_eq!(paths[0].as_os_str().as_bytes(), b"caf\xE9.bin");
+    }
+}
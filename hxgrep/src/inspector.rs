@@ -0,0 +1,443 @@
+//! Data interpretation panel for matches
+//!
+//! Decodes the bytes at a match offset as integers, floats, timestamps,
+//! GUIDs and UTF-16 text, so hits can be read without pasting them into a
+//! separate tool. Selected with `--inspect`; shown as a block under each
+//! match in hex output and as an `inspection` field in structured records.
+
+use crate::cli::InspectType;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+/// Number of bytes at the match offset that are decoded (enough for a GUID)
+pub const INSPECT_LEN: usize = 16;
+
+/// Seconds between 1601-01-01 (FILETIME epoch) and 1970-01-01
+const FILETIME_EPOCH_DIFF: i64 = 11_644_473_600;
+
+/// Seconds between 1904-01-01 (HFS epoch) and 1970-01-01
+const HFS_EPOCH_DIFF: i64 = 2_082_844_800;
+
+static INSPECTOR: OnceLock<Inspector> = OnceLock::new();
+
+impl InspectType {
+    /// Name used in output records
+    pub fn name(&self) -> &'static str {
+        match self {
+            InspectType::U8 => "u8",
+            InspectType::I8 => "i8",
+            InspectType::U16 => "u16",
+            InspectType::I16 => "i16",
+            InspectType::U32 => "u32",
+            InspectType::I32 => "i32",
+            InspectType::U64 => "u64",
+            InspectType::I64 => "i64",
+            InspectType::F32 => "f32",
+            InspectType::F64 => "f64",
+            InspectType::Unix => "unix",
+            InspectType::Filetime => "filetime",
+            InspectType::Hfs => "hfs",
+            InspectType::Dos => "dos",
+            InspectType::Guid => "guid",
+            InspectType::Utf16 => "utf16",
+            InspectType::All => "all",
+        }
+    }
+
+    /// Word size in bytes, used to byte-swap for the big-endian interpretation
+    fn word_size(&self) -> usize {
+        match self {
+            InspectType::U16 | InspectType::I16 | InspectType::Utf16 => 2,
+            InspectType::U64 | InspectType::I64 | InspectType::F64 => 8,
+            _ => 4,
+        }
+    }
+
+    /// Every concrete type, in display order
+    fn all() -> [InspectType; 16] {
+        [
+            InspectType::U8,
+            InspectType::I8,
+            InspectType::U16,
+            InspectType::I16,
+            InspectType::U32,
+            InspectType::I32,
+            InspectType::U64,
+            InspectType::I64,
+            InspectType::F32,
+            InspectType::F64,
+            InspectType::Unix,
+            InspectType::Filetime,
+            InspectType::Hfs,
+            InspectType::Dos,
+            InspectType::Guid,
+            InspectType::Utf16,
+        ]
+    }
+}
+
+/// One decoded interpretation of the bytes at a match
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct InspectedValue {
+    /// Interpretation type (`u32`, `filetime`, `guid`, ...)
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Byte order (`le` or `be`) for multi-byte types that have one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endian: Option<String>,
+    /// Decoded value
+    pub value: String,
+}
+
+/// Decodes match bytes as the selected types
+#[derive(Debug, Clone)]
+pub struct Inspector {
+    types: Vec<InspectType>,
+}
+
+impl Inspector {
+    /// Create an inspector for the given types (`all` selects every type)
+    pub fn new(types: &[InspectType]) -> Self {
+        let mut selected = Vec::new();
+        for &t in types {
+            let expanded: Vec<InspectType> = if t == InspectType::All {
+                InspectType::all().to_vec()
+            } else {
+                vec![t]
+            };
+            for t in expanded {
+                if !selected.contains(&t) {
+                    selected.push(t);
+                }
+            }
+        }
+        Self { types: selected }
+    }
+
+    /// Decode the bytes at a match
+    ///
+    /// Only the first [`INSPECT_LEN`] bytes are used. Types that need more
+    /// bytes than are available are left out.
+    pub fn inspect(&self, bytes: &[u8]) -> Vec<InspectedValue> {
+        let bytes = &bytes[..bytes.len().min(INSPECT_LEN)];
+        let mut values = Vec::new();
+
+        for &t in &self.types {
+            let name = t.name();
+            match t {
+                InspectType::U8 => push(&mut values, name, None, bytes.first().map(|b| b.to_string())),
+                InspectType::I8 => {
+                    push(&mut values, name, None, bytes.first().map(|&b| (b as i8).to_string()))
+                }
+                InspectType::U16 => push_both(&mut values, t, bytes, |b| {
+                    read::<2>(b).map(|a| u16::from_le_bytes(a).to_string())
+                }),
+                InspectType::I16 => push_both(&mut values, t, bytes, |b| {
+                    read::<2>(b).map(|a| i16::from_le_bytes(a).to_string())
+                }),
+                InspectType::U32 => push_both(&mut values, t, bytes, |b| {
+                    read::<4>(b).map(|a| u32::from_le_bytes(a).to_string())
+                }),
+                InspectType::I32 => push_both(&mut values, t, bytes, |b| {
+                    read::<4>(b).map(|a| i32::from_le_bytes(a).to_string())
+                }),
+                InspectType::U64 => push_both(&mut values, t, bytes, |b| {
+                    read::<8>(b).map(|a| u64::from_le_bytes(a).to_string())
+                }),
+                InspectType::I64 => push_both(&mut values, t, bytes, |b| {
+                    read::<8>(b).map(|a| i64::from_le_bytes(a).to_string())
+                }),
+                InspectType::F32 => push_both(&mut values, t, bytes, |b| {
+                    read::<4>(b).map(|a| format_float(f32::from_le_bytes(a) as f64, true))
+                }),
+                InspectType::F64 => push_both(&mut values, t, bytes, |b| {
+                    read::<8>(b).map(|a| format_float(f64::from_le_bytes(a), false))
+                }),
+                InspectType::Unix => push_both(&mut values, t, bytes, |b| {
+                    read::<4>(b).map(|a| format_timestamp(u32::from_le_bytes(a) as i64))
+                }),
+                InspectType::Hfs => push_both(&mut values, t, bytes, |b| {
+                    read::<4>(b)
+                        .map(|a| format_timestamp(u32::from_le_bytes(a) as i64 - HFS_EPOCH_DIFF))
+                }),
+                InspectType::Filetime => {
+                    push(&mut values, name, None, read::<8>(bytes).map(|a| {
+                        let ticks = u64::from_le_bytes(a);
+                        format_timestamp((ticks / 10_000_000) as i64 - FILETIME_EPOCH_DIFF)
+                    }))
+                }
+                InspectType::Dos => push(&mut values, name, None, read::<4>(bytes).map(format_dos)),
+                InspectType::Guid => push(&mut values, name, None, read::<16>(bytes).map(format_guid)),
+                InspectType::Utf16 => push_both(&mut values, t, bytes, |b| {
+                    (b.len() >= 2).then(|| decode_utf16(b))
+                }),
+                InspectType::All => {}
+            }
+        }
+
+        values
+    }
+
+    /// Render the decoded values as an indented text block, one line per type
+    pub fn render(&self, bytes: &[u8]) -> Vec<String> {
+        render_values(&self.inspect(bytes))
+    }
+}
+
+/// Render decoded values as text lines, joining the byte orders of a type
+pub fn render_values(values: &[InspectedValue]) -> Vec<String> {
+    let mut lines: Vec<(String, Vec<String>)> = Vec::new();
+
+    for value in values {
+        let text = match &value.endian {
+            Some(endian) => format!("{} {}", endian, value.value),
+            None => value.value.clone(),
+        };
+        match lines.last_mut() {
+            Some((kind, parts)) if *kind == value.kind => parts.push(text),
+            _ => lines.push((value.kind.clone(), vec![text])),
+        }
+    }
+
+    lines
+        .into_iter()
+        .map(|(kind, parts)| format!("    {:<8} {}", kind, parts.join("  ")))
+        .collect()
+}
+
+/// Add a value that has no byte order
+fn push(values: &mut Vec<InspectedValue>, kind: &str, endian: Option<&str>, value: Option<String>) {
+    if let Some(value) = value {
+        values.push(InspectedValue {
+            kind: kind.to_string(),
+            endian: endian.map(str::to_string),
+            value,
+        });
+    }
+}
+
+/// Add the little- and big-endian interpretations of a type
+///
+/// `decode` reads little-endian values; the big-endian one is produced by
+/// decoding the byte-swapped words.
+fn push_both<F>(values: &mut Vec<InspectedValue>, kind: InspectType, bytes: &[u8], decode: F)
+where
+    F: Fn(&[u8]) -> Option<String>,
+{
+    push(values, kind.name(), Some("le"), decode(bytes));
+
+    let swapped: Vec<u8> = bytes
+        .chunks_exact(kind.word_size())
+        .flat_map(|w| w.iter().rev().copied())
+        .collect();
+    push(values, kind.name(), Some("be"), decode(&swapped));
+}
+
+/// First `N` bytes as an array, if available
+fn read<const N: usize>(bytes: &[u8]) -> Option<[u8; N]> {
+    bytes.get(..N).and_then(|b| b.try_into().ok())
+}
+
+/// Format a float, switching to exponent notation for very large or small values
+fn format_float(value: f64, single: bool) -> String {
+    let magnitude = value.abs();
+    if value == 0.0 || !value.is_finite() || (1e-4..1e15).contains(&magnitude) {
+        if single {
+            format!("{}", value as f32)
+        } else {
+            format!("{}", value)
+        }
+    } else if single {
+        format!("{:e}", value as f32)
+    } else {
+        format!("{:e}", value)
+    }
+}
+
+/// Format seconds since 1970-01-01 as a UTC date and time
+pub(crate) fn format_timestamp(seconds: i64) -> String {
+    let days = seconds.div_euclid(86_400);
+    let secs = seconds.rem_euclid(86_400);
+    let (year, month, day) = civil_from_days(days);
+
+    if !(1..=9999).contains(&year) {
+        return "out of range".to_string();
+    }
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
+
+/// Convert days since 1970-01-01 to a (year, month, day) civil date
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Format an MS-DOS date/time (FAT, ZIP): little-endian time word, then date word
+fn format_dos(bytes: [u8; 4]) -> String {
+    let time = u16::from_le_bytes([bytes[0], bytes[1]]);
+    let date = u16::from_le_bytes([bytes[2], bytes[3]]);
+
+    let year = 1980 + (date >> 9);
+    let month = (date >> 5) & 0x0F;
+    let day = date & 0x1F;
+    let hour = time >> 11;
+    let minute = (time >> 5) & 0x3F;
+    let second = (time & 0x1F) * 2;
+
+    if !(1..=12).contains(&month) || day == 0 || hour > 23 || minute > 59 || second > 59 {
+        return "invalid".to_string();
+    }
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year, month, day, hour, minute, second
+    )
+}
+
+/// Format a GUID in the Windows mixed-endian layout
+fn format_guid(b: [u8; 16]) -> String {
+    let guid = format!(
+        "{{{:08X}-{:04X}-{:04X}-{:02X}{:02X}-{:02X}{:02X}{:02X}{:02X}{:02X}{:02X}}}",
+        u32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+        u16::from_le_bytes([b[4], b[5]]),
+        u16::from_le_bytes([b[6], b[7]]),
+        b[8],
+        b[9],
+        b[10],
+        b[11],
+        b[12],
+        b[13],
+        b[14],
+        b[15]
+    );
+    if crate::output_style::get_output_style().lowercase {
+        guid.to_lowercase()
+    } else {
+        guid
+    }
+}
+
+/// Decode little-endian UTF-16 up to the first NUL, replacing unprintable characters
+fn decode_utf16(bytes: &[u8]) -> String {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|w| u16::from_le_bytes([w[0], w[1]]))
+        .take_while(|&u| u != 0)
+        .collect();
+
+    let text: String = char::decode_utf16(units)
+        .map(|c| match c {
+            Ok(c) if !c.is_control() => c,
+            _ => '.',
+        })
+        .collect();
+    format!("\"{}\"", text)
+}
+
+/// Set the global inspector used for hex output
+pub fn set_inspector(inspector: Inspector) {
+    INSPECTOR.set(inspector).ok();
+}
+
+/// Get the global inspector, if `--inspect` was given
+pub fn get_inspector() -> Option<&'static Inspector> {
+    INSPECTOR.get()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(values: &[InspectedValue], kind: &str, endian: Option<&str>) -> Option<String> {
+        values
+            .iter()
+            .find(|v| v.kind == kind && v.endian.as_deref() == endian)
+            .map(|v| v.value.clone())
+    }
+
+    #[test]
+    fn test_integers_and_floats_in_both_byte_orders() {
+        let inspector = Inspector::new(&[InspectType::U16, InspectType::I32, InspectType::F32]);
+        let values = inspector.inspect(&[0x01, 0x02, 0x80, 0x3F]);
+
+        assert_eq!(value(&values, "u16", Some("le")).unwrap(), "513");
+        assert_eq!(value(&values, "u16", Some("be")).unwrap(), "258");
+        assert_eq!(value(&values, "i32", Some("le")).unwrap(), "1065353729");
+        assert_eq!(value(&values, "f32", Some("le")).unwrap(), "1.0000612");
+        // u64 was not selected and would not fit anyway
+        assert!(value(&values, "u64", Some("le")).is_none());
+    }
+
+    #[test]
+    fn test_timestamps() {
+        let inspector = Inspector::new(&[
+            InspectType::Unix,
+            InspectType::Filetime,
+            InspectType::Hfs,
+            InspectType::Dos,
+        ]);
+
+        // 2001-09-09 01:46:40 UTC
+        let unix = 1_000_000_000u32.to_le_bytes();
+        let values = inspector.inspect(&unix);
+        assert_eq!(value(&values, "unix", Some("le")).unwrap(), "2001-09-09 01:46:40 UTC");
+        assert!(value(&values, "filetime", None).is_none());
+
+        let filetime = ((1_000_000_000 + FILETIME_EPOCH_DIFF) as u64 * 10_000_000).to_le_bytes();
+        let values = inspector.inspect(&filetime);
+        assert_eq!(value(&values, "filetime", None).unwrap(), "2001-09-09 01:46:40 UTC");
+
+        let hfs = ((1_000_000_000 + HFS_EPOCH_DIFF) as u32).to_be_bytes();
+        let values = inspector.inspect(&hfs);
+        assert_eq!(value(&values, "hfs", Some("be")).unwrap(), "2001-09-09 01:46:40 UTC");
+
+        // 2020-05-17 13:45:30 as DOS time/date
+        let time: u16 = (13 << 11) | (45 << 5) | 15;
+        let date: u16 = (40 << 9) | (5 << 5) | 17;
+        let mut dos = time.to_le_bytes().to_vec();
+        dos.extend_from_slice(&date.to_le_bytes());
+        let values = inspector.inspect(&dos);
+        assert_eq!(value(&values, "dos", None).unwrap(), "2020-05-17 13:45:30");
+    }
+
+    #[test]
+    fn test_guid_and_utf16() {
+        let inspector = Inspector::new(&[InspectType::All]);
+        let guid = [
+            0x33, 0x22, 0x11, 0x00, 0x55, 0x44, 0x77, 0x66, 0x88, 0x99, 0xAA, 0xBB, 0xCC, 0xDD,
+            0xEE, 0xFF,
+        ];
+        let values = inspector.inspect(&guid);
+        assert_eq!(
+            value(&values, "guid", None).unwrap(),
+            "{00112233-4455-6677-8899-AABBCCDDEEFF}"
+        );
+
+        let values = inspector.inspect(&[b'H', 0, b'i', 0, 0, 0]);
+        assert_eq!(value(&values, "utf16", Some("le")).unwrap(), "\"Hi\"");
+    }
+
+    #[test]
+    fn test_render_joins_byte_orders() {
+        let inspector = Inspector::new(&[InspectType::U8, InspectType::U16]);
+        let lines = inspector.render(&[0x01, 0x02]);
+        assert_eq!(lines, vec!["    u8       1", "    u16      le 513  be 258"]);
+    }
+}
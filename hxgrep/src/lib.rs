@@ -10,9 +10,30 @@
 //! * `cli` - Command-line interface handling
 //! * `config` - Configuration and validation
 //! * `regex_processor` - Regular expression compilation and processing
+//! * `reverse` - Rebuild binaries from hex dumps (xxd -r)
+//! * `replace` - Binary search-and-replace (patch) mode
+//! * `alignment` - Sector/cluster aligned matching and LBA reporting
+//! * `diff` - Streaming byte-level comparison of two inputs
+//! * `entropy` - Windowed Shannon entropy scanning
+//! * `strings` - Printable string extraction across encodings
+//! * `stats` - Byte histogram and statistics
 //! * `stream` - File streaming and pattern matching
+//! * `checkpoint` - Checkpoint files for resumable searches
+//! * `audit` - Hash-chained audit log of every run (`--audit-log`)
+//! * `index` - Persistent n-gram index to narrow repeated searches
+//! * `blockhash` - Aligned block hashing against known-file hash sets
+//! * `hashing` - MD5/SHA-1/SHA-256 digests
+//! * `ewf` - Expert Witness Format (E01) section metadata
+//! * `vmdk` - VMDK descriptor and sparse extent metadata
+//! * `image_info` - Forensic image metadata for the `info` subcommand
+//! * `html_report` - Self-contained HTML report of search results
+//! * `multifile` - Multi-file processing with a shared global limit
+//! * `walk` - Recursive directory traversal with include/exclude filters
+//! * `search` - Regex search producing structured match records
+//! * `inspector` - Integer/float/timestamp/GUID interpretation of match bytes
 //! * `buffer_manager` - Efficient buffer management for large files
 //! * `output` - Hexadecimal output formatting
+//! * `output_style` - Offset radix, hex case and byte grouping options
 //! * `error` - Error types and handling
 //!
 //! ## Example Usage
@@ -27,19 +48,40 @@
 //! // Process file with regex...
 //! ```
 
+pub mod alignment;
+pub mod audit;
+pub mod blockhash;
 pub mod buffer_manager;
+pub mod checkpoint;
 pub mod cli;
 pub mod color_context;
 pub mod config;
+pub mod diff;
+pub mod entropy;
 pub mod error;
+pub mod ewf;
 pub mod forensic_image;
+pub mod hashing;
+pub mod html_report;
+pub mod image_info;
+pub mod index;
+pub mod input;
+pub mod inspector;
 pub mod multifile;
 pub mod output;
+pub mod output_style;
 pub mod parallel;
 pub mod progress;
 pub mod regex_processor;
+pub mod replace;
+pub mod reverse;
+pub mod search;
+pub mod stats;
 pub mod stream;
+pub mod strings;
 pub mod structured_output;
+pub mod vmdk;
+pub mod walk;
 
 pub use cli::Cli;
 pub use config::Config;
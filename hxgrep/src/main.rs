@@ -1,126 +1,398 @@
-use hxgrep::cli::Cli;
+use hxgrep::alignment::{AlignedMatcher, Alignment};
+use hxgrep::audit::{AuditInput, AuditLog, AuditRecord};
+use hxgrep::blockhash::{BlockHasher, KnownHashes};
+use hxgrep::checkpoint::{pattern_hash, Checkpoint, Checkpointer, InputIdentity};
+use hxgrep::cli::{
+    BlockhashArgs, Cli, Command, DiffArgs, EntropyArgs, HashAlgorithm, IndexArgs, InfoArgs,
+    ProgressFormat, ReportArgs, ReverseArgs, StatsArgs, StringsArgs, VerifyAuditArgs,
+};
 use hxgrep::config::Config;
-use hxgrep::error::Result;
+use hxgrep::diff::{DiffOptions, Differ};
+use hxgrep::entropy::{render_map, EntropyScanner};
+use hxgrep::error::{BingrepError, Result};
+use hxgrep::forensic_image::ForensicImageReader;
+use hxgrep::hashing::{render_hashes, HashingReader, RegionHashes};
+use hxgrep::html_report::{HitContext, HtmlReport, ReportHit, ReportInput};
+use hxgrep::image_info::ImageInfo;
+use hxgrep::index::{IndexBuilder, NgramIndex};
+use hxgrep::input::{input_label, open_input, read_path_list, InputReader, STDIN_LABEL};
+use hxgrep::inspector::{render_values, Inspector};
 use hxgrep::multifile::MultiFileProcessor;
 use hxgrep::output::OutputFormatter;
-use hxgrep::parallel::{ParallelHexDump, ParallelProcessor};
+use hxgrep::output_style::OutputStyle;
+use hxgrep::parallel::{read_chunk, ParallelHexDump, ParallelProcessor};
+use hxgrep::progress::ProgressIndicator;
 use hxgrep::regex_processor::RegexProcessor;
-use hxgrep::stream::FileProcessor;
+use hxgrep::replace::{patch_file, ReplaceOptions, Replacer};
+use hxgrep::reverse::{reverse_dump, DumpStyle, ReverseWriter};
+use hxgrep::search::{GroupSelection, MatchCollector, ReportMode};
+use hxgrep::stats::{StatsCollector, StatsScope};
+use hxgrep::stream::{FileProcessor, STREAM_OFFSET_SPAN};
+use hxgrep::strings::StringExtractor;
+use hxgrep::structured_output::{BinaryMatch, OutputFormat, StructuredFormatter};
+use hxgrep::walk::WalkOptions;
 use clap::Parser;
 use std::fs::File;
-use std::io::{self, Read, Seek, SeekFrom};
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::SystemTime;
 
-fn main() -> Result<()> {
-    let cli = Cli::parse();
+/// Exit status when no match was found (grep compatible)
+const EXIT_NO_MATCH: u8 = 1;
 
-    // Set global color choice
-    hxgrep::color_context::set_color_choice(cli.color.clone());
+/// Exit status for errors (grep compatible)
+const EXIT_ERROR: u8 = 2;
 
-    // Check file path or stdin
-    let file_path = match &cli.file_path {
-        Some(path) => {
-            if path == "-" {
-                // Handle stdin input
-                return handle_stdin_input(&cli);
-            }
-            path.clone()
+/// What a finished run found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Outcome {
+    /// Whether the run succeeded (exit status 0 instead of 1)
+    success: bool,
+    /// Matches, hits or differences the run reported, for the audit log
+    matches: u64,
+}
+
+impl Outcome {
+    /// A run that succeeds without looking for anything (dumps, analyses)
+    fn done() -> Self {
+        Self {
+            success: true,
+            matches: 0,
         }
-        None => {
-            // Clap will automatically show help when no file path is provided
-            eprintln!("Error: 파일 경로가 필요합니다.\n");
-            eprintln!("사용법: hxgrep <파일경로> [옵션]");
-            eprintln!("사용법: hxgrep - [옵션] < input_file (stdin)");
-            eprintln!("도움말: hxgrep --help");
-            return Ok(());
+    }
+
+    /// A search that succeeds when it found something
+    fn found(matches: usize) -> Self {
+        Self {
+            success: matches > 0,
+            matches: matches as u64,
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let started = SystemTime::now();
+    let mut cli = Cli::parse();
+    // Several inputs or a file list always mean multi-file mode
+    cli.multi_file |= cli.file_paths.len() > 1 || cli.files_from.is_some();
+
+    // A damaged audit log is refused before anything runs
+    let audit_log = match cli.audit_log.as_ref().map(AuditLog::open).transpose() {
+        Ok(audit_log) => audit_log,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return ExitCode::from(EXIT_ERROR);
+        }
+    };
+
+    let result = run(&cli);
+    if let Some(progress) = hxgrep::progress::get_progress() {
+        match &result {
+            Err(BingrepError::Io(e)) if e.kind() == io::ErrorKind::BrokenPipe => progress.finish(),
+            Err(e) => progress.fail(&e.to_string()),
+            Ok(_) => progress.finish(),
+        }
+    }
+
+    let status = match &result {
+        Ok(outcome) if outcome.success => 0,
+        Ok(_) => EXIT_NO_MATCH,
+        // The reader went away (e.g. `| head`); like grep, this is not an error
+        Err(BingrepError::Io(e)) if e.kind() == io::ErrorKind::BrokenPipe => 0,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            EXIT_ERROR
+        }
+    };
+
+    if let Some(audit_log) = audit_log {
+        let record = audit_record(&cli, started, &result, status);
+        if let Err(e) = audit_log.append(record) {
+            eprintln!("Error: could not write the audit log: {}", e);
+            return ExitCode::from(EXIT_ERROR);
         }
+    }
+    ExitCode::from(status)
+}
+
+/// Describe a finished run for `--audit-log`
+fn audit_record(
+    cli: &Cli,
+    started: SystemTime,
+    result: &Result<Outcome>,
+    status: u8,
+) -> AuditRecord {
+    let argv = std::env::args_os()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect();
+    let mut record = AuditRecord::new(started, argv);
+
+    // Expanded inputs of multi-file mode, or the paths given on the command line
+    let inputs = hxgrep::audit::recorded_inputs().unwrap_or_else(|| {
+        let paths = match &cli.command {
+            Some(Command::Entropy(args)) => vec![args.file_path.clone()],
+            Some(Command::Strings(args)) => vec![args.file_path.clone()],
+            Some(Command::Diff(args)) => vec![args.file_a.clone(), args.file_b.clone()],
+            Some(Command::Stats(args)) => vec![args.file_path.clone()],
+            Some(Command::Reverse(args)) => vec![args.input.clone()],
+            Some(Command::Index(args)) => vec![args.file_path.clone()],
+            Some(Command::Blockhash(args)) => std::iter::once(&args.file_path)
+                .chain(&args.hashsets)
+                .cloned()
+                .collect(),
+            Some(Command::Info(args)) => vec![args.file_path.clone()],
+            Some(Command::VerifyAudit(args)) => vec![args.log_path.clone()],
+            Some(Command::Report(args)) => args.file_paths.clone(),
+            None => cli.file_paths.clone(),
+        };
+        paths.into_iter().map(PathBuf::from).collect()
+    });
+    record.inputs = inputs.iter().map(AuditInput::describe).collect();
+
+    record.patterns = match &cli.command {
+        Some(Command::Strings(args)) => args.filter.iter().cloned().collect(),
+        Some(Command::Report(args)) => args.patterns.clone(),
+        Some(_) => Vec::new(),
+        None => cli.expression.iter().cloned().collect(),
     };
 
+    match result {
+        Ok(outcome) => record.matches = outcome.matches,
+        Err(e) if status == EXIT_ERROR => record.errors.push(e.to_string()),
+        Err(_) => {}
+    }
+    record.exit_status = status;
+    record
+}
+
+/// Run hxgrep and report what it found
+///
+/// Searches succeed only when there was at least one match; hex dumps and
+/// analysis subcommands always succeed. See [`run_command`] for subcommands.
+fn run(cli: &Cli) -> Result<Outcome> {
+    // Set global color choice
+    hxgrep::color_context::set_color_choice(cli.color.clone());
+    hxgrep::output_style::set_output_style(OutputStyle::from_cli(cli)?);
+    if !cli.inspect.is_empty() {
+        hxgrep::inspector::set_inspector(Inspector::new(&cli.inspect));
+    }
+    match cli.progress_format {
+        // Totals are added by each scan once its inputs are known
+        ProgressFormat::Json => hxgrep::progress::set_progress(ProgressIndicator::json(
+            progress_event_writer(cli.progress_fd)?,
+        )),
+        ProgressFormat::Bar if cli.progress_fd.is_some() => {
+            return Err(BingrepError::InvalidArgument(
+                "--progress-fd requires --progress-format json".to_string(),
+            ));
+        }
+        // The bar is drawn only on a terminal
+        ProgressFormat::Bar if cli.show_progress => hxgrep::progress::set_progress(
+            ProgressIndicator::new(0, ProgressIndicator::should_show_progress()),
+        ),
+        ProgressFormat::Bar => {}
+    }
+
+    if cli.threads > 0 {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(cli.threads)
+            .build_global()
+            .map_err(|e| BingrepError::InvalidArgument(format!("--threads: {}", e)))?;
+    }
+
+    if let Some(command) = &cli.command {
+        return run_command(command, cli);
+    }
+
+    if cli.file_paths.is_empty() && cli.files_from.is_none() {
+        // Clap will automatically show help when no file path is provided
+        eprintln!("사용법: hxgrep <파일경로>... [옵션]");
+        eprintln!("사용법: hxgrep - [옵션] < input_file (stdin)");
+        eprintln!("사용법: find . -print0 | hxgrep --files-from - -0 [옵션]");
+        eprintln!("도움말: hxgrep --help\n");
+        return Err(BingrepError::InvalidArgument(
+            "파일 경로가 필요합니다.".to_string(),
+        ));
+    }
+
+    // Structured formats and match extraction collect match records
+    // instead of streaming hex lines
+    let extracts = cli.only_matching || !cli.capture.is_empty();
+    if cli.multi_file && extracts {
+        return Err(BingrepError::InvalidArgument(
+            "--only-matching and --capture are not supported with --multi".to_string(),
+        ));
+    }
+    let resumable = cli.checkpoint.is_some() || cli.resume.is_some();
+    if resumable
+        && (cli.multi_file
+            || cli.file_paths.first().is_some_and(|path| path == "-")
+            || extracts
+            || cli.replace.is_some()
+            || ReportMode::from_cli(cli).is_some()
+            || !matches!(parse_output_format(cli)?, OutputFormat::Hex))
+    {
+        return Err(BingrepError::InvalidArgument(
+            "--checkpoint and --resume only support hex output searches of a single file"
+                .to_string(),
+        ));
+    }
+    if cli.index.is_some()
+        && (cli.multi_file
+            || cli.file_paths.first().is_some_and(|path| path == "-")
+            || cli.replace.is_some()
+            || resumable)
+    {
+        return Err(BingrepError::InvalidArgument(
+            "--index only supports searches of the single file it was built for".to_string(),
+        ));
+    }
+    if !cli.hash.is_empty() && (cli.multi_file || cli.replace.is_some()) {
+        return Err(BingrepError::InvalidArgument(
+            "--hash only supports searches and dumps of a single input".to_string(),
+        ));
+    }
+    if let Some(mode) = ReportMode::from_cli(cli) {
+        return handle_report_mode(cli, mode);
+    }
+
     // Handle multi-file processing
     if cli.multi_file {
-        let config = Config::default();
-        config.validate_cli(&cli)?;
+        if cli.replace.is_some() {
+            return Err(BingrepError::InvalidArgument(
+                "--replace cannot be combined with --multi".to_string(),
+            ));
+        }
+        return handle_multi_file(cli);
+    }
 
-        let multi_processor = MultiFileProcessor::new(config);
+    // Single input: file, forensic image or stdin
+    let file_path = cli.file_paths[0].clone();
+    if cli.expression.is_some()
+        && cli.replace.is_none()
+        && (extracts
+            || cli.index.is_some()
+            || cli.hash_matches.is_some()
+            || !matches!(parse_output_format(cli)?, OutputFormat::Hex))
+    {
+        return handle_structured_search(cli, &file_path);
+    }
+    if file_path == "-" {
+        // Handle stdin input
+        return handle_stdin_input(cli);
+    }
 
-        return multi_processor.process_files_by_glob(
-            &file_path,
-            cli.expression.as_deref(),
-            cli.line_width,
-            cli.limit,
-            &cli.separator,
-            !cli.hide_offset,
-            cli.parallel,
-            cli.chunk_size,
-            cli.global_limit,
-        );
+    if let Some(replacement) = &cli.replace {
+        return handle_replace(cli, &file_path, replacement);
     }
 
     // Create configuration and validate CLI parameters
-    let config = Config::default();
-    config.validate_cli(&cli)?;
+    let config = Config::from_cli(cli)?;
+    let alignment = config.alignment.clone();
+    let with_filename = config.with_filename;
+
+    let mut processor = FileProcessor::new(config).with_file_name(file_path.as_str());
 
-    let mut processor = FileProcessor::new(config);
+    // A resumed search only reports what is still missing, also toward -n
+    let mut checkpoint = open_checkpoint(cli, &file_path, alignment.as_ref())?;
+    let previous_matches = checkpoint.as_ref().map_or(0, Checkpointer::previous_matches);
+    if cli.limit > 0 && previous_matches >= cli.limit {
+        eprintln!("Checkpoint already holds {} matches, nothing to resume", previous_matches);
+        return Ok(Outcome::done());
+    }
+    let limit = if cli.limit > 0 { cli.limit - previous_matches } else { 0 };
+    let start_offset = checkpoint.as_ref().map_or(cli.position, Checkpointer::offset);
 
     // Check if this is a forensic image file (E01, VMDK) and handle accordingly
-    if hxgrep::forensic_image::is_forensic_image(&file_path) {
+    let outcome = if hxgrep::forensic_image::is_forensic_image(&file_path) {
         // Process forensic image file - parallel processing not supported for forensic images yet
         let format_name = hxgrep::forensic_image::get_format_name(&file_path)
             .unwrap_or("Unknown");
         eprintln!("Detected {} forensic image: {}", format_name, file_path);
 
-        if let Some(expression) = cli.expression {
-            let regex = RegexProcessor::compile_pattern(&expression)?;
-            processor.process_stream_by_regex_from_path(
-                &file_path,
+        let image = ForensicImageReader::new(&file_path)?;
+        let image_size = image.size();
+        add_progress_total(image_size);
+        let mut image = HashingReader::new(image, &input_hash_algorithms(cli))?;
+
+        let outcome = if let Some(expression) = &cli.expression {
+            let regex = RegexProcessor::compile_pattern(expression)?;
+            if let Some(checkpoint) = checkpoint {
+                processor = processor.with_checkpoint(checkpoint);
+            }
+            let match_count = processor.process_stream_by_regex(
+                &mut image,
                 &regex,
                 cli.line_width,
-                cli.limit,
+                limit,
                 &cli.separator,
                 !cli.hide_offset,
             )?;
+            Outcome {
+                success: previous_matches + match_count > 0,
+                matches: match_count as u64,
+            }
         } else {
-            processor.process_file_stream_from_path(
-                &file_path,
+            processor.process_file_stream(
+                &mut image,
                 cli.line_width,
                 cli.limit,
                 &cli.separator,
                 !cli.hide_offset,
+                image_size,
             )?;
-        }
+            Outcome::done()
+        };
+        finish_input_hashes(cli, &file_path, &mut image)?;
+        outcome
     } else {
         // Open regular file
-        let mut file = File::open(&file_path)?;
+        let file = File::open(&file_path)?;
         let file_size = file.metadata()?.len();
+        add_progress_total(file_size.saturating_sub(start_offset));
+        let mut file = HashingReader::new(file, &input_hash_algorithms(cli))?;
 
         // Seek to starting position
         file.seek(SeekFrom::Start(cli.position))?;
 
         // Process file with or without regex
-        if let Some(expression) = cli.expression {
-            let regex = RegexProcessor::compile_pattern(&expression)?;
+        let outcome = if let Some(expression) = &cli.expression {
+            let regex = RegexProcessor::compile_pattern(expression)?;
 
-            if cli.parallel && file_size > cli.chunk_size as u64 {
+            let match_count = if cli.parallel && file_size > cli.chunk_size as u64 {
                 // Use parallel processing for large files
                 ParallelProcessor::process_file_parallel(
                     &mut file,
                     &regex,
                     cli.chunk_size,
                     cli.line_width,
-                    cli.limit,
+                    limit,
                     &cli.separator,
                     !cli.hide_offset,
                     file_size,
-                )?;
+                    alignment.as_ref(),
+                    with_filename.then_some(file_path.as_str()),
+                    checkpoint.as_mut(),
+                    &mut io::stdout(),
+                )?
             } else {
                 // Use regular processing
+                if let Some(checkpoint) = checkpoint {
+                    processor = processor.with_checkpoint(checkpoint);
+                }
                 processor.process_stream_by_regex(
                     &mut file,
                     &regex,
                     cli.line_width,
-                    cli.limit,
+                    limit,
                     &cli.separator,
                     !cli.hide_offset,
-                )?;
+                )?
+            };
+            Outcome {
+                success: previous_matches + match_count > 0,
+                matches: match_count as u64,
             }
         } else {
             if cli.parallel && file_size > cli.chunk_size as u64 {
@@ -133,6 +405,7 @@ fn main() -> Result<()> {
                     &cli.separator,
                     !cli.hide_offset,
                     file_size,
+                    &mut io::stdout(),
                 )?;
             } else {
                 // Use regular processing
@@ -145,16 +418,949 @@ fn main() -> Result<()> {
                     file_size,
                 )?;
             }
+            Outcome::done()
+        };
+        finish_input_hashes(cli, &file_path, &mut file)?;
+        outcome
+    };
+
+    Ok(outcome)
+}
+
+/// Digests to compute while the inputs are read
+///
+/// `--hash` selects them; an audited run otherwise hashes with SHA-256, so
+/// its record can describe the inputs without reading them a second time.
+fn input_hash_algorithms(cli: &Cli) -> Vec<HashAlgorithm> {
+    if cli.hash.is_empty() && cli.audit_log.is_some() {
+        vec![HashAlgorithm::Sha256]
+    } else {
+        cli.hash.clone()
+    }
+}
+
+/// Open an input that is hashed with [`input_hash_algorithms`] while it is read
+fn open_hashed_input<P: AsRef<Path>>(
+    path: P,
+    cli: &Cli,
+) -> Result<HashingReader<Box<dyn InputReader>>> {
+    Ok(HashingReader::new(open_input(path)?, &input_hash_algorithms(cli))?)
+}
+
+/// Complete the digests of an input opened with [`input_hash_algorithms`]
+///
+/// They are reported with `--hash`; digests computed only for the audit log
+/// are recorded without being printed.
+fn finish_input_hashes<R: Read + Seek>(
+    cli: &Cli,
+    file_path: &str,
+    input: &mut HashingReader<R>,
+) -> Result<()> {
+    let hashes = input.finish()?;
+    if !cli.hash.is_empty() {
+        report_input_hashes(file_path, hashes);
+    } else if let Some(hashes) = hashes {
+        hxgrep::audit::record_input_hashes(file_path, &hashes);
+    }
+    Ok(())
+}
+
+/// Print the `--hash` digests of an input to stderr
+///
+/// For E01 images the digests are also compared with the ones the
+/// acquisition tool stored in the image; a mismatch is reported as a warning.
+fn report_input_hashes(file_path: &str, hashes: Option<RegionHashes>) {
+    let Some(hashes) = hashes else {
+        return;
+    };
+    for (algorithm, hash) in hashes.digests() {
+        eprintln!("{} ({}) = {}", algorithm.name().to_uppercase(), input_label(file_path), hash);
+    }
+    hxgrep::audit::record_input_hashes(file_path, &hashes);
+
+    if !hxgrep::forensic_image::is_e01_file(file_path) {
+        return;
+    }
+    let checks = match hxgrep::ewf::stored_hashes(Path::new(file_path)) {
+        Ok(stored) => stored.check(&hashes),
+        Err(e) => {
+            eprintln!("Warning: could not read the hashes stored in {}: {}", file_path, e);
+            return;
+        }
+    };
+    if checks.is_empty() {
+        eprintln!(
+            "Note: {} stores no hash for the selected algorithms to compare with",
+            file_path
+        );
+    }
+    for check in checks {
+        let name = check.algorithm.name().to_uppercase();
+        if check.is_match() {
+            eprintln!("{} verified: matches the hash stored in the image", name);
+        } else {
+            eprintln!(
+                "Warning: {} mismatch: computed {} but the image stores {}",
+                name, check.computed, check.stored
+            );
+        }
+    }
+}
+
+/// Open the destination of JSON progress events: stderr or an inherited descriptor
+fn progress_event_writer(fd: Option<i32>) -> Result<Box<dyn Write + Send>> {
+    use std::os::fd::FromRawFd;
+
+    let Some(fd) = fd else {
+        return Ok(Box::new(io::stderr()));
+    };
+    // SAFETY: fcntl only queries the descriptor's flags
+    if fd < 0 || unsafe { libc::fcntl(fd, libc::F_GETFD) } == -1 {
+        return Err(BingrepError::InvalidArgument(format!(
+            "--progress-fd {} is not an open file descriptor",
+            fd
+        )));
+    }
+    // SAFETY: the descriptor is open and handed to us by the parent process;
+    // the writer lives for the rest of the run
+    Ok(Box::new(unsafe { File::from_raw_fd(fd) }))
+}
+
+/// Set up `--checkpoint`/`--resume` for a search of `file_path`
+///
+/// A resume is refused when the input or the pattern differs from the
+/// checkpoint. Without `--checkpoint`, a resumed search keeps recording into
+/// the file it resumed from.
+fn open_checkpoint(
+    cli: &Cli,
+    file_path: &str,
+    alignment: Option<&Alignment>,
+) -> Result<Option<Checkpointer>> {
+    let Some(path) = cli.checkpoint.as_ref().or(cli.resume.as_ref()) else {
+        return Ok(None);
+    };
+
+    let input = InputIdentity::of(file_path)?;
+    let hash = pattern_hash(&[cli.expression.as_deref().unwrap_or_default()], alignment);
+    let state = match &cli.resume {
+        Some(resume) => {
+            let mut checkpoint = Checkpoint::load(resume)?;
+            checkpoint.check_resumable(&input, &hash)?;
+            // The input may have been mounted somewhere else since
+            checkpoint.input = input;
+            checkpoint
+        }
+        None => Checkpoint::new(input, hash, cli.position),
+    };
+
+    Ok(Some(Checkpointer::new(Some(PathBuf::from(path)), state)))
+}
+
+/// Dispatch analysis subcommands
+///
+/// Subcommands that look for something (`strings`, `blockhash`, `report` and
+/// `entropy` with thresholds) succeed only when they found it; `diff`
+/// succeeds only when the inputs are identical, like cmp(1). The others
+/// always succeed.
+fn run_command(command: &Command, cli: &Cli) -> Result<Outcome> {
+    match command {
+        Command::Entropy(args) => handle_entropy(args, cli),
+        Command::Strings(args) => handle_strings(args, cli),
+        Command::Diff(args) => handle_diff(args, cli),
+        Command::Stats(args) => handle_stats(args, cli),
+        Command::Reverse(args) => handle_reverse(args, cli),
+        Command::Index(args) => handle_index(args, cli),
+        Command::Blockhash(args) => handle_blockhash(args, cli),
+        Command::Info(args) => handle_info(args, cli),
+        Command::VerifyAudit(args) => handle_verify_audit(args),
+        Command::Report(args) => handle_report(args, cli),
+    }
+}
+
+/// Parse the `--format` option
+fn parse_output_format(cli: &Cli) -> Result<OutputFormat> {
+    OutputFormat::from_str(&cli.output_format).ok_or_else(|| {
+        BingrepError::InvalidArgument(format!(
+            "Unknown output format '{}' (expected hex, json, csv or plain)",
+            cli.output_format
+        ))
+    })
+}
+
+/// Handle the `entropy` subcommand
+fn handle_entropy(args: &EntropyArgs, cli: &Cli) -> Result<Outcome> {
+    let format = parse_output_format(cli)?;
+    let scanner = EntropyScanner::new(args.window, args.step.unwrap_or(args.window))?
+        .with_thresholds(args.high, args.low);
+
+    let mut input = open_hashed_input(&args.file_path, cli)?;
+    add_progress_total(input.seek(SeekFrom::End(0))?);
+    input.rewind()?;
+    let label = input_label(&args.file_path);
+    let blocks = scanner.scan(&mut input, label, cli.chunk_size, cli.parallel)?;
+    finish_input_hashes(cli, &args.file_path, &mut input)?;
+
+    let formatter = StructuredFormatter::new(format);
+    let mut stdout = io::stdout().lock();
+
+    // The map is a text rendering; structured formats get the block records instead
+    let text_map = args.map && matches!(format, OutputFormat::Hex | OutputFormat::Plain);
+    if text_map {
+        let max_offset = blocks.last().map(|b| b.offset).unwrap_or(0);
+        let hex_offset_length = OutputFormatter::calculate_hex_offset_length(max_offset);
+        for line in render_map(&blocks, args.map_width, hex_offset_length) {
+            writeln!(stdout, "{}", line)?;
+        }
+    }
+
+    // With thresholds, only regions beyond them count as found
+    if scanner.has_thresholds() {
+        let regions = scanner.merge_regions(&blocks);
+        formatter.output_records(&regions, &mut stdout)?;
+        return Ok(Outcome::found(regions.len()));
+    } else if !text_map {
+        formatter.output_records(&blocks, &mut stdout)?;
+    }
+
+    Ok(Outcome::done())
+}
+
+/// Handle the `strings` subcommand
+fn handle_strings(args: &StringsArgs, cli: &Cli) -> Result<Outcome> {
+    let format = parse_output_format(cli)?;
+    let extractor = StringExtractor::new(args.min_len, &args.encodings, args.filter.as_deref())?;
+
+    let mut input = open_hashed_input(&args.file_path, cli)?;
+    add_progress_total(input.seek(SeekFrom::End(0))?);
+    input.rewind()?;
+    let strings = extractor.extract(
+        &mut input,
+        input_label(&args.file_path),
+        cli.chunk_size,
+        cli.parallel,
+    )?;
+    finish_input_hashes(cli, &args.file_path, &mut input)?;
+
+    StructuredFormatter::new(format).output_records(&strings, &mut io::stdout().lock())?;
+    Ok(Outcome::found(strings.len()))
+}
+
+/// Handle the `diff` subcommand
+fn handle_diff(args: &DiffArgs, cli: &Cli) -> Result<Outcome> {
+    let format = parse_output_format(cli)?;
+    let differ = Differ::new(DiffOptions {
+        mode: args.mode,
+        merge_gap: args.merge_gap,
+        resync_window: args.resync_window,
+        sync_len: args.sync_len,
+        max_bytes: args.max_bytes,
+        chunk_size: cli.chunk_size,
+    })?;
+
+    let mut input_a = open_hashed_input(&args.file_a, cli)?;
+    let mut input_b = open_hashed_input(&args.file_b, cli)?;
+
+    let mut ranges = Vec::new();
+    let (label_a, label_b) = (input_label(&args.file_a), input_label(&args.file_b));
+    let summary = differ.diff(label_a, &mut input_a, label_b, &mut input_b, |range| {
+        if !args.summary {
+            ranges.push(range);
+        }
+    })?;
+    finish_input_hashes(cli, &args.file_a, &mut input_a)?;
+    finish_input_hashes(cli, &args.file_b, &mut input_b)?;
+    // Identical inputs succeed, like cmp(1)
+    let outcome = Outcome {
+        success: summary.ranges == 0,
+        matches: summary.ranges,
+    };
+
+    let formatter = StructuredFormatter::new(format);
+    let mut stdout = io::stdout().lock();
+
+    if args.summary {
+        formatter.output_records(std::slice::from_ref(&summary), &mut stdout)?;
+        return Ok(outcome);
+    }
+
+    if matches!(format, OutputFormat::Hex) {
+        let max_offset = summary.size_a.max(summary.size_b);
+        let hex_offset_length = OutputFormatter::calculate_hex_offset_length(max_offset);
+        let use_color = OutputFormatter::should_use_color(hxgrep::color_context::get_color_choice());
+        for range in &ranges {
+            writeln!(stdout, "{}", range.render(hex_offset_length, use_color))?;
         }
+    } else {
+        formatter.output_records(&ranges, &mut stdout)?;
+    }
+
+    eprintln!(
+        "{} differing range(s), {} / {} byte(s) changed",
+        summary.ranges, summary.changed_bytes_a, summary.changed_bytes_b
+    );
+    Ok(outcome)
+}
+
+/// Handle the `stats` subcommand
+fn handle_stats(args: &StatsArgs, cli: &Cli) -> Result<Outcome> {
+    let format = parse_output_format(cli)?;
+    let collector = StatsCollector::new(args.range_size)?;
+
+    let mut input = open_hashed_input(&args.file_path, cli)?;
+    add_progress_total(input.seek(SeekFrom::End(0))?);
+    input.rewind()?;
+    let result = collector.scan(&mut input, cli.chunk_size, cli.parallel)?;
+    finish_input_hashes(cli, &args.file_path, &mut input)?;
+
+    // Ranges first, then the whole input
+    let regions: Vec<_> = result
+        .ranges
+        .iter()
+        .map(|range| (range, StatsScope::Range))
+        .chain(std::iter::once((&result.total, StatsScope::Total)))
+        .collect();
+
+    let formatter = StructuredFormatter::new(format);
+    let mut stdout = io::stdout().lock();
+
+    if args.histogram {
+        let bins: Vec<_> = regions
+            .iter()
+            .flat_map(|(stats, scope)| stats.bins(input_label(&args.file_path), *scope))
+            .collect();
+        formatter.output_records(&bins, &mut stdout)?;
+    } else {
+        let reports: Vec<_> = regions
+            .iter()
+            .map(|(stats, scope)| stats.report(input_label(&args.file_path), *scope))
+            .collect();
+        formatter.output_records(&reports, &mut stdout)?;
+    }
+
+    Ok(Outcome::done())
+}
+
+/// Handle the `reverse` subcommand
+fn handle_reverse(args: &ReverseArgs, cli: &Cli) -> Result<Outcome> {
+    let input: Box<dyn BufRead> = if args.input == "-" {
+        Box::new(BufReader::new(io::stdin()))
+    } else {
+        Box::new(BufReader::new(File::open(&args.input)?))
+    };
+
+    let mut writer = match args.output.as_deref() {
+        None | Some("-") => ReverseWriter::stream(Box::new(BufWriter::new(io::stdout()))),
+        Some(path) if args.patch => ReverseWriter::patch(path)?,
+        Some(path) => ReverseWriter::create(path)?,
+    };
+
+    let style = DumpStyle {
+        radix: cli.offset_radix,
+        endian: cli.endian,
+    };
+    let format = reverse_dump(input, args.input_format, style, &mut writer)?;
+    let size = writer.finish()?;
+
+    if let (Some(path), Some(format)) = (&args.output, format) {
+        eprintln!("Wrote {} bytes to {} ({:?} dump)", size, path, format);
+    }
+    Ok(Outcome::done())
+}
+
+/// Handle the `index` subcommand
+fn handle_index(args: &IndexArgs, cli: &Cli) -> Result<Outcome> {
+    if args.file_path == "-" {
+        return Err(BingrepError::InvalidArgument(
+            "stdin cannot be indexed; give a file path".to_string(),
+        ));
     }
+    let builder = IndexBuilder::new(usize::from(args.ngram), args.block_size)?;
+    let output = args
+        .output
+        .clone()
+        .unwrap_or_else(|| format!("{}.hxidx", args.file_path));
 
+    let identity = InputIdentity::of(&args.file_path)?;
+    let mut input = open_hashed_input(&args.file_path, cli)?;
+    add_progress_total(input.seek(SeekFrom::End(0))?);
+    let header = builder.build(&mut input, identity, cli.parallel, &output)?;
+    finish_input_hashes(cli, &args.file_path, &mut input)?;
+
+    eprintln!(
+        "Indexed {} bytes of {} into {} ({} blocks of {} bytes, {} of {} {}-gram buckets, {} too common)",
+        header.input_size,
+        args.file_path,
+        output,
+        header.blocks,
+        header.block_size,
+        header.grams,
+        header.buckets,
+        header.ngram,
+        header.common
+    );
+    Ok(Outcome::done())
+}
+
+/// Handle the `blockhash` subcommand
+fn handle_blockhash(args: &BlockhashArgs, cli: &Cli) -> Result<Outcome> {
+    let format = parse_output_format(cli)?;
+    let mut known = KnownHashes::new(args.algorithm);
+    for hashset in &args.hashsets {
+        known.load(hashset)?;
+    }
+    let hasher = BlockHasher::new(&known, args.block_size)?;
+
+    let mut input = open_hashed_input(&args.file_path, cli)?;
+    let input_size = input.seek(SeekFrom::End(0))?;
+    add_progress_total(input_size.saturating_sub(args.block_offset));
+    input.seek(SeekFrom::Start(args.block_offset))?;
+    let label = input_label(&args.file_path);
+    let matches = hasher.scan(&mut input, label, cli.chunk_size, cli.parallel)?;
+    finish_input_hashes(cli, &args.file_path, &mut input)?;
+
+    StructuredFormatter::new(format).output_records(&matches, &mut io::stdout().lock())?;
+    eprintln!(
+        "{} of {} block(s) matched {} known {} hash(es)",
+        matches.len(),
+        input_size.saturating_sub(args.block_offset) / args.block_size,
+        known.len(),
+        args.algorithm.name()
+    );
+    Ok(Outcome::found(matches.len()))
+}
+
+/// Handle the `info` subcommand
+fn handle_info(args: &InfoArgs, cli: &Cli) -> Result<Outcome> {
+    let format = parse_output_format(cli)?;
+    let info = ImageInfo::read(&args.file_path)?;
+
+    let mut stdout = io::stdout().lock();
+    match format {
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut stdout, &info).map_err(io::Error::from)?;
+            writeln!(stdout)?;
+        }
+        OutputFormat::Hex | OutputFormat::Plain => {
+            for line in info.render() {
+                writeln!(stdout, "{}", line)?;
+            }
+        }
+        OutputFormat::Csv => {
+            return Err(BingrepError::InvalidArgument(
+                "info supports text (hex, plain) and json output".to_string(),
+            ));
+        }
+    }
+    Ok(Outcome::done())
+}
+
+/// Handle the `verify-audit` subcommand
+fn handle_verify_audit(args: &VerifyAuditArgs) -> Result<Outcome> {
+    let verification = hxgrep::audit::verify(&args.log_path)?;
+    for problem in &verification.problems {
+        println!("{}", problem);
+    }
+    if !verification.is_valid() {
+        return Err(BingrepError::InvalidArgument(format!(
+            "audit log {} failed verification: {} problem(s) in {} record(s)",
+            args.log_path,
+            verification.problems.len(),
+            verification.records
+        )));
+    }
+
+    println!("{}: {} record(s), hash chain intact", args.log_path, verification.records);
+    if let Some(last_hash) = &verification.last_hash {
+        println!("last record hash: {}", last_hash);
+    }
+    Ok(Outcome::done())
+}
+
+/// Handle the `report` subcommand
+///
+/// Every pattern is searched in every input; the input is hashed during the
+/// first pass and the context of each hit is read back from it.
+fn handle_report(args: &ReportArgs, cli: &Cli) -> Result<Outcome> {
+    let regexes = args
+        .patterns
+        .iter()
+        .map(|pattern| RegexProcessor::compile_pattern(pattern))
+        .collect::<Result<Vec<_>>>()?;
+    let command_line = std::env::args()
+        .map(|arg| if arg.contains(char::is_whitespace) { format!("\"{}\"", arg) } else { arg })
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let mut report = HtmlReport {
+        title: args.title.clone(),
+        case_number: args.case_number.clone(),
+        examiner: args.examiner.clone(),
+        generated: hxgrep::audit::format_time(SystemTime::now()),
+        command_line,
+        patterns: args.patterns.clone(),
+        inputs: Vec::new(),
+        hits: Vec::new(),
+        limit: args.limit,
+    };
+
+    for path in &args.file_paths {
+        let image = if hxgrep::forensic_image::is_forensic_image(path) {
+            Some(ImageInfo::read(path)?)
+        } else {
+            None
+        };
+        let mut input = HashingReader::new(open_input(path)?, &args.hash)?;
+        let size = input.seek(SeekFrom::End(0))?;
+
+        for (pattern, regex) in regexes.iter().enumerate() {
+            let collector = MatchCollector::new(regex, None)?;
+            add_progress_total(size);
+            input.seek(SeekFrom::Start(0))?;
+            let matches = collector.collect(&mut input, path, cli.chunk_size, cli.parallel, args.limit)?;
+            for record in matches {
+                let context = HitContext::read(&mut input, &record, args.context)?;
+                report.hits.push(ReportHit { pattern, record, context });
+            }
+        }
+
+        let hashes = input.finish()?;
+        let hash_checks = match (&image, &hashes) {
+            (Some(ImageInfo::Ewf(info)), Some(hashes)) => info.stored_hashes.check(hashes),
+            _ => Vec::new(),
+        };
+        report_input_hashes(path, hashes.clone());
+        report.inputs.push(ReportInput { path: path.clone(), size, hashes, hash_checks, image });
+    }
+
+    match args.output.as_deref() {
+        None | Some("-") => report.write(&mut io::stdout().lock())?,
+        Some(output) => {
+            let mut writer = BufWriter::new(File::create(output)?);
+            report.write(&mut writer)?;
+            writer.flush()?;
+            eprintln!(
+                "Wrote {} hit(s) of {} pattern(s) in {} input(s) to {}",
+                report.hits.len(),
+                report.patterns.len(),
+                report.inputs.len(),
+                output
+            );
+        }
+    }
+    Ok(Outcome::found(report.hits.len()))
+}
+
+/// Handle `--replace` patch mode
+fn handle_replace(cli: &Cli, file_path: &str, replacement: &str) -> Result<Outcome> {
+    let format = parse_output_format(cli)?;
+    let config = Config::from_cli(cli)?;
+    let expression = cli.expression.as_deref().unwrap_or_default();
+    let regex = RegexProcessor::compile_pattern(expression)?;
+
+    let options = ReplaceOptions {
+        same_length: cli.same_length,
+        max_replacements: cli.max_replacements,
+        start_offset: cli.position as usize,
+    };
+    let replacer = Replacer::new(regex, replacement, options)?;
+
+    let path = Path::new(file_path);
+    let replacements = patch_file(
+        path,
+        &replacer,
+        config.alignment.as_ref(),
+        cli.dry_run,
+        cli.backup,
+    )?;
+
+    StructuredFormatter::new(format).output_records(&replacements, &mut io::stdout().lock())?;
+
+    if cli.dry_run {
+        eprintln!(
+            "Dry run: {} replacement(s) in {} (no changes written)",
+            replacements.len(),
+            file_path
+        );
+    } else {
+        eprintln!("Replaced {} occurrence(s) in {}", replacements.len(), file_path);
+    }
+
+    Ok(Outcome::found(replacements.len()))
+}
+
+/// Run a regex search that collects match records (structured formats, `-o`, `--capture`)
+fn handle_structured_search(cli: &Cli, file_path: &str) -> Result<Outcome> {
+    let format = parse_output_format(cli)?;
+    let config = Config::from_cli(cli)?;
+    let expression = cli.expression.as_deref().unwrap_or_default();
+    let regex = RegexProcessor::compile_pattern(expression)?;
+
+    // Structured records carry all groups; hex output shows groups only on request
+    let groups = if !cli.capture.is_empty() || !matches!(format, OutputFormat::Hex) {
+        Some(GroupSelection::parse(&regex, &cli.capture)?)
+    } else {
+        None
+    };
+    // --hash alone only hashes the whole input
+    let match_hashes = if cli.hash_matches.is_some() { cli.hash.as_slice() } else { &[] };
+    let collector = MatchCollector::new(&regex, config.alignment.as_ref())?
+        .with_inspector(hxgrep::inspector::get_inspector())
+        .with_groups(groups)
+        .with_hashes(match_hashes, cli.hash_matches.unwrap_or(0));
+
+    let mut input = open_hashed_input(file_path, cli)?;
+    let input_size = input.seek(SeekFrom::End(0))?;
+    let matches = if cli.index.is_some() {
+        collect_indexed(cli, &collector, &regex, &mut input, file_path, cli.limit)?
+    } else {
+        add_progress_total(input_size.saturating_sub(cli.position));
+        input.seek(SeekFrom::Start(cli.position))?;
+        let label = input_label(file_path);
+        collector.collect(&mut input, label, cli.chunk_size, cli.parallel, cli.limit)?
+    };
+
+    if matches!(format, OutputFormat::Hex) {
+        let hex_offset_length = OutputFormatter::calculate_hex_offset_length(input_size);
+        let mut stdout = io::stdout().lock();
+        for m in &matches {
+            // Indexed hex searches print the same -w windows as the streaming search
+            if m.groups.is_none() && !cli.only_matching {
+                print_match_window(
+                    &mut stdout,
+                    m,
+                    &mut input,
+                    cli,
+                    config.alignment.as_ref(),
+                    config.with_filename,
+                )?;
+                continue;
+            }
+            print_match_record(
+                &mut stdout,
+                m,
+                cli,
+                config.alignment.as_ref(),
+                hex_offset_length,
+                config.with_filename,
+            )?;
+        }
+    } else {
+        StructuredFormatter::new(format).output_matches(&matches, &mut io::stdout().lock())?;
+    }
+
+    finish_input_hashes(cli, file_path, &mut input)?;
+    Ok(Outcome::found(matches.len()))
+}
+
+/// Collect matches with `--index`, verifying only the candidate regions of the input
+///
+/// Patterns without a literal the index can use are searched in full.
+fn collect_indexed<R: Read + Seek>(
+    cli: &Cli,
+    collector: &MatchCollector,
+    regex: &regex::bytes::Regex,
+    input: &mut R,
+    file_path: &str,
+    limit: usize,
+) -> Result<Vec<BinaryMatch>> {
+    let mut index = NgramIndex::open(cli.index.as_deref().unwrap_or_default())?;
+    index.check_input(&InputIdentity::of(file_path)?, input)?;
+
+    let ranges = match index.candidate_ranges(regex)? {
+        Some(ranges) => ranges,
+        None => {
+            eprintln!(
+                "Warning: the pattern has no literal of at least {} bytes to look up in the index; scanning the whole input",
+                index.header().ngram
+            );
+            std::iter::once(0..index.header().input_size).collect()
+        }
+    };
+    let ranges: Vec<_> = ranges
+        .into_iter()
+        .filter_map(|range| {
+            let start = range.start.max(cli.position);
+            (start < range.end).then_some(start..range.end)
+        })
+        .collect();
+
+    add_progress_total(ranges.iter().map(|range| range.end - range.start).sum());
+    let label = input_label(file_path);
+    collector.collect_ranges(input, label, &ranges, cli.chunk_size, cli.parallel, limit)
+}
+
+/// Search or dump every input of multi-file mode
+fn handle_multi_file(cli: &Cli) -> Result<Outcome> {
+    let config = Config::from_cli(cli)?;
+    let walk = WalkOptions::from_cli(cli)?;
+    let (files, missing) = multi_file_inputs(cli, &walk)?;
+
+    let multi_processor = MultiFileProcessor::new(config)
+        .with_walk_options(walk)
+        .with_file_order(cli.file_order)
+        .with_input_hashes(&input_hash_algorithms(cli));
+
+    // With -p, files are processed concurrently and each file's output is
+    // emitted as one block
+    let match_count = if cli.parallel {
+        multi_processor.process_files_parallel(
+            &files,
+            cli.expression.as_deref(),
+            cli.line_width,
+            cli.limit,
+            &cli.separator,
+            !cli.hide_offset,
+            cli.parallel,
+            cli.chunk_size,
+            cli.global_limit,
+        )?
+    } else {
+        multi_processor.process_files_by_list(
+            &files,
+            cli.expression.as_deref(),
+            cli.line_width,
+            cli.limit,
+            &cli.separator,
+            !cli.hide_offset,
+            cli.parallel,
+            cli.chunk_size,
+            cli.global_limit,
+        )?
+    };
+    check_missing_inputs(missing)?;
+    if cli.expression.is_none() {
+        return Ok(Outcome::done());
+    }
+    Ok(Outcome::found(match_count))
+}
+
+/// Expand the positional glob patterns and the `--files-from` list into files
+///
+/// Directories are walked with `-r`. A positional or listed path that does
+/// not exist is reported right away and counted, so the run can go on with
+/// the other inputs and still fail at the end (like grep).
+fn multi_file_inputs(cli: &Cli, walk: &WalkOptions) -> Result<(Vec<PathBuf>, usize)> {
+    let mut files = Vec::new();
+    let mut missing = Vec::new();
+    for pattern in &cli.file_paths {
+        match walk.expand(pattern) {
+            Ok(expanded) => files.extend(expanded),
+            Err(BingrepError::Io(e)) if e.kind() == io::ErrorKind::NotFound => {
+                eprintln!("Error: {}", e);
+                missing.push(PathBuf::from(pattern));
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    if let Some(source) = &cli.files_from {
+        for path in read_path_list(source, cli.null_separated)? {
+            if !path.exists() {
+                eprintln!("Error: {}: No such file or directory", path.display());
+                missing.push(path);
+                continue;
+            }
+            files.extend(walk.expand_path(&path)?);
+        }
+    }
+    hxgrep::audit::record_inputs(&files);
+    hxgrep::audit::record_inputs(&missing);
+    Ok((files, missing.len()))
+}
+
+/// Fail a multi-file run once its other inputs are done if any input path was missing
+fn check_missing_inputs(missing: usize) -> Result<()> {
+    if missing == 0 {
+        return Ok(());
+    }
+    Err(BingrepError::Io(io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("{} input(s) not found", missing),
+    )))
+}
+
+/// Handle `--count`, `--files-with-matches`, `--files-without-match` and `--quiet`
+///
+/// Inputs are the single path (file, forensic image or stdin) or, in
+/// multi-file mode, every file from the glob patterns and `--files-from`
+/// (walking directories with `-r`).
+fn handle_report_mode(cli: &Cli, mode: ReportMode) -> Result<Outcome> {
+    let config = Config::from_cli(cli)?;
+    let expression = cli.expression.as_deref().unwrap_or_default();
+    let regex = RegexProcessor::compile_pattern(expression)?;
+    let collector = MatchCollector::new(&regex, config.alignment.as_ref())?;
+
+    let (inputs, missing) = if cli.multi_file {
+        multi_file_inputs(cli, &WalkOptions::from_cli(cli)?)?
+    } else {
+        (vec![PathBuf::from(&cli.file_paths[0])], 0)
+    };
+
+    let progress = hxgrep::progress::get_progress();
+    if let Some(progress) = progress.filter(|_| cli.multi_file) {
+        let total_bytes = inputs
+            .iter()
+            .filter_map(|path| std::fs::metadata(path).ok())
+            .map(|metadata| metadata.len())
+            .sum();
+        progress.set_file_count(inputs.len());
+        progress.add_total(total_bytes);
+    }
+
+    let mut stdout = io::stdout().lock();
+    let mut outcome = Outcome {
+        success: false,
+        matches: 0,
+    };
+    for path in &inputs {
+        // The file is opened by its own path; the name is only displayed
+        let input_path = path.display().to_string();
+        let label = input_label(&input_path);
+        let mut input = open_hashed_input(path, cli)?;
+        let input_size = input.seek(SeekFrom::End(0))?;
+        let limit = mode.match_limit(cli.limit);
+        let count = if cli.index.is_some() {
+            collect_indexed(cli, &collector, &regex, &mut input, &input_path, limit)?.len()
+        } else {
+            match progress {
+                Some(progress) if cli.multi_file => progress.start_file(label, input_size),
+                _ => add_progress_total(input_size.saturating_sub(cli.position)),
+            }
+            input.seek(SeekFrom::Start(cli.position))?;
+            collector.count(&mut input, cli.chunk_size, cli.parallel, limit)?
+        };
+        finish_input_hashes(cli, &input_path, &mut input)?;
+
+        if let Some(line) = mode.report_line(label, config.with_filename, count) {
+            writeln!(stdout, "{}", line)?;
+        }
+        outcome.success |= mode.is_success(count);
+        outcome.matches += count as u64;
+
+        // Like grep, -q succeeds on a match even if some input was missing
+        if mode == ReportMode::Quiet && outcome.success {
+            return Ok(outcome);
+        }
+    }
+
+    check_missing_inputs(missing)?;
+    Ok(outcome)
+}
+
+/// Add bytes about to be scanned to the `--progress` total
+fn add_progress_total(bytes: u64) {
+    if let Some(progress) = hxgrep::progress::get_progress() {
+        progress.add_total(bytes);
+    }
+}
+
+/// Print a collected match as hex lines: the matched bytes, or one line per selected group
+fn print_match_record<W: Write>(
+    out: &mut W,
+    m: &BinaryMatch,
+    cli: &Cli,
+    alignment: Option<&Alignment>,
+    hex_offset_length: usize,
+    with_filename: bool,
+) -> Result<()> {
+    let format_line = |offset: u64, line: &str| {
+        OutputFormatter::format_line_with_match_highlight(
+            offset,
+            line,
+            !cli.hide_offset,
+            hex_offset_length,
+            hxgrep::color_context::get_color_choice(),
+            None,
+            None,
+            with_filename.then_some(m.file_path.as_str()),
+        )
+    };
+
+    let annotate = |hex_string: String, offset: u64, label: Option<&str>| {
+        let mut notes: Vec<String> = label.map(str::to_string).into_iter().collect();
+        notes.extend(alignment.map(|a| a.describe(offset)));
+        if notes.is_empty() {
+            hex_string
+        } else {
+            OutputFormatter::append_annotation(&hex_string, &notes.join(", "))
+        }
+    };
+
+    match &m.groups {
+        Some(groups) => {
+            for group in groups {
+                let hex_string =
+                    OutputFormatter::format_bytes_for_display(&group.bytes(), &cli.separator);
+                let line = annotate(hex_string, group.offset, Some(&group.name));
+                writeln!(out, "{}", format_line(group.offset, &line))?;
+            }
+        }
+        None => {
+            let hex_string = OutputFormatter::format_bytes_for_display(&m.bytes(), &cli.separator);
+            let line = annotate(hex_string, m.offset, None);
+            writeln!(out, "{}", format_line(m.offset, &line))?;
+        }
+    }
+
+    print_match_details(out, m)
+}
+
+/// Print the `-w` bytes starting at a collected match, like the streaming hex search
+///
+/// The offset column is as wide as the streaming search makes it, so an
+/// indexed search prints the same lines as a full scan.
+fn print_match_window<W: Write, R: Read + Seek>(
+    out: &mut W,
+    m: &BinaryMatch,
+    input: &mut R,
+    cli: &Cli,
+    alignment: Option<&Alignment>,
+    with_filename: bool,
+) -> Result<()> {
+    let window = read_chunk(input, m.offset, cli.line_width)?;
+    let mut hex_string = OutputFormatter::format_bytes_for_display(&window, &cli.separator);
+    if let Some(alignment) = alignment {
+        hex_string = OutputFormatter::append_annotation(&hex_string, &alignment.describe(m.offset));
+    }
+    writeln!(
+        out,
+        "{}",
+        OutputFormatter::format_line_with_match_highlight(
+            m.offset,
+            &hex_string,
+            !cli.hide_offset,
+            OutputFormatter::calculate_hex_offset_length(STREAM_OFFSET_SPAN),
+            hxgrep::color_context::get_color_choice(),
+            None,
+            None,
+            with_filename.then_some(m.file_path.as_str()),
+        )
+    )?;
+
+    print_match_details(out, m)
+}
+
+/// Print the `--inspect` values and `--hash-matches` digests of a collected match
+fn print_match_details<W: Write>(out: &mut W, m: &BinaryMatch) -> Result<()> {
+    if let Some(inspection) = &m.inspection {
+        for line in render_values(inspection) {
+            writeln!(out, "{}", line)?;
+        }
+    }
+    for line in m.hashes.iter().flat_map(render_hashes) {
+        writeln!(out, "{}", line)?;
+    }
     Ok(())
 }
 
 /// Handle stdin input processing
-fn handle_stdin_input(cli: &Cli) -> Result<()> {
-    let config = Config::default();
-    config.validate_cli(cli)?;
+fn handle_stdin_input(cli: &Cli) -> Result<Outcome> {
+    if cli.replace.is_some() {
+        return Err(BingrepError::InvalidArgument(
+            "--replace cannot patch stdin; give a file path".to_string(),
+        ));
+    }
+
+    let config = Config::from_cli(cli)?;
 
     // Read all data from stdin into a buffer
     let mut stdin_data = Vec::new();
@@ -162,52 +1368,86 @@ fn handle_stdin_input(cli: &Cli) -> Result<()> {
 
     if stdin_data.is_empty() {
         eprintln!("Warning: No data received from stdin");
-        return Ok(());
+        if cli.expression.is_none() {
+            return Ok(Outcome::done());
+        }
+        return Ok(Outcome::found(0));
     }
 
     let data_size = stdin_data.len() as u64;
+    add_progress_total(data_size);
 
     // Process data with or without regex
-    if let Some(expression) = &cli.expression {
+    let outcome = if let Some(expression) = &cli.expression {
         let regex = RegexProcessor::compile_pattern(expression)?;
-        process_stdin_with_regex(&stdin_data, &regex, cli, data_size)?;
+        let match_count = process_stdin_with_regex(
+            &stdin_data,
+            &regex,
+            cli,
+            data_size,
+            config.alignment.as_ref(),
+            config.with_filename.then_some(STDIN_LABEL),
+        )?;
+        hxgrep::progress::advance(data_size);
+        Outcome::found(match_count)
     } else {
         process_stdin_hex_dump(&stdin_data, cli, data_size)?;
-    }
+        hxgrep::progress::advance(data_size);
+        Outcome::done()
+    };
 
-    Ok(())
+    let algorithms = input_hash_algorithms(cli);
+    if !cli.hash.is_empty() {
+        report_input_hashes("-", Some(RegionHashes::of(&algorithms, &stdin_data)));
+    } else if !algorithms.is_empty() {
+        hxgrep::audit::record_input_hashes("-", &RegionHashes::of(&algorithms, &stdin_data));
+    }
+    Ok(outcome)
 }
 
-/// Process stdin data with regex search
+/// Process stdin data with regex search, returning the number of matches
 fn process_stdin_with_regex(
     data: &[u8],
     regex: &regex::bytes::Regex,
     cli: &Cli,
     data_size: u64,
-) -> Result<()> {
+    alignment: Option<&Alignment>,
+    file_name: Option<&str>,
+) -> Result<usize> {
     let hex_offset_length = OutputFormatter::calculate_hex_offset_length(data_size);
+    let matcher = AlignedMatcher::new(regex, alignment)?;
     let mut match_count = 0;
 
-    for mat in regex.find_iter(data) {
-        let match_offset = mat.start() as u64;
-        let end_pos = (mat.start() + cli.line_width).min(data.len());
-        let display_bytes = &data[mat.start()..end_pos];
+    for hit in matcher.find_iter(data, 0) {
+        let match_offset = hit.start as u64;
+        let end_pos = (hit.start + cli.line_width).min(data.len());
+        let display_bytes = &data[hit.start..end_pos];
 
-        let hex_string = OutputFormatter::format_bytes_as_hex(display_bytes, &cli.separator);
-        OutputFormatter::print_line(
+        let mut hex_string = OutputFormatter::format_bytes_for_display(display_bytes, &cli.separator);
+        if let Some(alignment) = matcher.alignment() {
+            hex_string =
+                OutputFormatter::append_annotation(&hex_string, &alignment.describe(match_offset));
+        }
+        OutputFormatter::print_line_with_match_highlight(
             match_offset,
             &hex_string,
             !cli.hide_offset,
             hex_offset_length,
+            hxgrep::color_context::get_color_choice(),
+            None,
+            None,
+            file_name,
         );
+        OutputFormatter::print_inspection(&data[hit.start..]);
 
         match_count += 1;
+        hxgrep::progress::record_matches(1);
         if cli.limit > 0 && match_count >= cli.limit {
             break;
         }
     }
 
-    Ok(())
+    Ok(match_count)
 }
 
 /// Process stdin data as hex dump
@@ -220,7 +1460,7 @@ fn process_stdin_hex_dump(data: &[u8], cli: &Cli, data_size: u64) -> Result<()>
         let end_pos = (pos + cli.line_width).min(data.len());
         let line_bytes = &data[pos..end_pos];
 
-        let hex_string = OutputFormatter::format_bytes_as_hex(line_bytes, &cli.separator);
+        let hex_string = OutputFormatter::format_bytes_for_display(line_bytes, &cli.separator);
         OutputFormatter::print_line(pos as u64, &hex_string, !cli.hide_offset, hex_offset_length);
 
         pos += cli.line_width;
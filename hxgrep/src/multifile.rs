@@ -1,28 +1,175 @@
+use crate::audit;
+use crate::cli::{FileOrder, HashAlgorithm};
 use crate::config::Config;
 use crate::error::Result;
+use crate::hashing::HashingReader;
 use crate::parallel::{ParallelHexDump, ParallelProcessor};
+use crate::progress;
 use crate::regex_processor::RegexProcessor;
 use crate::stream::FileProcessor;
-use glob::glob;
+use crate::walk::WalkOptions;
+use std::collections::BTreeMap;
 use std::fs::File;
-use std::path::Path;
+use std::io::{self, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// What [`MultiFileProcessor::process_with_budget`] did with a file
+enum Budgeted {
+    /// The file was processed
+    Done(FileSummary),
+    /// Other files hold the rest of the budget; try again once they are done
+    Deferred,
+    /// The global limit had been reached, so the file was not touched
+    Skipped,
+}
+
+/// Per-file outcome of a multi-file run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileSummary {
+    /// File that was processed
+    pub path: PathBuf,
+    /// Matches (regex mode) or lines (hex dump mode) printed for the file
+    pub count: usize,
+    /// Number of bytes read from the file
+    pub bytes_scanned: u64,
+}
+
+/// Match/line budget shared by every file of a multi-file run
+///
+/// Workers reserve part of the remaining budget before processing a file and
+/// hand back whatever they did not use. Reserving never blocks: a worker that
+/// finds the budget empty while other reservations are outstanding gets
+/// [`Reservation::Pending`] and the file is retried once they are released,
+/// so the limit is never exceeded and never left unused.
+#[derive(Debug)]
+pub struct GlobalLimit {
+    limit: usize,
+    state: Mutex<Budget>,
+}
+
+/// Outcome of [`GlobalLimit::reserve`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reservation {
+    /// Process the file with this per-file limit (0 for unlimited)
+    Granted(usize),
+    /// The budget is empty, but outstanding reservations may hand some back
+    Pending,
+    /// The global limit has been reached
+    Exhausted,
+}
+
+#[derive(Debug)]
+struct Budget {
+    remaining: usize,
+    outstanding: usize,
+}
+
+impl GlobalLimit {
+    /// Create a budget of `limit` matches/lines (0 for unlimited)
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            state: Mutex::new(Budget {
+                remaining: limit,
+                outstanding: 0,
+            }),
+        }
+    }
+
+    /// Whether the budget is unlimited
+    pub fn is_unlimited(&self) -> bool {
+        self.limit == 0
+    }
+
+    /// Reserve budget for one file without waiting for other files
+    pub fn reserve(&self, per_file_limit: usize) -> Reservation {
+        if self.is_unlimited() {
+            return Reservation::Granted(per_file_limit);
+        }
+
+        let mut budget = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if budget.remaining > 0 {
+            let take = if per_file_limit == 0 {
+                budget.remaining
+            } else {
+                per_file_limit.min(budget.remaining)
+            };
+            budget.remaining -= take;
+            budget.outstanding += 1;
+            Reservation::Granted(take)
+        } else if budget.outstanding > 0 {
+            Reservation::Pending
+        } else {
+            Reservation::Exhausted
+        }
+    }
+
+    /// Return the part of a reservation that was not used
+    pub fn release(&self, reserved: usize, used: usize) {
+        if self.is_unlimited() {
+            return;
+        }
+
+        let mut budget = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        budget.remaining += reserved.saturating_sub(used);
+        budget.outstanding -= 1;
+    }
+
+    /// Whether the whole budget has been used up
+    pub fn is_exhausted(&self) -> bool {
+        if self.is_unlimited() {
+            return false;
+        }
+        let budget = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        budget.remaining == 0 && budget.outstanding == 0
+    }
+}
 
 /// Multi-file processor for handling glob patterns and multiple files
 pub struct MultiFileProcessor {
     config: Config,
+    walk: WalkOptions,
+    file_order: FileOrder,
+    input_hashes: Vec<HashAlgorithm>,
 }
 
 impl MultiFileProcessor {
     /// Create a new MultiFileProcessor
     pub fn new(config: Config) -> Self {
-        Self { config }
+        Self {
+            config,
+            walk: WalkOptions::default(),
+            file_order: FileOrder::Input,
+            input_hashes: Vec::new(),
+        }
+    }
+
+    /// Set how directories among the inputs are walked
+    pub fn with_walk_options(mut self, walk: WalkOptions) -> Self {
+        self.walk = walk;
+        self
+    }
+
+    /// Set the order in which [`Self::process_files_parallel`] emits files
+    pub fn with_file_order(mut self, file_order: FileOrder) -> Self {
+        self.file_order = file_order;
+        self
+    }
+
+    /// Hash every file with `algorithms` while it is read and record the
+    /// digests for the audit log
+    pub fn with_input_hashes(mut self, algorithms: &[HashAlgorithm]) -> Self {
+        self.input_hashes = algorithms.to_vec();
+        self
     }
 
     /// Process multiple files using glob pattern
     ///
     /// # Arguments
     ///
-    /// * `pattern` - Glob pattern to match files (e.g., "*.bin", "data/**/*.txt")
+    /// * `pattern` - Glob pattern to match files (e.g., "*.bin", "data/**/*.txt");
+    ///   matched directories are walked when recursive walking is enabled
     /// * `expression` - Optional regex expression to search for
     /// * `line_width` - Number of bytes to display per line
     /// * `limit` - Maximum number of matches/lines per file (0 for unlimited)
@@ -31,6 +178,9 @@ impl MultiFileProcessor {
     /// * `parallel` - Whether to use parallel processing
     /// * `chunk_size` - Chunk size for parallel processing
     /// * `global_limit` - Global limit across all files (0 for unlimited)
+    ///
+    /// Returns the total number of matches (regex mode) or lines (hex dump mode) printed.
+    #[allow(clippy::too_many_arguments)]
     pub fn process_files_by_glob(
         &self,
         pattern: &str,
@@ -42,49 +192,25 @@ impl MultiFileProcessor {
         parallel: bool,
         chunk_size: usize,
         global_limit: usize,
-    ) -> Result<()> {
-        let paths = glob(pattern)?;
-        let mut total_processed = 0;
-
-        for path_result in paths {
-            let path = path_result?;
-
-            // Skip directories
-            if path.is_dir() {
-                continue;
-            }
-
-            println!("=== Processing: {} ===", path.display());
-
-            let processed_count = self.process_single_file(
-                &path,
-                expression,
-                line_width,
-                limit,
-                separator,
-                show_offset,
-                parallel,
-                chunk_size,
-            )?;
-
-            total_processed += processed_count;
-
-            // Check global limit
-            if global_limit > 0 && total_processed >= global_limit {
-                println!("=== Global limit of {} reached ===", global_limit);
-                break;
-            }
-        }
-
-        println!("=== Total matches/lines processed: {} ===", total_processed);
-        Ok(())
+    ) -> Result<usize> {
+        self.process_paths(
+            self.walk.expand(pattern)?,
+            expression,
+            line_width,
+            limit,
+            separator,
+            show_offset,
+            parallel,
+            chunk_size,
+            global_limit,
+        )
     }
 
     /// Process a list of specific files
     ///
     /// # Arguments
     ///
-    /// * `file_paths` - Vector of file paths to process
+    /// * `file_paths` - Paths of the files to process
     /// * `expression` - Optional regex expression to search for
     /// * `line_width` - Number of bytes to display per line
     /// * `limit` - Maximum number of matches/lines per file (0 for unlimited)
@@ -93,9 +219,12 @@ impl MultiFileProcessor {
     /// * `parallel` - Whether to use parallel processing
     /// * `chunk_size` - Chunk size for parallel processing
     /// * `global_limit` - Global limit across all files (0 for unlimited)
+    ///
+    /// Returns the total number of matches (regex mode) or lines (hex dump mode) printed.
+    #[allow(clippy::too_many_arguments)]
     pub fn process_files_by_list(
         &self,
-        file_paths: Vec<&str>,
+        file_paths: &[PathBuf],
         expression: Option<&str>,
         line_width: usize,
         limit: usize,
@@ -104,27 +233,50 @@ impl MultiFileProcessor {
         parallel: bool,
         chunk_size: usize,
         global_limit: usize,
-    ) -> Result<()> {
-        let mut total_processed = 0;
+    ) -> Result<usize> {
+        let mut paths = Vec::new();
 
+        // Missing files and directories (without recursion) are skipped with a warning
         for file_path in file_paths {
-            let path = Path::new(file_path);
-
-            // Skip if file doesn't exist or is a directory
-            if !path.exists() {
-                eprintln!("Warning: File {} does not exist, skipping", file_path);
-                continue;
-            }
+            paths.extend(self.walk.expand_path(file_path)?);
+        }
 
-            if path.is_dir() {
-                eprintln!("Warning: {} is a directory, skipping", file_path);
-                continue;
-            }
+        self.process_paths(
+            paths,
+            expression,
+            line_width,
+            limit,
+            separator,
+            show_offset,
+            parallel,
+            chunk_size,
+            global_limit,
+        )
+    }
 
-            println!("=== Processing: {} ===", path.display());
+    /// Process files one after another, sharing the global limit between them
+    #[allow(clippy::too_many_arguments)]
+    fn process_paths(
+        &self,
+        paths: Vec<PathBuf>,
+        expression: Option<&str>,
+        line_width: usize,
+        limit: usize,
+        separator: &str,
+        show_offset: bool,
+        parallel: bool,
+        chunk_size: usize,
+        global_limit: usize,
+    ) -> Result<usize> {
+        announce_progress(&paths);
+        let budget = GlobalLimit::new(global_limit);
+        let mut summaries = Vec::new();
 
-            let processed_count = self.process_single_file(
-                path,
+        for path in paths {
+            let summary = self.process_with_budget(
+                &path,
+                &budget,
+                &mut io::stdout(),
                 expression,
                 line_width,
                 limit,
@@ -133,24 +285,63 @@ impl MultiFileProcessor {
                 parallel,
                 chunk_size,
             )?;
+            // Files run one at a time, so no reservation is outstanding here
+            match summary {
+                Budgeted::Done(summary) => summaries.push(summary),
+                Budgeted::Deferred | Budgeted::Skipped => break,
+            }
+        }
 
-            total_processed += processed_count;
+        let mut out = io::stdout().lock();
+        Self::print_summary(&mut out, &summaries, &budget, expression.is_some())
+    }
+
+    /// Print the global limit notice, the per-file summary and the totals to
+    /// `out`, returning the total count
+    fn print_summary<W: Write>(
+        out: &mut W,
+        summaries: &[FileSummary],
+        budget: &GlobalLimit,
+        regex_mode: bool,
+    ) -> Result<usize> {
+        let unit = if regex_mode { "matches" } else { "lines" };
+        let total_processed: usize = summaries.iter().map(|s| s.count).sum();
+        let total_bytes: u64 = summaries.iter().map(|s| s.bytes_scanned).sum();
 
-            // Check global limit
-            if global_limit > 0 && total_processed >= global_limit {
-                println!("=== Global limit of {} reached ===", global_limit);
-                break;
+        if budget.is_exhausted() {
+            writeln!(out, "=== Global limit of {} reached ===", budget.limit)?;
+        }
+
+        if !summaries.is_empty() {
+            writeln!(out, "=== Summary ===")?;
+            for summary in summaries {
+                writeln!(
+                    out,
+                    "{}: {} {}, {} bytes scanned",
+                    summary.path.display(),
+                    summary.count,
+                    unit,
+                    summary.bytes_scanned
+                )?;
             }
         }
 
-        println!("=== Total matches/lines processed: {} ===", total_processed);
-        Ok(())
+        writeln!(out, "=== Total matches/lines processed: {} ===", total_processed)?;
+        writeln!(out, "=== Total bytes scanned: {} ===", total_bytes)?;
+        out.flush()?;
+        Ok(total_processed)
     }
 
-    /// Process a single file and return the number of matches/lines processed
-    fn process_single_file(
+    /// Process one file within the shared budget, writing its output to `out`
+    ///
+    /// The file is not touched when the budget is empty: it is deferred while
+    /// other files hold reservations and skipped once the limit is reached.
+    #[allow(clippy::too_many_arguments)]
+    fn process_with_budget<W: Write>(
         &self,
         path: &Path,
+        budget: &GlobalLimit,
+        out: &mut W,
         expression: Option<&str>,
         line_width: usize,
         limit: usize,
@@ -158,14 +349,58 @@ impl MultiFileProcessor {
         show_offset: bool,
         parallel: bool,
         chunk_size: usize,
-    ) -> Result<usize> {
-        let mut file = File::open(path)?;
+    ) -> Result<Budgeted> {
+        let file_limit = match budget.reserve(limit) {
+            Reservation::Granted(file_limit) => file_limit,
+            Reservation::Pending => return Ok(Budgeted::Deferred),
+            Reservation::Exhausted => return Ok(Budgeted::Skipped),
+        };
+
+        let result = self.process_single_file(
+            path,
+            out,
+            expression,
+            line_width,
+            file_limit,
+            separator,
+            show_offset,
+            parallel,
+            chunk_size,
+        );
+        let used = result.as_ref().map(|s| s.count).unwrap_or(0);
+        budget.release(file_limit, used);
+        result.map(Budgeted::Done)
+    }
+
+    /// Process a single file and return how many matches/lines were printed
+    ///
+    /// The `=== Processing ===` header and all lines are written to `out`.
+    #[allow(clippy::too_many_arguments)]
+    fn process_single_file<W: Write>(
+        &self,
+        path: &Path,
+        out: &mut W,
+        expression: Option<&str>,
+        line_width: usize,
+        limit: usize,
+        separator: &str,
+        show_offset: bool,
+        parallel: bool,
+        chunk_size: usize,
+    ) -> Result<FileSummary> {
+        let file = File::open(path)?;
         let file_size = file.metadata()?.len();
+        let mut file = HashingReader::new(file, &self.input_hashes)?;
 
-        if let Some(expr) = expression {
+        writeln!(out, "=== Processing: {} ===", path.display())?;
+        let file_name = path.display().to_string();
+        if let Some(progress) = progress::get_progress() {
+            progress.start_file(&file_name, file_size);
+        }
+
+        let count = if let Some(expr) = expression {
             // Regex search mode
             let regex = RegexProcessor::compile_pattern(expr)?;
-            let matches_before = Self::count_matches_in_output();
 
             if parallel && file_size > chunk_size as u64 {
                 ParallelProcessor::process_file_parallel(
@@ -177,9 +412,14 @@ impl MultiFileProcessor {
                     separator,
                     show_offset,
                     file_size,
-                )?;
+                    self.config.alignment.as_ref(),
+                    self.config.with_filename.then_some(file_name.as_str()),
+                    None,
+                    out,
+                )?
             } else {
-                let mut processor = FileProcessor::new(self.config.clone());
+                let mut processor = FileProcessor::with_writer(self.config.clone(), out)
+                    .with_file_name(file_name);
                 processor.process_stream_by_regex(
                     &mut file,
                     &regex,
@@ -187,64 +427,62 @@ impl MultiFileProcessor {
                     limit,
                     separator,
                     show_offset,
-                )?;
+                )?
             }
-
-            let matches_after = Self::count_matches_in_output();
-            Ok(matches_after - matches_before)
-        } else {
+        } else if parallel && file_size > chunk_size as u64 {
             // Hex dump mode
-            let lines_before = Self::count_lines_in_output();
-
-            if parallel && file_size > chunk_size as u64 {
-                ParallelHexDump::process_file_parallel(
-                    &mut file,
-                    chunk_size,
-                    line_width,
-                    limit,
-                    separator,
-                    show_offset,
-                    file_size,
-                )?;
-            } else {
-                let mut processor = FileProcessor::new(self.config.clone());
-                processor.process_file_stream(
-                    &mut file,
-                    line_width,
-                    limit,
-                    separator,
-                    show_offset,
-                    file_size,
-                )?;
-            }
-
-            let lines_after = Self::count_lines_in_output();
-            Ok(lines_after - lines_before)
+            ParallelHexDump::process_file_parallel(
+                &mut file,
+                chunk_size,
+                line_width,
+                limit,
+                separator,
+                show_offset,
+                file_size,
+                out,
+            )?
+        } else {
+            let mut processor = FileProcessor::with_writer(self.config.clone(), out);
+            processor.process_file_stream(
+                &mut file,
+                line_width,
+                limit,
+                separator,
+                show_offset,
+                file_size,
+            )?
+        };
+
+        // The reader stops early once the limit is hit, so its position is
+        // how far into the file the scan actually got
+        let bytes_scanned = file.stream_position()?.min(file_size);
+        if let Some(progress) = progress::get_progress() {
+            progress.finish_file(file_size, bytes_scanned);
+        }
+        if let Some(hashes) = file.finish()? {
+            audit::record_input_hashes(path, &hashes);
         }
-    }
-
-    /// Dummy function to count matches - in a real implementation,
-    /// this would capture output and count actual matches
-    fn count_matches_in_output() -> usize {
-        // This is a simplified implementation
-        // In practice, you'd want to capture stdout and count lines
-        0
-    }
 
-    /// Dummy function to count lines - in a real implementation,
-    /// this would capture output and count actual lines
-    fn count_lines_in_output() -> usize {
-        // This is a simplified implementation
-        // In practice, you'd want to capture stdout and count lines
-        0
+        Ok(FileSummary {
+            path: path.to_path_buf(),
+            count,
+            bytes_scanned,
+        })
     }
 
     /// Process multiple files in parallel
     ///
-    /// This method processes multiple files concurrently using rayon
+    /// This method processes multiple files concurrently using rayon, sharing
+    /// `global_limit` (0 for unlimited) between the workers, and returns the
+    /// total number of matches (regex mode) or lines (hex dump mode) printed.
+    ///
+    /// Each file's output is buffered and written as one block, in input order
+    /// or in completion order depending on the configured [`FileOrder`], so
+    /// lines from different files never interleave.
+    #[allow(clippy::too_many_arguments)]
     pub fn process_files_parallel(
         &self,
-        file_paths: Vec<&str>,
+        file_paths: &[PathBuf],
         expression: Option<&str>,
         line_width: usize,
         limit: usize,
@@ -252,39 +490,194 @@ impl MultiFileProcessor {
         show_offset: bool,
         parallel_processing: bool,
         chunk_size: usize,
-    ) -> Result<()> {
+        global_limit: usize,
+    ) -> Result<usize> {
         use rayon::prelude::*;
 
-        let results: Vec<Result<()>> = file_paths
+        let mut paths = Vec::new();
+        for file_path in file_paths {
+            paths.extend(self.walk.expand_path(file_path)?);
+        }
+
+        announce_progress(&paths);
+        let budget = GlobalLimit::new(global_limit);
+        let output = OrderedOutput::new(self.file_order);
+
+        let process = |index: usize, path: &PathBuf| -> Result<Budgeted> {
+            let mut block = Vec::new();
+            let result = self.process_with_budget(
+                path,
+                &budget,
+                &mut block,
+                expression,
+                line_width,
+                limit,
+                separator,
+                show_offset,
+                parallel_processing,
+                chunk_size,
+            );
+            // Always emit, even when empty, so later files are not held back;
+            // a deferred file emits when it is retried
+            if !matches!(result, Ok(Budgeted::Deferred)) {
+                output.emit(index, block)?;
+            }
+            result
+        };
+
+        // Workers never wait on each other for budget, so nested rayon work
+        // inside a file cannot deadlock the pool
+        let results: Vec<Result<Budgeted>> = paths
             .par_iter()
-            .map(|file_path| {
-                let path = Path::new(file_path);
+            .enumerate()
+            .map(|(index, path)| process(index, path))
+            .collect();
 
-                if !path.exists() || path.is_dir() {
-                    return Ok(());
+        // Every reservation has been released by now, so deferred files get
+        // whatever budget is left, in input order
+        let mut summaries = Vec::new();
+        for (index, result) in results.into_iter().enumerate() {
+            let result = match result {
+                Ok(Budgeted::Deferred) => process(index, &paths[index]),
+                result => result,
+            };
+            if let Budgeted::Done(summary) = result? {
+                summaries.push(summary);
+            }
+        }
+
+        let mut out = io::stdout().lock();
+        Self::print_summary(&mut out, &summaries, &budget, expression.is_some())
+    }
+}
+
+/// Announce the files of a run and their total size to the `--progress` indicator
+fn announce_progress(paths: &[PathBuf]) {
+    if let Some(progress) = progress::get_progress() {
+        let total_bytes = paths
+            .iter()
+            .filter_map(|path| path.metadata().ok())
+            .map(|metadata| metadata.len())
+            .sum();
+        progress.set_file_count(paths.len());
+        progress.add_total(total_bytes);
+    }
+}
+
+/// Writes per-file output blocks to stdout without interleaving them
+struct OrderedOutput {
+    order: FileOrder,
+    state: Mutex<PendingBlocks>,
+}
+
+/// Blocks that finished ahead of their turn (input order only)
+struct PendingBlocks {
+    next: usize,
+    blocks: BTreeMap<usize, Vec<u8>>,
+}
+
+impl OrderedOutput {
+    fn new(order: FileOrder) -> Self {
+        Self {
+            order,
+            state: Mutex::new(PendingBlocks {
+                next: 0,
+                blocks: BTreeMap::new(),
+            }),
+        }
+    }
+
+    /// Hand over the output of file `index`
+    ///
+    /// In input order the block is held back until every earlier file has
+    /// been emitted; in completion order it is written right away.
+    fn emit(&self, index: usize, block: Vec<u8>) -> io::Result<()> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let mut stdout = io::stdout().lock();
+
+        match self.order {
+            FileOrder::Completion => stdout.write_all(&block)?,
+            FileOrder::Input => {
+                state.blocks.insert(index, block);
+                loop {
+                    let next = state.next;
+                    match state.blocks.remove(&next) {
+                        Some(ready) => stdout.write_all(&ready)?,
+                        None => break,
+                    }
+                    state.next += 1;
                 }
+            }
+        }
 
-                println!("=== Processing: {} ===", path.display());
+        stdout.flush()
+    }
+}
 
-                self.process_single_file(
-                    path,
-                    expression,
-                    line_width,
-                    limit,
-                    separator,
-                    show_offset,
-                    parallel_processing,
-                    chunk_size,
-                )
-                .map(|_| ())
-            })
-            .collect();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_global_limit_unlimited_passes_per_file_limit() {
+        let budget = GlobalLimit::new(0);
+        assert_eq!(budget.reserve(5), Reservation::Granted(5));
+        assert_eq!(budget.reserve(0), Reservation::Granted(0));
+        assert!(!budget.is_exhausted());
+    }
+
+    #[test]
+    fn test_global_limit_reserve_and_release() {
+        let budget = GlobalLimit::new(5);
+
+        // Per-file limit is capped by the remaining budget
+        assert_eq!(budget.reserve(3), Reservation::Granted(3));
+        assert_eq!(budget.reserve(3), Reservation::Granted(2));
+        // Empty, but outstanding reservations may still hand budget back
+        assert_eq!(budget.reserve(3), Reservation::Pending);
+        budget.release(2, 2);
+        assert_eq!(budget.reserve(3), Reservation::Pending);
+        budget.release(3, 1);
+        assert_eq!(budget.reserve(0), Reservation::Granted(2));
+        budget.release(2, 2);
+
+        assert!(budget.is_exhausted());
+        assert_eq!(budget.reserve(0), Reservation::Exhausted);
+    }
+
+    #[test]
+    fn test_print_summary_reports_closed_pipe() {
+        struct ClosedPipe;
+        impl Write for ClosedPipe {
+            fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+                Err(io::ErrorKind::BrokenPipe.into())
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
 
-        // Check for any errors
-        for result in results {
-            result?;
+        let summaries = [FileSummary {
+            path: PathBuf::from("a.bin"),
+            count: 2,
+            bytes_scanned: 10,
+        }];
+        let budget = GlobalLimit::new(0);
+        match MultiFileProcessor::print_summary(&mut ClosedPipe, &summaries, &budget, true) {
+            Err(crate::BingrepError::Io(e)) => assert_eq!(e.kind(), io::ErrorKind::BrokenPipe),
+            other => panic!("unexpected result: {:?}", other),
         }
 
-        Ok(())
+        let mut out = Vec::new();
+        let budget = GlobalLimit::new(2);
+        assert_eq!(budget.reserve(0), Reservation::Granted(2));
+        budget.release(2, 2);
+        assert_eq!(
+            MultiFileProcessor::print_summary(&mut out, &summaries, &budget, true).unwrap(),
+            2
+        );
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.starts_with("=== Global limit of 2 reached ===\n=== Summary ===\n"));
+        assert!(text.contains("a.bin: 2 matches, 10 bytes scanned"));
     }
 }
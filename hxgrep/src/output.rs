@@ -1,6 +1,7 @@
 /// Utilities for formatting binary data as hexadecimal output
 use colored::*;
 use crate::cli::ColorChoice;
+use crate::output_style;
 use std::io::IsTerminal;
 
 pub struct OutputFormatter;
@@ -15,14 +16,24 @@ impl OutputFormatter {
             .join(separator)
     }
 
+    /// Format bytes for hex dump and match lines
+    ///
+    /// Applies the global output style (hex case, word grouping and word
+    /// endianness); groups are joined with `separator`.
+    pub fn format_bytes_for_display(bytes: &[u8], separator: &str) -> String {
+        output_style::get_output_style().format_bytes(bytes, separator)
+    }
+
     /// Format offset with proper padding based on file size
+    ///
+    /// Radix and notation follow the global output style (`0010h` by default).
     pub fn format_offset(offset: u64, hex_offset_length: usize) -> String {
-        format!("{:0width$X}h", offset, width = hex_offset_length)
+        output_style::get_output_style().format_offset(offset, hex_offset_length)
     }
 
-    /// Calculate the number of digits needed for hex offset display
+    /// Calculate the number of digits needed for offset display
     pub fn calculate_hex_offset_length(file_size: u64) -> usize {
-        format!("{:X}", file_size).len()
+        output_style::get_output_style().offset_width(file_size)
     }
 
     /// Print a line with optional offset
@@ -37,6 +48,9 @@ impl OutputFormatter {
     }
 
     /// Print a line with optional offset and color support, with match highlighting
+    ///
+    /// `file_name` is printed in front of the line (`-H`) when given.
+    #[allow(clippy::too_many_arguments)]
     pub fn print_line_with_match_highlight(
         offset: u64,
         hex_data: &str,
@@ -45,31 +59,65 @@ impl OutputFormatter {
         color_choice: &ColorChoice,
         match_start: Option<usize>,
         match_length: Option<usize>,
+        file_name: Option<&str>,
     ) {
-        let should_use_color = match color_choice {
-            ColorChoice::Always => true,
-            ColorChoice::Never => false,
-            ColorChoice::Auto => std::io::stdout().is_terminal(),
-        };
+        println!(
+            "{}",
+            Self::format_line_with_match_highlight(
+                offset,
+                hex_data,
+                show_offset,
+                hex_offset_length,
+                color_choice,
+                match_start,
+                match_length,
+                file_name,
+            )
+        );
+    }
 
-        if show_offset {
+    /// Format a line with optional offset and color support, with match highlighting
+    /// (returns a string instead of printing)
+    #[allow(clippy::too_many_arguments)]
+    pub fn format_line_with_match_highlight(
+        offset: u64,
+        hex_data: &str,
+        show_offset: bool,
+        hex_offset_length: usize,
+        color_choice: &ColorChoice,
+        match_start: Option<usize>,
+        match_length: Option<usize>,
+        file_name: Option<&str>,
+    ) -> String {
+        let should_use_color = Self::should_use_color(color_choice);
+
+        let line = if show_offset {
             let offset_str = Self::format_offset(offset, hex_offset_length);
 
             if should_use_color {
-                println!(
+                format!(
                     "{} : {}",
                     offset_str.cyan().bold(),
                     Self::colorize_hex_data_with_match(hex_data, match_start, match_length)
-                );
+                )
             } else {
-                println!("{} : {}", offset_str, hex_data);
+                format!("{} : {}", offset_str, hex_data)
             }
+        } else if should_use_color {
+            Self::colorize_hex_data_with_match(hex_data, match_start, match_length)
         } else {
-            if should_use_color {
-                println!("{}", Self::colorize_hex_data_with_match(hex_data, match_start, match_length));
-            } else {
-                println!("{}", hex_data);
-            }
+            hex_data.to_string()
+        };
+
+        Self::prefix_file_name(line, file_name, should_use_color)
+    }
+
+    /// Put `file_name:` in front of a formatted line (file name in magenta with color)
+    pub fn prefix_file_name(line: String, file_name: Option<&str>, use_color: bool) -> String {
+        match file_name {
+            Some(name) if use_color => format!("{}:{}", name.magenta(), line),
+            Some(name) => format!("{}:{}", name, line),
+            None => line,
         }
     }
 
@@ -89,29 +137,44 @@ impl OutputFormatter {
             color_choice,
             None,
             None,
+            None,
         );
     }
 
+    /// Whether stdout output should be colored for the given choice
+    pub fn should_use_color(color_choice: &ColorChoice) -> bool {
+        match color_choice {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => std::io::stdout().is_terminal(),
+        }
+    }
+
     /// Apply colors to hex data with match highlighting
     fn colorize_hex_data_with_match(
         hex_data: &str,
         match_start: Option<usize>,
         match_length: Option<usize>,
     ) -> String {
-        let bytes: Vec<&str> = hex_data.split_whitespace().collect();
+        // Match positions are in bytes; each displayed word covers `group` bytes
+        let group = output_style::get_output_style().group_size.max(1);
+        let highlighted: Vec<bool> = (0..hex_data.split_whitespace().count())
+            .map(|i| match (match_start, match_length) {
+                (Some(start), Some(len)) => i * group < start + len && start < (i + 1) * group,
+                _ => false,
+            })
+            .collect();
 
-        bytes
-            .iter()
+        Self::colorize_hex_data_with_mask(hex_data, &highlighted)
+    }
+
+    /// Apply colors to hex data, highlighting the bytes flagged in `highlighted`
+    fn colorize_hex_data_with_mask(hex_data: &str, highlighted: &[bool]) -> String {
+        hex_data
+            .split_whitespace()
             .enumerate()
             .map(|(i, byte)| {
-                // Check if this byte is part of a match
-                let is_match = if let (Some(start), Some(len)) = (match_start, match_length) {
-                    i >= start && i < start + len
-                } else {
-                    false
-                };
-
-                if is_match {
+                if highlighted.get(i).copied().unwrap_or(false) {
                     // Highlight matches with dark red color
                     byte.red().bold().to_string()
                 } else {
@@ -144,6 +207,25 @@ impl OutputFormatter {
             .join(" ")
     }
 
+    /// Print the `--inspect` block for the bytes at a match, if enabled
+    pub fn print_inspection(bytes: &[u8]) {
+        for line in Self::inspection_lines(bytes) {
+            println!("{}", line);
+        }
+    }
+
+    /// Lines of the `--inspect` block for the bytes at a match (empty if disabled)
+    pub fn inspection_lines(bytes: &[u8]) -> Vec<String> {
+        crate::inspector::get_inspector()
+            .map(|inspector| inspector.render(bytes))
+            .unwrap_or_default()
+    }
+
+    /// Append an annotation (e.g. LBA/cluster information) to formatted hex data
+    pub fn append_annotation(hex_data: &str, annotation: &str) -> String {
+        format!("{}  [{}]", hex_data, annotation)
+    }
+
     /// Format a line with offset (returns a string instead of printing)
     pub fn format_line_with_offset(
         offset: u64,
@@ -156,6 +238,28 @@ impl OutputFormatter {
             hex_data
         )
     }
+
+    /// Format a line with offset, highlighting the bytes flagged in `highlighted`
+    ///
+    /// `hex_data` must use whitespace as byte separator. Without color this is
+    /// the same as [`Self::format_line_with_offset`].
+    pub fn format_line_with_highlight(
+        offset: u64,
+        hex_data: &str,
+        hex_offset_length: usize,
+        highlighted: &[bool],
+        use_color: bool,
+    ) -> String {
+        if !use_color {
+            return Self::format_line_with_offset(offset, hex_data, hex_offset_length);
+        }
+
+        format!(
+            "{} : {}",
+            Self::format_offset(offset, hex_offset_length).cyan().bold(),
+            Self::colorize_hex_data_with_mask(hex_data, highlighted)
+        )
+    }
 }
 
 #[cfg(test)]
@@ -183,6 +287,16 @@ mod tests {
         assert_eq!(no_separator, "00FF42");
     }
 
+    #[test]
+    fn test_prefix_file_name() {
+        let line = "0010h : 4D 5A".to_string();
+        assert_eq!(
+            OutputFormatter::prefix_file_name(line.clone(), Some("a.bin"), false),
+            "a.bin:0010h : 4D 5A"
+        );
+        assert_eq!(OutputFormatter::prefix_file_name(line.clone(), None, false), line);
+    }
+
     #[test]
     fn test_format_offset() {
         let result = OutputFormatter::format_offset(0x1234, 6);
@@ -0,0 +1,159 @@
+//! Global output style for offsets and hex bytes
+//!
+//! Controls the offset radix and notation, hex digit case and byte grouping
+//! (2/4/8-byte words shown big- or little-endian, like `xxd -g -e`).
+
+use crate::cli::{Cli, OffsetNotation, OffsetRadix, WordEndian};
+use crate::error::{BingrepError, Result};
+use std::sync::OnceLock;
+
+static OUTPUT_STYLE: OnceLock<OutputStyle> = OnceLock::new();
+
+/// Formatting options for offsets and displayed bytes
+#[derive(Debug, Clone)]
+pub struct OutputStyle {
+    pub radix: OffsetRadix,
+    pub notation: OffsetNotation,
+    pub lowercase: bool,
+    pub group_size: usize,
+    pub endian: WordEndian,
+}
+
+impl Default for OutputStyle {
+    fn default() -> Self {
+        Self {
+            radix: OffsetRadix::Hex,
+            notation: OffsetNotation::Suffix,
+            lowercase: false,
+            group_size: 1,
+            endian: WordEndian::Big,
+        }
+    }
+}
+
+impl OutputStyle {
+    /// Build the style from CLI options
+    pub fn from_cli(cli: &Cli) -> Result<Self> {
+        if !matches!(cli.group_size, 1 | 2 | 4 | 8) {
+            return Err(BingrepError::InvalidArgument(format!(
+                "--group must be 1, 2, 4 or 8 (got {})",
+                cli.group_size
+            )));
+        }
+
+        Ok(Self {
+            radix: cli.offset_radix.unwrap_or(OffsetRadix::Hex),
+            notation: cli.offset_notation,
+            lowercase: cli.lowercase,
+            group_size: cli.group_size,
+            endian: cli.endian.unwrap_or(WordEndian::Big),
+        })
+    }
+
+    /// Digits of `offset` in the configured radix, zero padded to `width`
+    fn offset_digits(&self, offset: u64, width: usize) -> String {
+        match (self.radix, self.lowercase) {
+            (OffsetRadix::Hex, false) => format!("{:0width$X}", offset, width = width),
+            (OffsetRadix::Hex, true) => format!("{:0width$x}", offset, width = width),
+            (OffsetRadix::Dec, _) => format!("{:0width$}", offset, width = width),
+            (OffsetRadix::Oct, _) => format!("{:0width$o}", offset, width = width),
+        }
+    }
+
+    /// Format an offset, e.g. `0010h`, `0x0010`, `16` or `020o`
+    pub fn format_offset(&self, offset: u64, width: usize) -> String {
+        let digits = self.offset_digits(offset, width);
+        match (self.notation, self.radix) {
+            (OffsetNotation::None, _) | (_, OffsetRadix::Dec) => digits,
+            (OffsetNotation::Suffix, OffsetRadix::Hex) => format!("{}h", digits),
+            (OffsetNotation::Suffix, OffsetRadix::Oct) => format!("{}o", digits),
+            (OffsetNotation::Prefix, OffsetRadix::Hex) => format!("0x{}", digits),
+            (OffsetNotation::Prefix, OffsetRadix::Oct) => format!("0o{}", digits),
+        }
+    }
+
+    /// Number of digits needed to show offsets up to `max_offset`
+    pub fn offset_width(&self, max_offset: u64) -> usize {
+        self.offset_digits(max_offset, 0).len()
+    }
+
+    /// Format bytes for display, grouped into words joined by `separator`
+    ///
+    /// With little-endian display the bytes of each word are reversed, so a
+    /// 4-byte group of `01 02 03 04` reads `04030201`. A trailing partial
+    /// group is shown with the bytes that are available.
+    pub fn format_bytes(&self, bytes: &[u8], separator: &str) -> String {
+        bytes
+            .chunks(self.group_size.max(1))
+            .map(|group| {
+                let mut word = String::with_capacity(group.len() * 2);
+                let mut push = |b: &u8| {
+                    if self.lowercase {
+                        word.push_str(&format!("{:02x}", b));
+                    } else {
+                        word.push_str(&format!("{:02X}", b));
+                    }
+                };
+                match self.endian {
+                    WordEndian::Big => group.iter().for_each(&mut push),
+                    WordEndian::Little => group.iter().rev().for_each(&mut push),
+                }
+                word
+            })
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+}
+
+/// Set the global output style
+pub fn set_output_style(style: OutputStyle) {
+    OUTPUT_STYLE.set(style).ok();
+}
+
+/// Get the current output style (defaults to uppercase hex offsets with `h`, single bytes)
+pub fn get_output_style() -> &'static OutputStyle {
+    static DEFAULT: OnceLock<OutputStyle> = OnceLock::new();
+    OUTPUT_STYLE
+        .get()
+        .unwrap_or_else(|| DEFAULT.get_or_init(OutputStyle::default))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offset_radix_and_notation() {
+        let mut style = OutputStyle::default();
+        assert_eq!(style.format_offset(0x1A, 4), "001Ah");
+
+        style.lowercase = true;
+        style.notation = OffsetNotation::Prefix;
+        assert_eq!(style.format_offset(0x1A, 4), "0x001a");
+
+        style.radix = OffsetRadix::Dec;
+        assert_eq!(style.format_offset(26, 4), "0026");
+        assert_eq!(style.offset_width(1000), 4);
+
+        style.radix = OffsetRadix::Oct;
+        style.notation = OffsetNotation::Suffix;
+        assert_eq!(style.format_offset(8, 3), "010o");
+
+        style.notation = OffsetNotation::None;
+        assert_eq!(style.format_offset(8, 0), "10");
+    }
+
+    #[test]
+    fn test_byte_grouping_and_endianness() {
+        let bytes = [0x01, 0x02, 0x03, 0x04, 0xAB, 0xCD];
+        let mut style = OutputStyle::default();
+        assert_eq!(style.format_bytes(&bytes, " "), "01 02 03 04 AB CD");
+
+        style.group_size = 4;
+        assert_eq!(style.format_bytes(&bytes, " "), "01020304 ABCD");
+
+        style.endian = WordEndian::Little;
+        style.lowercase = true;
+        assert_eq!(style.format_bytes(&bytes, "-"), "04030201-cdab");
+    }
+}
@@ -1,9 +1,230 @@
+use crate::alignment::{AlignedMatcher, Alignment};
+use crate::checkpoint::Checkpointer;
 use crate::error::Result;
 use crate::output::OutputFormatter;
+use crate::progress;
 use rayon::prelude::*;
 use regex::bytes::Regex;
-use std::fs::File;
-use std::io::{Read, Seek, SeekFrom};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+
+/// Read up to `len` bytes starting at `offset`
+///
+/// Keeps reading until the chunk is full or the reader is exhausted, so the
+/// returned buffer is only shorter than `len` at the end of the input.
+pub fn read_chunk<R: Read + Seek>(reader: &mut R, offset: u64, len: usize) -> Result<Vec<u8>> {
+    let mut chunk_buffer = vec![0u8; len];
+    reader.seek(SeekFrom::Start(offset))?;
+
+    let mut filled = 0;
+    while filled < len {
+        let bytes_read = reader.read(&mut chunk_buffer[filled..])?;
+        if bytes_read == 0 {
+            break;
+        }
+        filled += bytes_read;
+    }
+
+    chunk_buffer.truncate(filled);
+    Ok(chunk_buffer)
+}
+
+/// A chunk of input read with context on both sides
+///
+/// `data` starts at absolute offset `data_offset` and covers the `owned`
+/// range plus up to `lead` bytes before and `overlap` bytes after it.
+/// Items should only be reported by the chunk whose `owned` range contains
+/// their start, so nothing is lost or duplicated at chunk boundaries.
+pub struct Chunk {
+    pub data: Vec<u8>,
+    pub data_offset: u64,
+    pub owned: Range<u64>,
+}
+
+/// Scan a reader chunk by chunk, optionally processing chunks in parallel
+///
+/// Chunks are read in batches (one per rayon thread when `parallel` is set)
+/// and the per-chunk results are returned in input order.
+///
+/// # Arguments
+///
+/// * `reader` - Input to scan from its current position to the end
+/// * `chunk_size` - Size of each owned chunk in bytes
+/// * `lead` - Context bytes to read before each chunk
+/// * `overlap` - Context bytes to read after each chunk
+/// * `parallel` - Whether to scan the chunks of a batch in parallel
+/// * `scan` - Function producing the items of one chunk
+pub fn scan_chunks<R, T, F>(
+    reader: &mut R,
+    chunk_size: usize,
+    lead: usize,
+    overlap: usize,
+    parallel: bool,
+    scan: F,
+) -> Result<Vec<T>>
+where
+    R: Read + Seek,
+    T: Send,
+    F: Fn(&Chunk) -> Vec<T> + Sync,
+{
+    scan_chunks_while(reader, chunk_size, lead, overlap, parallel, scan, |_| true)
+}
+
+/// Like [`scan_chunks`], but stops reading once `keep_going` returns false
+///
+/// `keep_going` is called with all results so far after each batch, so a
+/// scan that only needs the first few items does not read the whole input.
+pub fn scan_chunks_while<R, T, F, C>(
+    reader: &mut R,
+    chunk_size: usize,
+    lead: usize,
+    overlap: usize,
+    parallel: bool,
+    scan: F,
+    keep_going: C,
+) -> Result<Vec<T>>
+where
+    R: Read + Seek,
+    T: Send,
+    F: Fn(&Chunk) -> Vec<T> + Sync,
+    C: Fn(&[T]) -> bool,
+{
+    let chunk_size = chunk_size.max(1);
+    let batch_size = batch_size(parallel);
+
+    let origin = reader.stream_position()?;
+    let mut chunk_start = origin;
+    let mut results = Vec::new();
+    let mut finished = false;
+
+    while !finished {
+        let mut batch = Vec::with_capacity(batch_size);
+
+        while batch.len() < batch_size {
+            let data_offset = chunk_start.saturating_sub(lead as u64).max(origin);
+            let lead_len = (chunk_start - data_offset) as usize;
+            let data = read_chunk(reader, data_offset, lead_len + chunk_size + overlap)?;
+
+            if data.len() <= lead_len {
+                finished = true;
+                break;
+            }
+
+            let owned_end = (chunk_start + chunk_size as u64).min(data_offset + data.len() as u64);
+            batch.push(Chunk {
+                data,
+                data_offset,
+                owned: chunk_start..owned_end,
+            });
+            chunk_start += chunk_size as u64;
+        }
+
+        results.extend(scan_batch(&batch, parallel, &scan));
+        if !keep_going(&results) {
+            break;
+        }
+    }
+
+    Ok(results)
+}
+
+/// Scan only the given ranges of a reader, chunk by chunk
+///
+/// Each range is split into owned chunks of at most `chunk_size` bytes, read
+/// with `overlap` bytes of trailing context. Bytes outside the ranges are
+/// only read as overlap, which makes this suitable for verifying candidate
+/// regions instead of scanning a whole input.
+///
+/// # Arguments
+///
+/// * `reader` - Input to read the ranges from
+/// * `ranges` - Sorted, non-overlapping absolute ranges to scan
+/// * `chunk_size` - Maximum size of each owned chunk in bytes
+/// * `overlap` - Context bytes to read after each chunk
+/// * `parallel` - Whether to scan the chunks of a batch in parallel
+/// * `scan` - Function producing the items of one chunk
+/// * `keep_going` - Called with all results after each batch; false stops the scan
+pub fn scan_ranges<R, T, F, C>(
+    reader: &mut R,
+    ranges: &[Range<u64>],
+    chunk_size: usize,
+    overlap: usize,
+    parallel: bool,
+    scan: F,
+    keep_going: C,
+) -> Result<Vec<T>>
+where
+    R: Read + Seek,
+    T: Send,
+    F: Fn(&Chunk) -> Vec<T> + Sync,
+    C: Fn(&[T]) -> bool,
+{
+    let chunk_size = chunk_size.max(1) as u64;
+    let batch_size = batch_size(parallel);
+    let mut pieces = ranges.iter().flat_map(|range| {
+        (range.start..range.end)
+            .step_by(chunk_size as usize)
+            .map(move |start| start..(start + chunk_size).min(range.end))
+    });
+
+    let mut results = Vec::new();
+    loop {
+        let mut batch = Vec::with_capacity(batch_size);
+        for owned in pieces.by_ref().take(batch_size) {
+            let len = (owned.end - owned.start) as usize + overlap;
+            let data = read_chunk(reader, owned.start, len)?;
+            if data.is_empty() {
+                continue;
+            }
+            // Ranges past the end of the input only own what was read
+            let owned = owned.start..owned.end.min(owned.start + data.len() as u64);
+            batch.push(Chunk {
+                data,
+                data_offset: owned.start,
+                owned,
+            });
+        }
+        if batch.is_empty() {
+            break;
+        }
+
+        results.extend(scan_batch(&batch, parallel, &scan));
+        if !keep_going(&results) {
+            break;
+        }
+    }
+
+    Ok(results)
+}
+
+/// Number of chunks read per batch
+fn batch_size(parallel: bool) -> usize {
+    if parallel {
+        rayon::current_num_threads().max(1)
+    } else {
+        1
+    }
+}
+
+/// Scan a batch of chunks and return their items in input order
+fn scan_batch<T, F>(batch: &[Chunk], parallel: bool, scan: &F) -> Vec<T>
+where
+    T: Send,
+    F: Fn(&Chunk) -> Vec<T> + Sync,
+{
+    // Workers report their chunk's own bytes as they finish it
+    let scan_and_report = |chunk: &Chunk| {
+        let items = scan(chunk);
+        progress::advance(chunk.owned.end - chunk.owned.start);
+        items
+    };
+    let batch_results: Vec<Vec<T>> = if parallel {
+        batch.par_iter().map(scan_and_report).collect()
+    } else {
+        batch.iter().map(scan_and_report).collect()
+    };
+    batch_results.into_iter().flatten().collect()
+}
 
 /// Parallel file processor for improved performance on large files
 pub struct ParallelProcessor;
@@ -12,6 +233,7 @@ impl ParallelProcessor {
     /// Process file with parallel chunked search
     ///
     /// Divides the file into chunks and processes them in parallel for better performance.
+    /// Returns the number of matches printed.
     ///
     /// # Arguments
     ///
@@ -23,8 +245,13 @@ impl ParallelProcessor {
     /// * `separator` - String to separate hex bytes
     /// * `show_offset` - Whether to display offset values
     /// * `file_size` - Total size of the file for offset formatting
-    pub fn process_file_parallel(
-        file: &mut File,
+    /// * `alignment` - Optional sector/cluster alignment constraint for hits
+    /// * `file_name` - File name to show in front of each match line (`-H`)
+    /// * `checkpoint` - Checkpoint to resume from and record progress in
+    /// * `out` - Destination for the match lines
+    #[allow(clippy::too_many_arguments)]
+    pub fn process_file_parallel<R: Read + Seek, W: Write>(
+        file: &mut R,
         regex: &Regex,
         chunk_size: usize,
         width: usize,
@@ -32,10 +259,20 @@ impl ParallelProcessor {
         separator: &str,
         show_offset: bool,
         file_size: u64,
-    ) -> Result<()> {
+        alignment: Option<&Alignment>,
+        file_name: Option<&str>,
+        checkpoint: Option<&mut Checkpointer>,
+        out: &mut W,
+    ) -> Result<usize> {
+        let matcher = AlignedMatcher::new(regex, alignment)?;
         let hex_offset_length = OutputFormatter::calculate_hex_offset_length(file_size);
-        let mut all_matches = Vec::new();
-        let mut current_pos = file.stream_position()?;
+        let mut checkpoint = checkpoint;
+        // A checkpoint decides where the scan starts and which matches were already reported
+        let mut current_pos = match checkpoint.as_deref() {
+            Some(checkpoint) => checkpoint.offset(),
+            None => file.stream_position()?,
+        };
+        let mut last_match = checkpoint.as_deref().and_then(Checkpointer::last_match);
         let mut match_count = 0;
 
         // Calculate overlap size based on potential pattern length
@@ -43,6 +280,11 @@ impl ParallelProcessor {
         let overlap_size = 1024.min(chunk_size / 10); // 10% overlap, max 1KB
 
         while current_pos < file_size {
+            // Every match before this chunk has been reported
+            if let Some(checkpoint) = checkpoint.as_deref_mut() {
+                checkpoint.record(current_pos, last_match, match_count)?;
+            }
+
             let remaining = file_size - current_pos;
             let actual_chunk_size = if remaining < chunk_size as u64 {
                 remaining as usize
@@ -51,10 +293,7 @@ impl ParallelProcessor {
             };
 
             // Read chunk with overlap
-            let mut chunk_buffer = vec![0u8; actual_chunk_size];
-            file.seek(SeekFrom::Start(current_pos))?;
-            let bytes_read = file.read(&mut chunk_buffer)?;
-            chunk_buffer.truncate(bytes_read);
+            let chunk_buffer = read_chunk(file, current_pos, actual_chunk_size)?;
 
             if chunk_buffer.is_empty() {
                 break;
@@ -63,23 +302,30 @@ impl ParallelProcessor {
             // Process chunk and find matches
             let chunk_matches = Self::process_chunk(
                 &chunk_buffer,
-                regex,
+                &matcher,
                 current_pos,
                 width,
                 separator,
                 show_offset,
                 hex_offset_length,
+                file_name,
             );
 
-            // Add matches to the collection
+            // Chunks are scanned in order, so matches can be printed right away
             for (offset, line) in chunk_matches {
-                // Skip matches in overlap region except for the first chunk
-                if current_pos > 0 && offset >= current_pos + chunk_size as u64 {
+                // Matches in the overlap region belong to the next chunk
+                if offset >= current_pos + chunk_size as u64 {
+                    continue;
+                }
+                // Reported before the scan was resumed
+                if last_match.is_some_and(|last| offset <= last) {
                     continue;
                 }
 
-                all_matches.push((offset, line));
+                writeln!(out, "{}", line)?;
+                last_match = Some(offset);
                 match_count += 1;
+                progress::record_matches(1);
 
                 // Check limit
                 if limit > 0 && match_count >= limit {
@@ -88,48 +334,57 @@ impl ParallelProcessor {
             }
 
             if limit > 0 && match_count >= limit {
-                break;
+                if let Some(checkpoint) = checkpoint.as_deref_mut() {
+                    checkpoint.finish(current_pos, last_match, match_count)?;
+                }
+                return Ok(match_count);
             }
 
             // Move to next chunk (without overlap to avoid double processing)
+            progress::advance((chunk_size as u64).min(remaining));
             current_pos += chunk_size as u64;
         }
 
-        // Sort matches by offset and print
-        all_matches.sort_by_key(|(offset, _)| *offset);
-        for (_, line) in all_matches
-            .into_iter()
-            .take(if limit > 0 { limit } else { usize::MAX })
-        {
-            println!("{}", line);
+        if let Some(checkpoint) = checkpoint {
+            checkpoint.finish(current_pos.min(file_size), last_match, match_count)?;
         }
 
-        Ok(())
+        Ok(match_count)
     }
 
     /// Process a chunk of data and find regex matches
+    #[allow(clippy::too_many_arguments)]
     fn process_chunk(
         data: &[u8],
-        regex: &Regex,
+        matcher: &AlignedMatcher,
         chunk_start_offset: u64,
         width: usize,
         separator: &str,
         show_offset: bool,
         hex_offset_length: usize,
+        file_name: Option<&str>,
     ) -> Vec<(u64, String)> {
         let mut matches = Vec::new();
+        let use_color =
+            OutputFormatter::should_use_color(crate::color_context::get_color_choice());
 
-        for mat in regex.find_iter(data) {
-            let match_offset = chunk_start_offset + mat.start() as u64;
+        for hit in matcher.find_iter(data, chunk_start_offset) {
+            let match_offset = chunk_start_offset + hit.start as u64;
 
             // Determine the range to display
-            let start_pos = mat.start();
+            let start_pos = hit.start;
             let end_pos = (start_pos + width).min(data.len());
 
             if start_pos < data.len() {
                 let display_bytes = &data[start_pos..end_pos];
-                let hex_string = OutputFormatter::format_bytes_as_hex(display_bytes, separator);
-                let formatted_line = if show_offset {
+                let mut hex_string = OutputFormatter::format_bytes_for_display(display_bytes, separator);
+                if let Some(alignment) = matcher.alignment() {
+                    hex_string = OutputFormatter::append_annotation(
+                        &hex_string,
+                        &alignment.describe(match_offset),
+                    );
+                }
+                let mut formatted_line = if show_offset {
                     OutputFormatter::format_line_with_offset(
                         match_offset,
                         &hex_string,
@@ -138,6 +393,12 @@ impl ParallelProcessor {
                 } else {
                     hex_string
                 };
+                formatted_line =
+                    OutputFormatter::prefix_file_name(formatted_line, file_name, use_color);
+                for line in OutputFormatter::inspection_lines(&data[start_pos..]) {
+                    formatted_line.push('\n');
+                    formatted_line.push_str(&line);
+                }
                 matches.push((match_offset, formatted_line));
             }
         }
@@ -149,6 +410,7 @@ impl ParallelProcessor {
     ///
     /// This method divides a large buffer into smaller chunks and processes them
     /// in parallel using rayon's parallel iterators.
+    #[allow(clippy::too_many_arguments)]
     pub fn process_buffer_parallel(
         data: &[u8],
         regex: &Regex,
@@ -157,20 +419,24 @@ impl ParallelProcessor {
         separator: &str,
         show_offset: bool,
         hex_offset_length: usize,
-    ) -> Vec<(u64, String)> {
+        alignment: Option<&Alignment>,
+    ) -> Result<Vec<(u64, String)>> {
         const PARALLEL_CHUNK_SIZE: usize = 64 * 1024; // 64KB per thread
         const OVERLAP_SIZE: usize = 1024; // 1KB overlap
 
+        let matcher = AlignedMatcher::new(regex, alignment)?;
+
         if data.len() <= PARALLEL_CHUNK_SIZE {
-            return Self::process_chunk(
+            return Ok(Self::process_chunk(
                 data,
-                regex,
+                &matcher,
                 base_offset,
                 width,
                 separator,
                 show_offset,
                 hex_offset_length,
-            );
+                None,
+            ));
         }
 
         let mut chunks = Vec::new();
@@ -191,12 +457,13 @@ impl ParallelProcessor {
             .map(|(chunk_data, chunk_offset)| {
                 Self::process_chunk(
                     chunk_data,
-                    regex,
+                    &matcher,
                     chunk_offset,
                     width,
                     separator,
                     show_offset,
                     hex_offset_length,
+                    None,
                 )
             })
             .collect();
@@ -208,7 +475,7 @@ impl ParallelProcessor {
         // Remove duplicates that might occur in overlap regions
         matches.dedup_by_key(|(offset, _)| *offset);
 
-        matches
+        Ok(matches)
     }
 }
 
@@ -217,15 +484,19 @@ pub struct ParallelHexDump;
 
 impl ParallelHexDump {
     /// Process file in parallel for hex dump (non-regex mode)
-    pub fn process_file_parallel(
-        file: &mut File,
+    ///
+    /// Writes the lines to `out` and returns the number of lines printed.
+    #[allow(clippy::too_many_arguments)]
+    pub fn process_file_parallel<R: Read + Seek, W: Write>(
+        file: &mut R,
         chunk_size: usize,
         width: usize,
         limit: usize,
         separator: &str,
         show_offset: bool,
         file_size: u64,
-    ) -> Result<()> {
+        out: &mut W,
+    ) -> Result<usize> {
         let hex_offset_length = OutputFormatter::calculate_hex_offset_length(file_size);
         let mut current_pos = file.stream_position()?;
         let mut lines_processed = 0;
@@ -235,10 +506,8 @@ impl ParallelHexDump {
             let remaining = file_size - current_pos;
             let actual_chunk_size = (chunk_size as u64).min(remaining) as usize;
 
-            let mut chunk_buffer = vec![0u8; actual_chunk_size];
-            file.seek(SeekFrom::Start(current_pos))?;
-            let bytes_read = file.read(&mut chunk_buffer)?;
-            chunk_buffer.truncate(bytes_read);
+            let chunk_buffer = read_chunk(file, current_pos, actual_chunk_size)?;
+            let bytes_read = chunk_buffer.len();
 
             if chunk_buffer.is_empty() {
                 break;
@@ -260,17 +529,18 @@ impl ParallelHexDump {
             );
 
             for line in chunk_lines {
-                println!("{}", line);
+                writeln!(out, "{}", line)?;
                 lines_processed += 1;
                 if limit > 0 && lines_processed >= limit {
                     break;
                 }
             }
 
+            progress::advance(bytes_read as u64);
             current_pos += bytes_read as u64;
         }
 
-        Ok(())
+        Ok(lines_processed)
     }
 
     /// Process a chunk for hex dump output
@@ -292,7 +562,7 @@ impl ParallelHexDump {
             let line_bytes = &data[pos..end];
             let offset = start_offset + pos as u64;
 
-            let hex_string = OutputFormatter::format_bytes_as_hex(line_bytes, separator);
+            let hex_string = OutputFormatter::format_bytes_for_display(line_bytes, separator);
             let formatted_line = if show_offset {
                 OutputFormatter::format_line_with_offset(offset, &hex_string, hex_offset_length)
             } else {
@@ -1,14 +1,79 @@
+//! Progress reporting for `--progress`
+//!
+//! A single [`ProgressIndicator`] is installed for the whole run and every
+//! scanning path reports the bytes it has read through [`advance`]. The
+//! counters are atomic, so parallel workers and concurrently processed files
+//! all add into the same totals.
+//!
+//! The same counters drive either the human progress bar on stderr or, for
+//! wrappers and dashboards, a stream of JSON events (one object per line):
+//! `start`, periodic `progress`, and `finish` or `error` at the end.
+
+use serde::Serialize;
 use std::io::{self, Write};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, Once, OnceLock};
 use std::time::{Duration, Instant};
 
+/// Minimum time between two redraws of the progress bar
+const UPDATE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Global progress indicator, installed when `--progress` is given
+static PROGRESS: OnceLock<ProgressIndicator> = OnceLock::new();
+
 /// Progress indicator for file processing
+///
+/// All methods take `&self`: byte counters are atomics and the bar is
+/// redrawn by whichever caller first crosses the update interval.
 pub struct ProgressIndicator {
     start_time: Instant,
-    last_update: Instant,
-    total_bytes: u64,
-    processed_bytes: u64,
+    /// Milliseconds after `start_time` of the last redraw
+    last_update: AtomicU64,
+    total_bytes: AtomicU64,
+    processed_bytes: AtomicU64,
+    matches: AtomicU64,
+    files: Mutex<FileProgress>,
+    /// Destination of JSON events; the bar is drawn on stderr when `None`
+    events: Option<Mutex<Box<dyn Write + Send>>>,
+    /// Guards the single `start` event
+    started: Once,
     enabled: bool,
-    show_progress: bool,
+}
+
+/// A JSON progress event
+#[derive(Debug, Serialize)]
+struct ProgressEvent<'a> {
+    /// `start`, `progress`, `finish` or `error`
+    event: &'a str,
+    bytes_scanned: u64,
+    /// Bytes to scan in total (0 while unknown)
+    total_bytes: u64,
+    matches: u64,
+    /// File started most recently in a multi-file run
+    current_file: Option<&'a str>,
+    files_started: usize,
+    files_total: usize,
+    elapsed_seconds: f64,
+    bytes_per_second: f64,
+    /// Estimated seconds until done (`null` while unknown)
+    eta_seconds: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<&'a str>,
+}
+
+/// Position within a multi-file run
+#[derive(Debug, Default)]
+struct FileProgress {
+    /// Number of files in the run (0 outside multi-file mode)
+    total_files: usize,
+    /// Number of files started so far
+    started_files: usize,
+    /// File started most recently
+    name: String,
+    /// Size of that file
+    size: u64,
+    /// Overall processed bytes when that file was started
+    base: u64,
 }
 
 impl ProgressIndicator {
@@ -16,17 +81,29 @@ impl ProgressIndicator {
     ///
     /// # Arguments
     ///
-    /// * `total_bytes` - Total number of bytes to process
+    /// * `total_bytes` - Total number of bytes to process (0 if not known yet)
     /// * `show_progress` - Whether to show progress updates
     pub fn new(total_bytes: u64, show_progress: bool) -> Self {
-        let now = Instant::now();
         Self {
-            start_time: now,
-            last_update: now,
-            total_bytes,
-            processed_bytes: 0,
-            enabled: show_progress && total_bytes > 0,
-            show_progress,
+            start_time: Instant::now(),
+            last_update: AtomicU64::new(0),
+            total_bytes: AtomicU64::new(total_bytes),
+            processed_bytes: AtomicU64::new(0),
+            matches: AtomicU64::new(0),
+            files: Mutex::new(FileProgress::default()),
+            events: None,
+            started: Once::new(),
+            enabled: show_progress,
+        }
+    }
+
+    /// Create a progress indicator that writes JSON events to `out`
+    ///
+    /// Events are written whether or not `out` is a terminal.
+    pub fn json(out: Box<dyn Write + Send>) -> Self {
+        Self {
+            events: Some(Mutex::new(out)),
+            ..Self::new(0, true)
         }
     }
 
@@ -35,63 +112,230 @@ impl ProgressIndicator {
     /// # Arguments
     ///
     /// * `bytes_processed` - Additional bytes processed since last update
-    pub fn update(&mut self, bytes_processed: u64) {
-        self.processed_bytes = self.processed_bytes.saturating_add(bytes_processed);
+    pub fn update(&self, bytes_processed: u64) {
+        if self.enabled {
+            self.emit_start();
+        }
+        let _ =
+            self.processed_bytes
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |processed| {
+                    Some(processed.saturating_add(bytes_processed))
+                });
 
         if !self.enabled {
             return;
         }
 
-        let now = Instant::now();
-
-        // Update progress every 100ms
-        if now.duration_since(self.last_update) >= Duration::from_millis(100) {
+        // Update progress every 100ms; the exchange lets only one caller redraw
+        let now = self.start_time.elapsed().as_millis() as u64;
+        let last = self.last_update.load(Ordering::Relaxed);
+        if now.saturating_sub(last) >= UPDATE_INTERVAL.as_millis() as u64
+            && self
+                .last_update
+                .compare_exchange(last, now, Ordering::Relaxed, Ordering::Relaxed)
+                .is_ok()
+        {
             self.display_progress();
-            self.last_update = now;
         }
     }
 
+    /// Add matches found to the running count
+    pub fn add_matches(&self, count: u64) {
+        self.matches.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Add bytes that are going to be scanned to the total
+    pub fn add_total(&self, bytes: u64) {
+        self.total_bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    /// Set the number of files of a multi-file run
+    pub fn set_file_count(&self, count: usize) {
+        self.lock_files().total_files = count;
+    }
+
+    /// Record that scanning of a file of `size` bytes has started
+    pub fn start_file(&self, name: &str, size: u64) {
+        let mut files = self.lock_files();
+        files.started_files += 1;
+        files.name = name.to_string();
+        files.size = size;
+        files.base = self.processed_bytes();
+    }
+
+    /// Record that a file is done after scanning `scanned` of its `size` bytes
+    ///
+    /// Bytes left unread (e.g. once the match limit is hit) are dropped from
+    /// the total so the overall percentage and ETA stay meaningful.
+    pub fn finish_file(&self, size: u64, scanned: u64) {
+        let unread = size.saturating_sub(scanned);
+        let _ = self
+            .total_bytes
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |total| {
+                Some(total.saturating_sub(unread))
+            });
+    }
+
     /// Set the progress to completed
-    pub fn finish(&mut self) {
+    ///
+    /// JSON events report the bytes actually scanned; the bar is filled up.
+    pub fn finish(&self) {
         if !self.enabled {
             return;
         }
+        if self.events.is_some() {
+            self.emit_start();
+            self.emit("finish", None);
+            return;
+        }
 
-        self.processed_bytes = self.total_bytes;
+        self.processed_bytes
+            .fetch_max(self.total_bytes(), Ordering::Relaxed);
         self.display_progress();
         eprintln!(); // New line after progress
     }
 
-    /// Display current progress
-    fn display_progress(&self) {
-        if !self.show_progress {
+    /// Report that the run stopped with an error
+    pub fn fail(&self, message: &str) {
+        if !self.enabled {
             return;
         }
-
-        let percentage = if self.total_bytes > 0 {
-            (self.processed_bytes as f64 / self.total_bytes as f64 * 100.0) as u32
+        if self.events.is_some() {
+            self.emit_start();
+            self.emit("error", Some(message));
         } else {
-            0
+            eprintln!(); // Keep the error message off the bar's line
+        }
+    }
+
+    /// Bytes processed so far
+    pub fn processed_bytes(&self) -> u64 {
+        self.processed_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Total bytes to process (0 if unknown)
+    pub fn total_bytes(&self) -> u64 {
+        self.total_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Matches found so far
+    pub fn matches(&self) -> u64 {
+        self.matches.load(Ordering::Relaxed)
+    }
+
+    /// Whether the bar is drawn on stderr
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn lock_files(&self) -> std::sync::MutexGuard<'_, FileProgress> {
+        self.files.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Write the `start` event once, before any other event
+    fn emit_start(&self) {
+        if self.events.is_some() {
+            self.started.call_once(|| self.emit("start", None));
+        }
+    }
+
+    /// Write one JSON event line from the current counters
+    fn emit(&self, event: &str, message: Option<&str>) {
+        let Some(events) = &self.events else {
+            return;
         };
 
-        let elapsed = self.start_time.elapsed();
-        let bytes_per_sec = if elapsed.as_secs_f64() > 0.0 {
-            self.processed_bytes as f64 / elapsed.as_secs_f64()
-        } else {
-            0.0
+        let processed = self.processed_bytes();
+        let total = self.total_bytes();
+        let elapsed = self.start_time.elapsed().as_secs_f64();
+        let bytes_per_sec = bytes_per_second(processed, elapsed);
+        let files = self.lock_files();
+        let record = ProgressEvent {
+            event,
+            bytes_scanned: processed,
+            total_bytes: total,
+            matches: self.matches(),
+            current_file: (files.started_files > 0).then_some(files.name.as_str()),
+            files_started: files.started_files,
+            files_total: files.total_files,
+            elapsed_seconds: elapsed,
+            bytes_per_second: bytes_per_sec,
+            eta_seconds: (total > 0 && bytes_per_sec > 0.0)
+                .then(|| total.saturating_sub(processed) as f64 / bytes_per_sec),
+            message,
         };
 
+        // A dashboard that went away must not stop the scan
+        let mut out = events.lock().unwrap_or_else(|e| e.into_inner());
+        if let Ok(line) = serde_json::to_string(&record) {
+            let _ = writeln!(out, "{}", line);
+            let _ = out.flush();
+        }
+    }
+
+    /// Display current progress
+    fn display_progress(&self) {
+        if self.events.is_some() {
+            self.emit("progress", None);
+            return;
+        }
+
+        let mut line = self.format_progress();
+        let files = self.lock_files();
+        if files.total_files > 0 {
+            let file_done = self.processed_bytes().saturating_sub(files.base);
+            line.push_str(&format!(
+                " | file {}/{} {} {}%",
+                files.started_files,
+                files.total_files,
+                files.name,
+                percentage(file_done, files.size)
+            ));
+        }
+        drop(files);
+
+        // Clear the rest of the line in case the previous one was longer
+        eprint!("\r{}\x1b[K", line);
+        let _ = io::stderr().flush();
+    }
+
+    /// Format the overall bar: percentage, bytes, throughput and ETA
+    ///
+    /// Without a known total only the bytes processed and the throughput
+    /// are shown.
+    fn format_progress(&self) -> String {
+        let processed = self.processed_bytes();
+        let total = self.total_bytes();
+
+        let bytes_per_sec = bytes_per_second(processed, self.start_time.elapsed().as_secs_f64());
+
         let (rate_value, rate_unit) = format_bytes_per_second(bytes_per_sec);
-        let (processed_value, processed_unit) = format_bytes(self.processed_bytes);
-        let (total_value, total_unit) = format_bytes(self.total_bytes);
+        let (processed_value, processed_unit) = format_bytes(processed);
+
+        if total == 0 {
+            return format!(
+                "{:.1} {} {:.1} {}",
+                processed_value, processed_unit, rate_value, rate_unit
+            );
+        }
+
+        let percentage = percentage(processed, total);
+        let (total_value, total_unit) = format_bytes(total);
+        let eta = if processed >= total {
+            format_eta(Duration::ZERO)
+        } else {
+            Duration::try_from_secs_f64((total - processed) as f64 / bytes_per_sec)
+                .map(format_eta)
+                .unwrap_or_else(|_| "--:--".to_string())
+        };
 
         // Progress bar
         let bar_width = 20;
         let filled = (percentage as usize * bar_width) / 100;
         let empty = bar_width - filled;
 
-        eprint!(
-            "\r[{}{}] {}% ({:.1} {}/{:.1} {}) {:.1} {}/s",
+        format!(
+            "[{}{}] {}% ({:.1} {}/{:.1} {}) {:.1} {} ETA {}",
             "=".repeat(filled),
             " ".repeat(empty),
             percentage,
@@ -100,22 +344,14 @@ impl ProgressIndicator {
             total_value,
             total_unit,
             rate_value,
-            rate_unit
-        );
-
-        let _ = io::stderr().flush();
+            rate_unit,
+            eta
+        )
     }
 
     /// Create a progress indicator that's always disabled
     pub fn disabled() -> Self {
-        Self {
-            start_time: Instant::now(),
-            last_update: Instant::now(),
-            total_bytes: 0,
-            processed_bytes: 0,
-            enabled: false,
-            show_progress: false,
-        }
+        Self::new(0, false)
     }
 
     /// Check if progress should be shown based on output destination
@@ -127,6 +363,58 @@ impl ProgressIndicator {
     }
 }
 
+/// Install the global progress indicator
+pub fn set_progress(progress: ProgressIndicator) {
+    PROGRESS.set(progress).ok();
+}
+
+/// Get the global progress indicator, if `--progress` was given
+pub fn get_progress() -> Option<&'static ProgressIndicator> {
+    PROGRESS.get()
+}
+
+/// Report scanned bytes to the global progress indicator, if any
+pub fn advance(bytes: u64) {
+    if let Some(progress) = get_progress() {
+        progress.update(bytes);
+    }
+}
+
+/// Report matches found to the global progress indicator, if any
+pub fn record_matches(count: usize) {
+    if let Some(progress) = get_progress() {
+        progress.add_matches(count as u64);
+    }
+}
+
+/// Average throughput over `elapsed_secs`
+fn bytes_per_second(bytes: u64, elapsed_secs: f64) -> f64 {
+    if elapsed_secs > 0.0 {
+        bytes as f64 / elapsed_secs
+    } else {
+        0.0
+    }
+}
+
+/// Percentage of `done` out of `total`, capped at 100 (100 for an empty total)
+fn percentage(done: u64, total: u64) -> u32 {
+    if total == 0 {
+        return 100;
+    }
+    (done.min(total) as f64 / total as f64 * 100.0) as u32
+}
+
+/// Format a remaining time as `mm:ss`, or `h:mm:ss` from one hour on
+fn format_eta(eta: Duration) -> String {
+    let secs = eta.as_secs();
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{:02}:{:02}", minutes, seconds)
+    }
+}
+
 /// Format bytes with appropriate unit
 fn format_bytes(bytes: u64) -> (f64, &'static str) {
     const UNITS: &[(&str, u64)] = &[
@@ -191,8 +479,8 @@ mod tests {
     #[test]
     fn test_progress_indicator_creation() {
         let progress = ProgressIndicator::new(1000, true);
-        assert_eq!(progress.total_bytes, 1000);
-        assert_eq!(progress.processed_bytes, 0);
+        assert_eq!(progress.total_bytes(), 1000);
+        assert_eq!(progress.processed_bytes(), 0);
         assert!(progress.enabled);
 
         let disabled_progress = ProgressIndicator::disabled();
@@ -201,18 +489,122 @@ mod tests {
 
     #[test]
     fn test_progress_update() {
-        let mut progress = ProgressIndicator::new(1000, false); // Don't show to avoid stderr output in tests
+        let progress = ProgressIndicator::new(1000, false); // Don't show to avoid stderr output in tests
         progress.update(250);
-        assert_eq!(progress.processed_bytes, 250);
+        assert_eq!(progress.processed_bytes(), 250);
 
         progress.update(750);
-        assert_eq!(progress.processed_bytes, 1000);
+        assert_eq!(progress.processed_bytes(), 1000);
     }
 
     #[test]
     fn test_progress_overflow() {
-        let mut progress = ProgressIndicator::new(100, false);
+        let progress = ProgressIndicator::new(100, false);
         progress.update(150); // More than total
-        assert_eq!(progress.processed_bytes, 150); // Should not clamp, but saturate on add
+        assert_eq!(progress.processed_bytes(), 150); // Should not clamp, but saturate on add
+    }
+
+    #[test]
+    fn test_progress_aggregates_across_threads() {
+        let progress = ProgressIndicator::new(8000, false);
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    for _ in 0..100 {
+                        progress.update(10);
+                    }
+                });
+            }
+        });
+        assert_eq!(progress.processed_bytes(), 8000);
+    }
+
+    #[test]
+    fn test_progress_bar_and_eta() {
+        let progress = ProgressIndicator::new(1000, false);
+        progress.update(500);
+        let line = progress.format_progress();
+        assert!(
+            line.starts_with("[==========          ] 50% (500.0 B/1000.0 B)"),
+            "{}",
+            line
+        );
+        assert!(line.contains(" ETA "), "{}", line);
+
+        // Unread bytes of a file cut short leave the total
+        progress.finish_file(600, 100);
+        assert_eq!(progress.total_bytes(), 500);
+        assert!(progress.format_progress().contains("100%"));
+
+        let unknown = ProgressIndicator::new(0, false);
+        unknown.update(2048);
+        assert!(unknown.format_progress().starts_with("2.0 KB "));
+    }
+
+    #[test]
+    fn test_format_eta() {
+        assert_eq!(format_eta(Duration::from_secs(0)), "00:00");
+        assert_eq!(format_eta(Duration::from_secs(75)), "01:15");
+        assert_eq!(format_eta(Duration::from_secs(3725)), "1:02:05");
+    }
+
+    /// Writer that keeps what was written for inspection
+    #[derive(Clone, Default)]
+    struct SharedBuffer(std::sync::Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn events(buffer: &SharedBuffer) -> Vec<serde_json::Value> {
+        let data = buffer.0.lock().unwrap();
+        String::from_utf8_lossy(&data)
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_json_events() {
+        let buffer = SharedBuffer::default();
+        let progress = ProgressIndicator::json(Box::new(buffer.clone()));
+        progress.set_file_count(2);
+        progress.add_total(1000);
+        progress.start_file("a.bin", 600);
+        progress.update(600);
+        progress.add_matches(3);
+        progress.finish();
+
+        let events = events(&buffer);
+        assert_eq!(events.first().unwrap()["event"], "start");
+        let last = events.last().unwrap();
+        assert_eq!(last["event"], "finish");
+        assert_eq!(last["bytes_scanned"], 600);
+        assert_eq!(last["total_bytes"], 1000);
+        assert_eq!(last["matches"], 3);
+        assert_eq!(last["current_file"], "a.bin");
+        assert_eq!(last["files_started"], 1);
+        assert_eq!(last["files_total"], 2);
+        assert!(last.get("message").is_none());
+    }
+
+    #[test]
+    fn test_json_error_event() {
+        let buffer = SharedBuffer::default();
+        let progress = ProgressIndicator::json(Box::new(buffer.clone()));
+        progress.fail("disk went away");
+
+        let events = events(&buffer);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0]["event"], "start");
+        assert_eq!(events[1]["event"], "error");
+        assert_eq!(events[1]["message"], "disk went away");
+        assert_eq!(events[1]["current_file"], serde_json::Value::Null);
     }
 }
@@ -0,0 +1,615 @@
+//! Binary search-and-replace (patch) support
+//!
+//! Replaces regex matches with a byte template that may contain `\xHH`
+//! escapes and capture group references (`$1`, `${name}`), and writes the
+//! result back atomically through a temporary file. Files are planned in
+//! windows and streamed into the temporary copy, so they are never loaded
+//! into memory as a whole.
+
+use crate::alignment::{AlignedMatcher, Alignment};
+use crate::error::{BingrepError, Result};
+use crate::forensic_image::is_forensic_image;
+use crate::output::OutputFormatter;
+use crate::parallel::read_chunk;
+use crate::structured_output::StructuredRecord;
+use regex::bytes::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Cursor, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// Bytes of input searched per planning window; a window is read with as
+/// many bytes again after it, so matches up to this long are never cut off
+const PLAN_WINDOW: usize = 1024 * 1024;
+
+/// Bytes read before each planning window, so look-behind assertions such
+/// as `\b` see the input before it
+const PLAN_LEAD: u64 = 16;
+
+/// Part of a parsed replacement template
+#[derive(Debug, Clone, PartialEq)]
+enum TemplatePart {
+    Literal(Vec<u8>),
+    Group(usize),
+    Named(String),
+}
+
+/// Parsed replacement template
+#[derive(Debug, Clone)]
+pub struct ReplacementTemplate {
+    parts: Vec<TemplatePart>,
+}
+
+impl ReplacementTemplate {
+    /// Parse a replacement template
+    ///
+    /// Supported syntax: `\xHH` bytes, `\\`, `\$`, `\n`, `\r`, `\t`, `\0`,
+    /// `$N` / `${N}` numbered groups, `${name}` named groups and `$$`.
+    /// Any other character is inserted as its UTF-8 bytes.
+    pub fn parse(template: &str) -> Result<Self> {
+        let mut parts = Vec::new();
+        let mut literal = Vec::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(ch) = chars.next() {
+            match ch {
+                '\\' => match chars.next() {
+                    Some('x') | Some('X') => {
+                        let hex: String = chars.by_ref().take(2).collect();
+                        let byte = u8::from_str_radix(&hex, 16)
+                            .ok()
+                            .filter(|_| hex.len() == 2)
+                            .ok_or_else(|| {
+                                BingrepError::InvalidPattern(format!(
+                                    "Invalid hex sequence in replacement: \\x{}",
+                                    hex
+                                ))
+                            })?;
+                        literal.push(byte);
+                    }
+                    Some('\\') => literal.push(b'\\'),
+                    Some('$') => literal.push(b'$'),
+                    Some('n') => literal.push(b'\n'),
+                    Some('r') => literal.push(b'\r'),
+                    Some('t') => literal.push(b'\t'),
+                    Some('0') => literal.push(0),
+                    Some(other) => {
+                        return Err(BingrepError::InvalidPattern(format!(
+                            "Unknown escape in replacement: \\{}",
+                            other
+                        )));
+                    }
+                    None => {
+                        return Err(BingrepError::InvalidPattern(
+                            "Replacement ends with a single backslash".to_string(),
+                        ));
+                    }
+                },
+                '$' => {
+                    let part = match chars.peek() {
+                        Some('$') => {
+                            chars.next();
+                            literal.push(b'$');
+                            continue;
+                        }
+                        Some('{') => {
+                            chars.next();
+                            let name: String = chars.by_ref().take_while(|&c| c != '}').collect();
+                            if name.is_empty() {
+                                return Err(BingrepError::InvalidPattern(
+                                    "Empty group reference ${} in replacement".to_string(),
+                                ));
+                            }
+                            match name.parse::<usize>() {
+                                Ok(index) => TemplatePart::Group(index),
+                                Err(_) => TemplatePart::Named(name),
+                            }
+                        }
+                        Some(c) if c.is_ascii_digit() => {
+                            let mut digits = String::new();
+                            while let Some(&c) = chars.peek().filter(|c| c.is_ascii_digit()) {
+                                digits.push(c);
+                                chars.next();
+                            }
+                            TemplatePart::Group(digits.parse().unwrap_or(usize::MAX))
+                        }
+                        _ => {
+                            return Err(BingrepError::InvalidPattern(
+                                "'$' in replacement must be followed by a group or '$'"
+                                    .to_string(),
+                            ));
+                        }
+                    };
+                    if !literal.is_empty() {
+                        parts.push(TemplatePart::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(part);
+                }
+                _ => {
+                    let mut buf = [0u8; 4];
+                    literal.extend_from_slice(ch.encode_utf8(&mut buf).as_bytes());
+                }
+            }
+        }
+
+        if !literal.is_empty() {
+            parts.push(TemplatePart::Literal(literal));
+        }
+
+        Ok(Self { parts })
+    }
+
+    /// Check that every group reference exists in `regex`
+    pub fn validate_groups(&self, regex: &Regex) -> Result<()> {
+        for part in &self.parts {
+            match part {
+                TemplatePart::Group(index) if *index >= regex.captures_len() => {
+                    return Err(BingrepError::InvalidPattern(format!(
+                        "Replacement refers to group ${} but the pattern has {} group(s)",
+                        index,
+                        regex.captures_len() - 1
+                    )));
+                }
+                TemplatePart::Named(name) if !regex.capture_names().flatten().any(|n| n == name) => {
+                    return Err(BingrepError::InvalidPattern(format!(
+                        "Replacement refers to unknown group ${{{}}}",
+                        name
+                    )));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Expand the template for one match
+    pub fn expand(&self, caps: &Captures) -> Vec<u8> {
+        let mut result = Vec::new();
+        for part in &self.parts {
+            match part {
+                TemplatePart::Literal(bytes) => result.extend_from_slice(bytes),
+                TemplatePart::Group(index) => {
+                    if let Some(m) = caps.get(*index) {
+                        result.extend_from_slice(m.as_bytes());
+                    }
+                }
+                TemplatePart::Named(name) => {
+                    if let Some(m) = caps.name(name) {
+                        result.extend_from_slice(m.as_bytes());
+                    }
+                }
+            }
+        }
+        result
+    }
+}
+
+/// A single planned replacement
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Replacement {
+    /// File path of the patched file
+    pub file_path: String,
+    /// Byte offset of the replaced match
+    pub offset: u64,
+    /// Hexadecimal representation of the original bytes
+    pub original: String,
+    /// Hexadecimal representation of the replacement bytes
+    pub replacement: String,
+    /// Length of the original match in bytes
+    #[serde(skip)]
+    original_len: usize,
+    #[serde(skip)]
+    replacement_bytes: Vec<u8>,
+}
+
+impl StructuredRecord for Replacement {
+    fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    fn hex_line(&self, hex_offset_length: usize) -> String {
+        let offset = OutputFormatter::format_offset(self.offset, hex_offset_length);
+        format!(
+            "{} : - {}\n{} : + {}",
+            offset, self.original, offset, self.replacement
+        )
+    }
+
+    fn plain_line(&self) -> String {
+        format!(
+            "{}:{} {} -> {}",
+            self.file_path, self.offset, self.original, self.replacement
+        )
+    }
+}
+
+/// Options controlling how replacements are planned
+#[derive(Debug, Clone, Default)]
+pub struct ReplaceOptions {
+    /// Require every replacement to be as long as the match it replaces
+    pub same_length: bool,
+    /// Maximum number of replacements (0 for unlimited)
+    pub max_replacements: usize,
+    /// Offset where replacing starts
+    pub start_offset: usize,
+}
+
+/// Regex based byte replacer
+pub struct Replacer {
+    regex: Regex,
+    template: ReplacementTemplate,
+    options: ReplaceOptions,
+}
+
+impl Replacer {
+    /// Create a new replacer, validating the template against the regex
+    pub fn new(regex: Regex, template: &str, options: ReplaceOptions) -> Result<Self> {
+        let template = ReplacementTemplate::parse(template)?;
+        template.validate_groups(&regex)?;
+        Ok(Self {
+            regex,
+            template,
+            options,
+        })
+    }
+
+    /// Compute the replacements for `data` without modifying it
+    pub fn plan(
+        &self,
+        data: &[u8],
+        file_path: &str,
+        alignment: Option<&Alignment>,
+    ) -> Result<Vec<Replacement>> {
+        self.plan_reader(&mut Cursor::new(data), file_path, alignment)
+    }
+
+    /// Compute the replacements for all of `reader` without modifying it
+    pub fn plan_reader<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        file_path: &str,
+        alignment: Option<&Alignment>,
+    ) -> Result<Vec<Replacement>> {
+        self.plan_windows(reader, file_path, alignment, PLAN_WINDOW)
+    }
+
+    /// Plan `reader` window by window
+    ///
+    /// Each window is searched from where the previous replacement ended,
+    /// and only matches starting inside it are taken, so the result is the
+    /// same as for one search over the whole input.
+    fn plan_windows<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        file_path: &str,
+        alignment: Option<&Alignment>,
+        window: usize,
+    ) -> Result<Vec<Replacement>> {
+        let matcher = AlignedMatcher::new(&self.regex, alignment)?;
+        let input_size = reader.seek(SeekFrom::End(0))?;
+        let start = (self.options.start_offset as u64).min(input_size);
+        let mut replacements: Vec<Replacement> = Vec::new();
+        let mut next_free = start;
+        let mut pos = start;
+
+        while pos < input_size {
+            let data_offset = pos.saturating_sub(PLAN_LEAD);
+            let lead = (pos - data_offset) as usize;
+            let data = read_chunk(reader, data_offset, lead + 2 * window)?;
+            let window_end = (pos + window as u64).min(input_size);
+            let from = (pos.max(next_free) - data_offset) as usize;
+
+            for hit in matcher.find_iter(&data[from..], data_offset + from as u64) {
+                let match_start = data_offset + (from + hit.start) as u64;
+                if match_start >= window_end {
+                    break;
+                }
+                // Aligned hits may overlap an earlier replacement
+                if match_start < next_free {
+                    continue;
+                }
+                let index = (match_start - data_offset) as usize;
+                let caps = match self.regex.captures_at(&data, index) {
+                    Some(caps) if caps.get(0).map(|m| m.start()) == Some(index) => caps,
+                    _ => continue,
+                };
+                let whole = caps.get(0).unwrap();
+                let new_bytes = self.template.expand(&caps);
+
+                if self.options.same_length && new_bytes.len() != whole.len() {
+                    return Err(BingrepError::InvalidArgument(format!(
+                        "Replacement at offset 0x{:X} is {} bytes but the match is {} bytes (--same-length)",
+                        match_start,
+                        new_bytes.len(),
+                        whole.len()
+                    )));
+                }
+
+                replacements.push(Replacement {
+                    file_path: file_path.to_string(),
+                    offset: match_start,
+                    original: OutputFormatter::format_bytes_as_hex(whole.as_bytes(), " "),
+                    replacement: OutputFormatter::format_bytes_as_hex(&new_bytes, " "),
+                    original_len: whole.len(),
+                    replacement_bytes: new_bytes,
+                });
+                next_free = (data_offset + whole.end() as u64).max(match_start + 1);
+
+                if self.options.max_replacements > 0
+                    && replacements.len() >= self.options.max_replacements
+                {
+                    return Ok(replacements);
+                }
+            }
+
+            pos = window_end.max(next_free);
+        }
+
+        Ok(replacements)
+    }
+
+    /// Build the patched contents from planned replacements
+    pub fn apply(data: &[u8], replacements: &[Replacement]) -> Vec<u8> {
+        let mut result = Vec::with_capacity(data.len());
+        // Writing to a Vec cannot fail
+        Self::copy_patched(&mut Cursor::new(data), &mut result, replacements)
+            .expect("patching in memory");
+        result
+    }
+
+    /// Copy all of `reader` to `out` with planned replacements applied
+    pub fn copy_patched<R: Read, W: Write>(
+        reader: &mut R,
+        out: &mut W,
+        replacements: &[Replacement],
+    ) -> io::Result<()> {
+        let mut pos = 0;
+        for replacement in replacements {
+            io::copy(&mut reader.take(replacement.offset - pos), out)?;
+            out.write_all(&replacement.replacement_bytes)?;
+            io::copy(
+                &mut reader.take(replacement.original_len as u64),
+                &mut io::sink(),
+            )?;
+            pos = replacement.offset + replacement.original_len as u64;
+        }
+        io::copy(reader, out)?;
+        Ok(())
+    }
+}
+
+/// Patch a file in place
+///
+/// Forensic images (E01, VMDK) are never modified, also not through a
+/// symlink: the path is resolved first and the file it points to is the
+/// one patched. With `dry_run` the file is left untouched and only the
+/// planned replacements are returned.
+pub fn patch_file(
+    path: &Path,
+    replacer: &Replacer,
+    alignment: Option<&Alignment>,
+    dry_run: bool,
+    backup: bool,
+) -> Result<Vec<Replacement>> {
+    let refuse = |image: &Path| {
+        Err(BingrepError::InvalidArgument(format!(
+            "Refusing to modify forensic image {}",
+            image.display()
+        )))
+    };
+    if is_forensic_image(path) {
+        return refuse(path);
+    }
+    let target = fs::canonicalize(path)?;
+    if is_forensic_image(&target) {
+        return refuse(&target);
+    }
+
+    let mut input = File::open(&target)?;
+    let replacements = replacer.plan_reader(&mut input, &path.display().to_string(), alignment)?;
+
+    if !dry_run && !replacements.is_empty() {
+        input.seek(SeekFrom::Start(0))?;
+        let mut input = BufReader::new(input);
+        write_atomically(
+            &target,
+            |out| Ok(Replacer::copy_patched(&mut input, out, &replacements)?),
+            backup,
+        )?;
+    }
+
+    Ok(replacements)
+}
+
+/// Path of the backup file written for `path` (`<path>.bak`)
+pub fn backup_path(path: &Path) -> PathBuf {
+    let mut backup = path.as_os_str().to_os_string();
+    backup.push(".bak");
+    PathBuf::from(backup)
+}
+
+/// Replace the contents of `path` through a temporary file in the same directory
+///
+/// `write` produces the new contents. The original is optionally copied to
+/// `<path>.bak` first; the new contents only become visible once the
+/// temporary file has been renamed over `path`. `path` must not be a
+/// symlink, which the rename would replace instead of the file it points to.
+pub fn write_atomically<F>(path: &Path, write: F, backup: bool) -> Result<()>
+where
+    F: FnOnce(&mut BufWriter<File>) -> Result<()>,
+{
+    if fs::symlink_metadata(path)?.file_type().is_symlink() {
+        return Err(BingrepError::InvalidArgument(format!(
+            "Refusing to replace symlink {}; patch the file it points to",
+            path.display()
+        )));
+    }
+    let file_name = path.file_name().ok_or_else(|| {
+        BingrepError::InvalidArgument(format!("Not a file path: {}", path.display()))
+    })?;
+    let temp_path = path.with_file_name(format!(
+        ".{}.hxgrep-{}.tmp",
+        file_name.to_string_lossy(),
+        std::process::id()
+    ));
+
+    let write_temp = || -> Result<()> {
+        let mut temp_file = BufWriter::new(File::create(&temp_path)?);
+        write(&mut temp_file)?;
+        let temp_file = temp_file.into_inner().map_err(|e| e.into_error())?;
+        temp_file.sync_all()?;
+        fs::set_permissions(&temp_path, fs::metadata(path)?.permissions())?;
+        Ok(())
+    };
+
+    if let Err(err) = write_temp() {
+        fs::remove_file(&temp_path).ok();
+        return Err(err);
+    }
+
+    if backup {
+        if let Err(err) = fs::copy(path, backup_path(path)) {
+            fs::remove_file(&temp_path).ok();
+            return Err(err.into());
+        }
+    }
+
+    if let Err(err) = fs::rename(&temp_path, path) {
+        fs::remove_file(&temp_path).ok();
+        return Err(err.into());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replacer(pattern: &str, template: &str, options: ReplaceOptions) -> Result<Replacer> {
+        Replacer::new(Regex::new(pattern).unwrap(), template, options)
+    }
+
+    #[test]
+    fn test_parse_template() {
+        let template = ReplacementTemplate::parse("\\x00A$1${name}$$\\\\").unwrap();
+        assert_eq!(
+            template.parts,
+            vec![
+                TemplatePart::Literal(vec![0x00, b'A']),
+                TemplatePart::Group(1),
+                TemplatePart::Named("name".to_string()),
+                TemplatePart::Literal(vec![b'$', b'\\']),
+            ]
+        );
+
+        assert!(ReplacementTemplate::parse("\\xZZ").is_err());
+        assert!(ReplacementTemplate::parse("\\x4").is_err());
+        assert!(ReplacementTemplate::parse("$").is_err());
+    }
+
+    #[test]
+    fn test_plan_and_apply_with_groups() {
+        let replacer = replacer(
+            r"(?-u)KEY=(?P<value>[0-9]+)",
+            "KEY=\\x00${value}",
+            ReplaceOptions::default(),
+        )
+        .unwrap();
+        let data = b"xxKEY=12;KEY=3;";
+
+        let replacements = replacer.plan(data, "test.bin", None).unwrap();
+        assert_eq!(replacements.len(), 2);
+        assert_eq!(replacements[0].offset, 2);
+        assert_eq!(replacements[0].original, "4B 45 59 3D 31 32");
+        assert_eq!(replacements[0].replacement, "4B 45 59 3D 00 31 32");
+
+        let patched = Replacer::apply(data, &replacements);
+        assert_eq!(patched, b"xxKEY=\x0012;KEY=\x003;");
+    }
+
+    #[test]
+    fn test_same_length_and_max_replacements() {
+        let data = b"AAAA";
+        let strict = ReplaceOptions {
+            same_length: true,
+            ..Default::default()
+        };
+        assert!(replacer("A", "BB", strict.clone())
+            .unwrap()
+            .plan(data, "test.bin", None)
+            .is_err());
+        assert_eq!(
+            replacer("A", "B", strict).unwrap().plan(data, "test.bin", None).unwrap().len(),
+            4
+        );
+
+        let limited = ReplaceOptions {
+            max_replacements: 2,
+            ..Default::default()
+        };
+        let replacements = replacer("A", "B", limited).unwrap().plan(data, "test.bin", None).unwrap();
+        assert_eq!(Replacer::apply(data, &replacements), b"BBAA");
+    }
+
+    #[test]
+    fn test_unknown_group_is_rejected() {
+        assert!(replacer("(A)", "$2", ReplaceOptions::default()).is_err());
+        assert!(replacer("(A)", "${missing}", ReplaceOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_forensic_image_is_refused() {
+        let replacer = replacer("A", "B", ReplaceOptions::default()).unwrap();
+        let result = patch_file(Path::new("evidence.E01"), &replacer, None, false, false);
+        assert!(matches!(result, Err(BingrepError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn test_windowed_plan_matches_whole_input() {
+        let data = b"xxABCDxABCxxxxABCDABCDxxABxCDxxxxxxxABCD".repeat(3);
+        for options in [
+            ReplaceOptions::default(),
+            ReplaceOptions { max_replacements: 5, start_offset: 9, ..Default::default() },
+        ] {
+            let replacer = replacer(r"\bx?ABC(D)?", "[$1]", options).unwrap();
+            let whole = replacer
+                .plan_windows(&mut Cursor::new(&data), "t", None, data.len())
+                .unwrap();
+            for window in [1, 3, 7, 16] {
+                let windowed = replacer
+                    .plan_windows(&mut Cursor::new(&data), "t", None, window)
+                    .unwrap();
+                let offsets = |r: &[Replacement]| r.iter().map(|r| r.offset).collect::<Vec<_>>();
+                assert_eq!(offsets(&windowed), offsets(&whole), "window {}", window);
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks_are_resolved() {
+        let dir = tempfile::tempdir().unwrap();
+        let replacer = replacer("AB", "XY", ReplaceOptions::default()).unwrap();
+
+        // A link to a forensic image is refused and the image is left alone
+        let image = dir.path().join("evidence.E01");
+        let link = dir.path().join("x.bin");
+        fs::write(&image, b"--AB--").unwrap();
+        std::os::unix::fs::symlink(&image, &link).unwrap();
+        let result = patch_file(&link, &replacer, None, false, false);
+        assert!(matches!(result, Err(BingrepError::InvalidArgument(_))));
+        assert_eq!(fs::read(&image).unwrap(), b"--AB--");
+
+        // Other links stay links and the file they point to is patched
+        let target = dir.path().join("target.bin");
+        let link = dir.path().join("y.bin");
+        fs::write(&target, b"--AB--AB").unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+        let replacements = patch_file(&link, &replacer, None, false, true).unwrap();
+        assert_eq!(replacements.len(), 2);
+        assert!(fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+        assert_eq!(fs::read(&target).unwrap(), b"--XY--XY");
+        assert_eq!(fs::read(backup_path(&target)).unwrap(), b"--AB--AB");
+    }
+}
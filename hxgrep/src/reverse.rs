@@ -0,0 +1,600 @@
+//! Reverse hex dump (`xxd -r` equivalent)
+//!
+//! Parses hxgrep's own hex and plain output as well as `xxd` and
+//! `hexdump -C` dumps and rebuilds the binary. Offsets are honoured: gaps
+//! are zero filled (sparse when writing to a file) and `--patch` writes the
+//! bytes into an existing file without truncating it.
+//!
+//! hxgrep offsets carry their radix in the `h`/`o` suffix or `0x`/`0o`
+//! prefix. Offsets without one (`--offset-radix dec`, `--offset-style none`)
+//! cannot be read back unambiguously, so they need the radix to be given.
+//! Byte groups (`-g`) are read in the `--endian` order, big-endian by
+//! default like the dump itself.
+
+use crate::cli::{DumpFormat, OffsetRadix, WordEndian};
+use crate::error::{BingrepError, Result};
+use crate::forensic_image::is_forensic_image;
+use regex::Regex;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, Seek, SeekFrom, Write};
+use std::path::Path;
+
+/// Size of the zero buffer used to fill gaps in streamed output
+const ZERO_FILL_CHUNK: usize = 64 * 1024;
+
+/// One parsed dump line
+#[derive(Debug, PartialEq)]
+pub enum DumpLine {
+    /// Blank line or banner without data
+    Skip,
+    /// Bytes at an explicit offset, or following the previous line
+    Data { offset: Option<u64>, bytes: Vec<u8> },
+    /// `*` line of `hexdump -C`: the previous line repeats
+    Repeat,
+    /// Offset-only line of `hexdump -C` marking the total length
+    End(u64),
+}
+
+fn parse_error(line: usize, text: &str, byte_index: usize, message: String) -> BingrepError {
+    BingrepError::Parse {
+        line,
+        column: text[..byte_index].chars().count() + 1,
+        message,
+    }
+}
+
+/// Output style of an hxgrep dump that the dump itself does not record
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DumpStyle {
+    /// Radix of offsets without a suffix or prefix (`--offset-radix`)
+    pub radix: Option<OffsetRadix>,
+    /// Byte order within groups of `-g 2|4|8` (`--endian`, big when not given)
+    pub endian: Option<WordEndian>,
+}
+
+/// Line parser for the supported dump formats
+pub struct DumpParser {
+    format: Option<DumpFormat>,
+    style: DumpStyle,
+    hex: Regex,
+    plain: Regex,
+    xxd: Regex,
+    hexdump: Regex,
+}
+
+impl DumpParser {
+    /// Create a parser; `DumpFormat::Auto` detects the format from the first data line
+    pub fn new(format: DumpFormat) -> Self {
+        Self {
+            format: (format != DumpFormat::Auto).then_some(format),
+            style: DumpStyle::default(),
+            hex: Regex::new(r"^\s*(0[xo])?([0-9A-Fa-f]+)([ho])? :").unwrap(),
+            plain: Regex::new(r"^(.*?):(\d+) ").unwrap(),
+            xxd: Regex::new(r"^\s*([0-9A-Fa-f]+):").unwrap(),
+            hexdump: Regex::new(r"^([0-9A-Fa-f]+)(?:  |\s*$)").unwrap(),
+        }
+    }
+
+    /// Read unmarked offsets and byte groups with `style`
+    pub fn with_style(mut self, style: DumpStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Format in use (`None` until the first data line with `auto`)
+    pub fn format(&self) -> Option<DumpFormat> {
+        self.format
+    }
+
+    fn detect(&self, line: &str) -> DumpFormat {
+        if self.hex.is_match(line) {
+            DumpFormat::Hex
+        } else if self.plain.is_match(line) {
+            DumpFormat::Plain
+        } else if self.xxd.is_match(line) {
+            DumpFormat::Xxd
+        } else if line.trim() == "*" || (self.hexdump.is_match(line) && line.contains("  ")) {
+            DumpFormat::Hexdump
+        } else {
+            DumpFormat::Raw
+        }
+    }
+
+    /// Parse one line (`line_no` is 1-based and only used for errors)
+    pub fn parse_line(&mut self, line_no: usize, line: &str) -> Result<DumpLine> {
+        let line = line.trim_end_matches(['\r', '\n']);
+        // Blank lines and multi-file banners ("=== Processing: ... ===") carry no data
+        if line.trim().is_empty() || line.starts_with("===") {
+            return Ok(DumpLine::Skip);
+        }
+
+        let format = match self.format {
+            Some(format) => format,
+            None => {
+                let format = self.detect(line);
+                self.format = Some(format);
+                format
+            }
+        };
+
+        let expected = |name: &str| {
+            parse_error(line_no, line, 0, format!("expected a {} line", name))
+        };
+        let parse_offset = |digits: &str, radix: u32, start: usize| {
+            u64::from_str_radix(digits, radix).map_err(|_| {
+                parse_error(line_no, line, start, format!("invalid offset '{}'", digits))
+            })
+        };
+
+        let (offset, data_start, terminator) = match format {
+            DumpFormat::Hex => {
+                let caps = self.hex.captures(line).ok_or_else(|| expected("hxgrep hex"))?;
+                let digits = caps.get(2).unwrap();
+                let marker = (caps.get(1).map(|m| m.as_str()), caps.get(3).map(|m| m.as_str()));
+                let radix = match marker {
+                    (Some("0x"), None) | (None, Some("h")) => 16,
+                    (Some("0o"), None) | (None, Some("o")) => 8,
+                    (None, None) => match self.style.radix {
+                        Some(OffsetRadix::Hex) => 16,
+                        Some(OffsetRadix::Dec) => 10,
+                        Some(OffsetRadix::Oct) => 8,
+                        None => {
+                            return Err(parse_error(
+                                line_no,
+                                line,
+                                digits.start(),
+                                format!(
+                                    "offset '{}' has no radix suffix or prefix; pass --offset-radix hex, dec or oct",
+                                    digits.as_str()
+                                ),
+                            ))
+                        }
+                    },
+                    _ => {
+                        return Err(parse_error(
+                            line_no,
+                            line,
+                            0,
+                            "offset has both a radix prefix and a suffix".to_string(),
+                        ))
+                    }
+                };
+                let offset = parse_offset(digits.as_str(), radix, digits.start())?;
+                (Some(offset), caps.get(0).unwrap().end(), Some("  ["))
+            }
+            DumpFormat::Plain => {
+                let caps = self.plain.captures(line).ok_or_else(|| expected("hxgrep plain"))?;
+                let digits = caps.get(2).unwrap();
+                let offset = parse_offset(digits.as_str(), 10, digits.start())?;
+                (Some(offset), caps.get(0).unwrap().end(), Some("  ["))
+            }
+            DumpFormat::Xxd => {
+                let caps = self.xxd.captures(line).ok_or_else(|| expected("xxd"))?;
+                let digits = caps.get(1).unwrap();
+                let offset = parse_offset(digits.as_str(), 16, digits.start())?;
+                (Some(offset), caps.get(0).unwrap().end(), Some("  "))
+            }
+            DumpFormat::Hexdump => {
+                if line.trim() == "*" {
+                    return Ok(DumpLine::Repeat);
+                }
+                let caps = self.hexdump.captures(line).ok_or_else(|| expected("hexdump -C"))?;
+                let digits = caps.get(1).unwrap();
+                let offset = parse_offset(digits.as_str(), 16, digits.start())?;
+                (Some(offset), caps.get(0).unwrap().end(), Some("|"))
+            }
+            DumpFormat::Raw | DumpFormat::Auto => (None, 0, None),
+        };
+
+        // xxd separates the hex and text columns with two spaces, so skip the
+        // single space following the offset before looking for the terminator
+        let data_start = if line[data_start..].starts_with(' ') {
+            data_start + 1
+        } else {
+            data_start
+        };
+        let data_end = terminator
+            .and_then(|t| line[data_start..].find(t))
+            .map(|pos| data_start + pos)
+            .unwrap_or(line.len());
+
+        // xxd and hexdump -C have fixed big-endian columns
+        let words = match format {
+            DumpFormat::Hex | DumpFormat::Plain | DumpFormat::Raw => {
+                Some(self.style.endian.unwrap_or(WordEndian::Big))
+            }
+            _ => None,
+        };
+        let bytes = parse_hex_bytes(line_no, line, data_start, data_end, words)?;
+
+        match offset {
+            Some(offset) if bytes.is_empty() && format == DumpFormat::Hexdump => {
+                Ok(DumpLine::End(offset))
+            }
+            _ if bytes.is_empty() => Ok(DumpLine::Skip),
+            _ => Ok(DumpLine::Data { offset, bytes }),
+        }
+    }
+}
+
+/// Parse hex digit groups in `line[start..end]`
+///
+/// Groups are separated by whitespace or punctuation (any hxgrep
+/// `--separator`), and each group must contain an even number of digits,
+/// so `41 42`, `41-42`, `4142` (xxd groups) and `41424344` all work.
+///
+/// `words` is the `--endian` of dumps that may hold `-g` words: groups of up
+/// to 8 bytes are reversed for little-endian.
+fn parse_hex_bytes(
+    line_no: usize,
+    line: &str,
+    start: usize,
+    end: usize,
+    words: Option<WordEndian>,
+) -> Result<Vec<u8>> {
+    let mut groups: Vec<Vec<u8>> = Vec::new();
+    let mut group_start = None;
+
+    let mut flush = |group_start: &mut Option<usize>, group_end: usize| -> Result<()> {
+        if let Some(begin) = group_start.take() {
+            let digits = &line[begin..group_end];
+            if !digits.len().is_multiple_of(2) {
+                return Err(parse_error(
+                    line_no,
+                    line,
+                    begin,
+                    format!("odd number of hex digits in '{}'", digits),
+                ));
+            }
+            let group = digits
+                .as_bytes()
+                .chunks(2)
+                .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).unwrap(), 16).unwrap())
+                .collect();
+            groups.push(group);
+        }
+        Ok(())
+    };
+
+    for (index, c) in line[start..end].char_indices() {
+        let index = start + index;
+        if c.is_ascii_hexdigit() {
+            group_start.get_or_insert(index);
+        } else if c.is_alphanumeric() {
+            return Err(parse_error(
+                line_no,
+                line,
+                index,
+                format!("unexpected character '{}'", c),
+            ));
+        } else {
+            flush(&mut group_start, index)?;
+        }
+    }
+    flush(&mut group_start, end)?;
+
+    if words == Some(WordEndian::Little) {
+        for group in groups.iter_mut().filter(|group| group.len() <= 8) {
+            group.reverse();
+        }
+    }
+
+    Ok(groups.into_iter().flatten().collect())
+}
+
+enum Target {
+    File(File),
+    Stream(Box<dyn Write>),
+}
+
+/// Destination of the rebuilt bytes
+pub struct ReverseWriter {
+    target: Target,
+    position: u64,
+    end: u64,
+}
+
+impl ReverseWriter {
+    /// Create (or truncate) `path`; gaps become sparse holes
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(Self {
+            target: Target::File(File::create(path)?),
+            position: 0,
+            end: 0,
+        })
+    }
+
+    /// Open an existing file for patching without truncating it
+    pub fn patch<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if is_forensic_image(path) {
+            return Err(BingrepError::InvalidArgument(format!(
+                "Refusing to modify forensic image {}",
+                path.display()
+            )));
+        }
+        let file = OpenOptions::new().write(true).open(path)?;
+        let end = file.metadata()?.len();
+        Ok(Self {
+            target: Target::File(file),
+            position: 0,
+            end,
+        })
+    }
+
+    /// Write sequentially to a stream; gaps are filled with zeros
+    pub fn stream(writer: Box<dyn Write>) -> Self {
+        Self {
+            target: Target::Stream(writer),
+            position: 0,
+            end: 0,
+        }
+    }
+
+    fn write_at(&mut self, offset: u64, data: &[u8], line_no: usize) -> Result<()> {
+        match &mut self.target {
+            Target::File(file) => {
+                if offset != self.position {
+                    file.seek(SeekFrom::Start(offset))?;
+                }
+                file.write_all(data)?;
+            }
+            Target::Stream(writer) => {
+                if offset < self.position {
+                    return Err(BingrepError::Parse {
+                        line: line_no,
+                        column: 1,
+                        message: format!(
+                            "offset {:X}h goes backwards; use -o to write out of order",
+                            offset
+                        ),
+                    });
+                }
+                write_zeros(writer, offset - self.position)?;
+                writer.write_all(data)?;
+            }
+        }
+
+        self.position = offset + data.len() as u64;
+        self.end = self.end.max(self.position);
+        Ok(())
+    }
+
+    /// Make the output at least `size` bytes long
+    fn extend_to(&mut self, size: u64) -> Result<()> {
+        if size <= self.end {
+            return Ok(());
+        }
+        match &mut self.target {
+            Target::File(file) => file.set_len(size)?,
+            Target::Stream(writer) => {
+                write_zeros(writer, size - self.position)?;
+                self.position = size;
+            }
+        }
+        self.end = size;
+        Ok(())
+    }
+
+    /// Flush the output and return its length
+    pub fn finish(mut self) -> Result<u64> {
+        match &mut self.target {
+            Target::File(file) => file.flush()?,
+            Target::Stream(writer) => writer.flush()?,
+        }
+        Ok(self.end)
+    }
+}
+
+fn write_zeros(writer: &mut dyn Write, mut count: u64) -> Result<()> {
+    let zeros = [0u8; ZERO_FILL_CHUNK];
+    while count > 0 {
+        let n = count.min(ZERO_FILL_CHUNK as u64) as usize;
+        writer.write_all(&zeros[..n])?;
+        count -= n as u64;
+    }
+    Ok(())
+}
+
+/// Rebuild binary data from a dump read from `input`
+///
+/// Returns the format that was parsed (`None` for empty input).
+pub fn reverse_dump<R: BufRead>(
+    mut input: R,
+    format: DumpFormat,
+    style: DumpStyle,
+    output: &mut ReverseWriter,
+) -> Result<Option<DumpFormat>> {
+    let mut parser = DumpParser::new(format).with_style(style);
+    let mut next_offset = 0u64;
+    let mut previous: Option<(u64, Vec<u8>)> = None;
+    let mut repeat_pending = false;
+    let mut raw_line = Vec::new();
+    let mut line_no = 0;
+
+    loop {
+        raw_line.clear();
+        if input.read_until(b'\n', &mut raw_line)? == 0 {
+            break;
+        }
+        line_no += 1;
+        let line = String::from_utf8_lossy(&raw_line);
+
+        let parsed = parser.parse_line(line_no, &line)?;
+        let target = match &parsed {
+            DumpLine::Skip => continue,
+            DumpLine::Repeat => {
+                if previous.is_none() {
+                    return Err(BingrepError::Parse {
+                        line: line_no,
+                        column: 1,
+                        message: "'*' without a preceding data line".to_string(),
+                    });
+                }
+                repeat_pending = true;
+                continue;
+            }
+            DumpLine::Data { offset, .. } => offset.unwrap_or(next_offset),
+            DumpLine::End(offset) => *offset,
+        };
+
+        // Expand "*": the previous line repeats up to the next offset
+        if repeat_pending {
+            repeat_pending = false;
+            if let Some((offset, bytes)) = &previous {
+                let mut pos = offset + bytes.len() as u64;
+                while pos < target {
+                    let n = (target - pos).min(bytes.len() as u64) as usize;
+                    output.write_at(pos, &bytes[..n], line_no)?;
+                    pos += n as u64;
+                }
+            }
+        }
+
+        match parsed {
+            DumpLine::Data { bytes, .. } => {
+                output.write_at(target, &bytes, line_no)?;
+                next_offset = target + bytes.len() as u64;
+                previous = Some((target, bytes));
+            }
+            DumpLine::End(offset) => output.extend_to(offset)?,
+            DumpLine::Skip | DumpLine::Repeat => {}
+        }
+    }
+
+    Ok(parser.format())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use std::sync::{Arc, Mutex};
+
+    /// Writer sharing its buffer so tests can inspect streamed output
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn reverse_to_vec(dump: &str, format: DumpFormat) -> Result<(Vec<u8>, Option<DumpFormat>)> {
+        reverse_styled(dump, format, DumpStyle::default())
+    }
+
+    fn reverse_styled(
+        dump: &str,
+        format: DumpFormat,
+        style: DumpStyle,
+    ) -> Result<(Vec<u8>, Option<DumpFormat>)> {
+        let buffer = SharedBuffer::default();
+        let mut writer = ReverseWriter::stream(Box::new(buffer.clone()));
+        let detected = reverse_dump(Cursor::new(dump), format, style, &mut writer)?;
+        writer.finish()?;
+        let data = buffer.0.lock().unwrap().clone();
+        Ok((data, detected))
+    }
+
+    #[test]
+    fn test_hxgrep_hex_with_gap_and_annotation() {
+        let dump = "0h : 41 42\n4h : 43-44  [LBA 0]\n";
+        let (data, format) = reverse_to_vec(dump, DumpFormat::Auto).unwrap();
+        assert_eq!(data, b"AB\0\0CD");
+        assert_eq!(format, Some(DumpFormat::Hex));
+    }
+
+    #[test]
+    fn test_hxgrep_plain_and_raw() {
+        let (data, format) = reverse_to_vec("C:\\dump.bin:2 4142\n", DumpFormat::Auto).unwrap();
+        assert_eq!(data, b"\0\0AB");
+        assert_eq!(format, Some(DumpFormat::Plain));
+
+        let (data, format) = reverse_to_vec("41 42\n43,44\n", DumpFormat::Auto).unwrap();
+        assert_eq!(data, b"ABCD");
+        assert_eq!(format, Some(DumpFormat::Raw));
+    }
+
+    #[test]
+    fn test_xxd_ignores_text_column() {
+        let dump = "00000000: 4142 4344 4546 4748  ABCDEFGH\n00000008: 3031                                     01\n";
+        let (data, format) = reverse_to_vec(dump, DumpFormat::Auto).unwrap();
+        assert_eq!(data, b"ABCDEFGH01");
+        assert_eq!(format, Some(DumpFormat::Xxd));
+    }
+
+    #[test]
+    fn test_hexdump_repeat_and_end() {
+        let dump = "\
+00000000  00 00 00 00 00 00 00 00  00 00 00 00 00 00 00 00  |................|
+*
+00000030  41 42 43 44 45 46 47 48  49 4a 4b 4c 4d 4e 4f 50  |ABCDEFGHIJKLMNOP|
+00000040
+";
+        let (data, format) = reverse_to_vec(dump, DumpFormat::Auto).unwrap();
+        assert_eq!(format, Some(DumpFormat::Hexdump));
+        assert_eq!(data.len(), 0x40);
+        assert!(data[..0x30].iter().all(|&b| b == 0));
+        assert_eq!(&data[0x30..], b"ABCDEFGHIJKLMNOP");
+    }
+
+    #[test]
+    fn test_offset_markers_and_radix() {
+        let dump = "0x00 : 41\n0o1 : 42\n2h : 43\n03o : 44\n";
+        let (data, format) = reverse_to_vec(dump, DumpFormat::Auto).unwrap();
+        assert_eq!(data, b"ABCD");
+        assert_eq!(format, Some(DumpFormat::Hex));
+
+        // Unmarked offsets need the radix
+        let dump = "10 : 41\n";
+        match reverse_to_vec(dump, DumpFormat::Auto) {
+            Err(BingrepError::Parse { column, .. }) => assert_eq!(column, 1),
+            other => panic!("unexpected result: {:?}", other.map(|(d, _)| d)),
+        }
+        let style = DumpStyle { radix: Some(OffsetRadix::Dec), endian: None };
+        assert_eq!(reverse_styled(dump, DumpFormat::Auto, style).unwrap().0.len(), 11);
+        let style = DumpStyle { radix: Some(OffsetRadix::Hex), endian: None };
+        assert_eq!(reverse_styled(dump, DumpFormat::Auto, style).unwrap().0.len(), 17);
+    }
+
+    #[test]
+    fn test_grouped_bytes_default_to_big_endian() {
+        let dump = "0h : 04030201 0605\n";
+        assert_eq!(reverse_to_vec(dump, DumpFormat::Auto).unwrap().0, [4, 3, 2, 1, 6, 5]);
+
+        let little = DumpStyle { radix: None, endian: Some(WordEndian::Little) };
+        let (data, _) = reverse_styled(dump, DumpFormat::Auto, little).unwrap();
+        assert_eq!(data, [1, 2, 3, 4, 5, 6]);
+
+        let big = DumpStyle { radix: None, endian: Some(WordEndian::Big) };
+        let (data, _) = reverse_styled(dump, DumpFormat::Auto, big).unwrap();
+        assert_eq!(data, [4, 3, 2, 1, 6, 5]);
+
+        // Single bytes, one run of bytes and xxd columns are not words
+        assert!(reverse_to_vec("0h : 01 02 03\n3h : 0102030405060708\n", DumpFormat::Hex).is_ok());
+        assert_eq!(reverse_to_vec("00000000: 0102 0304\n", DumpFormat::Auto).unwrap().0, [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_parse_errors_report_line_and_column() {
+        match reverse_to_vec("0h : 41 42\n2h : 43 4G\n", DumpFormat::Auto) {
+            Err(BingrepError::Parse { line, column, .. }) => assert_eq!((line, column), (2, 10)),
+            other => panic!("unexpected result: {:?}", other.map(|(d, _)| d)),
+        }
+
+        match reverse_to_vec("414\n", DumpFormat::Raw) {
+            Err(BingrepError::Parse { line, column, .. }) => assert_eq!((line, column), (1, 1)),
+            other => panic!("unexpected result: {:?}", other.map(|(d, _)| d)),
+        }
+
+        // Streams cannot seek back
+        assert!(reverse_to_vec("4h : 41\n0h : 42\n", DumpFormat::Hex).is_err());
+    }
+}
@@ -0,0 +1,495 @@
+//! Regex search producing structured match records
+//!
+//! The hex output of the search mode is streamed straight to stdout by
+//! [`crate::stream::FileProcessor`] and [`crate::parallel::ParallelProcessor`].
+//! Structured formats (JSON, CSV, plain), `--only-matching` and `--capture`
+//! instead collect [`BinaryMatch`] records through this module, including
+//! the capture groups of each match.
+
+use crate::alignment::{AlignedMatcher, Alignment};
+use crate::error::{BingrepError, Result};
+use crate::inspector::{Inspector, INSPECT_LEN};
+use crate::output::OutputFormatter;
+use crate::cli::{Cli, HashAlgorithm};
+use crate::hashing::RegionHashes;
+use crate::parallel::{read_chunk, scan_chunks, scan_chunks_while, scan_ranges, Chunk};
+use crate::progress;
+use crate::structured_output::{BinaryMatch, CaptureGroup};
+use regex::bytes::Regex;
+use std::io::{Read, Seek};
+use std::ops::Range;
+
+/// Bytes read after each chunk so matches crossing the boundary are complete
+const CHUNK_OVERLAP: usize = 64 * 1024;
+
+/// grep-style modes that report per-input results instead of listing matches
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportMode {
+    /// Print the number of matches (`-c`)
+    Count,
+    /// Print the names of inputs with at least one match (`-l`)
+    FilesWithMatches,
+    /// Print the names of inputs without any match (`-L`)
+    FilesWithoutMatch,
+    /// Print nothing and stop at the first match (`-q`)
+    Quiet,
+}
+
+impl ReportMode {
+    /// Report mode selected on the command line, if any
+    pub fn from_cli(cli: &Cli) -> Option<Self> {
+        if cli.quiet {
+            Some(Self::Quiet)
+        } else if cli.files_with_matches {
+            Some(Self::FilesWithMatches)
+        } else if cli.files_without_match {
+            Some(Self::FilesWithoutMatch)
+        } else if cli.count {
+            Some(Self::Count)
+        } else {
+            None
+        }
+    }
+
+    /// Matches to count per input before its result is known (0: all)
+    ///
+    /// Only `--count` needs every match (up to `-n`); the other modes only
+    /// need to know whether there is one.
+    pub fn match_limit(&self, limit: usize) -> usize {
+        match self {
+            Self::Count => limit,
+            _ => 1,
+        }
+    }
+
+    /// Line to print for an input with `count` matches, if any
+    ///
+    /// Like grep, `--count` prints the bare number unless several inputs
+    /// are searched (`with_filename`).
+    pub fn report_line(&self, file_path: &str, with_filename: bool, count: usize) -> Option<String> {
+        match self {
+            Self::Count if with_filename => Some(format!("{}:{}", file_path, count)),
+            Self::Count => Some(count.to_string()),
+            Self::FilesWithMatches if count > 0 => Some(file_path.to_string()),
+            Self::FilesWithoutMatch if count == 0 => Some(file_path.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Whether an input with `count` matches makes the run successful
+    pub fn is_success(&self, count: usize) -> bool {
+        match self {
+            Self::FilesWithoutMatch => count == 0,
+            _ => count > 0,
+        }
+    }
+}
+
+/// Capture groups to attach to match records
+#[derive(Debug, Clone, PartialEq)]
+pub enum GroupSelection {
+    /// Every capture group except the whole match
+    All,
+    /// Only the listed group indices, in the given order
+    Only(Vec<usize>),
+}
+
+impl GroupSelection {
+    /// Resolve `--capture` arguments (group numbers or names) against `regex`
+    ///
+    /// No arguments selects all groups.
+    pub fn parse(regex: &Regex, specs: &[String]) -> Result<Self> {
+        if specs.is_empty() {
+            return Ok(Self::All);
+        }
+
+        let mut indices = Vec::with_capacity(specs.len());
+        for spec in specs {
+            let index = match spec.parse::<usize>() {
+                Ok(index) if index < regex.captures_len() => index,
+                Ok(index) => {
+                    return Err(BingrepError::InvalidArgument(format!(
+                        "--capture {}: the pattern only has {} capture group(s)",
+                        index,
+                        regex.captures_len() - 1
+                    )))
+                }
+                Err(_) => regex
+                    .capture_names()
+                    .position(|name| name == Some(spec.as_str()))
+                    .ok_or_else(|| {
+                        BingrepError::InvalidArgument(format!(
+                            "--capture {}: no capture group with this name",
+                            spec
+                        ))
+                    })?,
+            };
+            indices.push(index);
+        }
+
+        Ok(Self::Only(indices))
+    }
+}
+
+/// Collects regex matches as [`BinaryMatch`] records
+pub struct MatchCollector<'r> {
+    regex: &'r Regex,
+    matcher: AlignedMatcher<'r>,
+    inspector: Option<&'r Inspector>,
+    groups: Option<GroupSelection>,
+    group_names: Vec<String>,
+    hashing: Option<(&'r [HashAlgorithm], usize)>,
+}
+
+impl<'r> MatchCollector<'r> {
+    /// Create a collector for `regex` with an optional alignment constraint
+    pub fn new(regex: &'r Regex, alignment: Option<&Alignment>) -> Result<Self> {
+        let group_names = regex
+            .capture_names()
+            .enumerate()
+            .map(|(index, name)| name.map_or_else(|| index.to_string(), str::to_string))
+            .collect();
+
+        Ok(Self {
+            regex,
+            matcher: AlignedMatcher::new(regex, alignment)?,
+            inspector: None,
+            groups: None,
+            group_names,
+            hashing: None,
+        })
+    }
+
+    /// Attach an inspector whose interpretations are added to every record
+    pub fn with_inspector(mut self, inspector: Option<&'r Inspector>) -> Self {
+        self.inspector = inspector;
+        self
+    }
+
+    /// Attach the selected capture groups to every record
+    pub fn with_groups(mut self, groups: Option<GroupSelection>) -> Self {
+        self.groups = groups;
+        self
+    }
+
+    /// Attach digests of each match region to every record (`--hash-matches`)
+    ///
+    /// The region is the matched bytes (`window` 0) or `window` bytes from
+    /// the start of the match, cut short at the end of the input.
+    pub fn with_hashes(mut self, algorithms: &'r [HashAlgorithm], window: usize) -> Self {
+        self.hashing = (!algorithms.is_empty()).then_some((algorithms, window));
+        self
+    }
+
+    /// Collect matches from the current position of `reader` to the end
+    ///
+    /// Records are returned in offset order; at most `limit` are kept
+    /// (0: unlimited).
+    pub fn collect<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        file_path: &str,
+        chunk_size: usize,
+        parallel: bool,
+        limit: usize,
+    ) -> Result<Vec<BinaryMatch>> {
+        let mut matches = scan_chunks(reader, chunk_size, 0, CHUNK_OVERLAP, parallel, |chunk| {
+            let matches = self.collect_chunk(chunk, file_path);
+            progress::record_matches(matches.len());
+            matches
+        })?;
+
+        if limit > 0 {
+            matches.truncate(limit);
+        }
+        self.hash_outside_chunks(reader, &mut matches)?;
+        Ok(matches)
+    }
+
+    /// Collect matches starting inside the given ranges of `reader`
+    ///
+    /// Used to verify the candidate regions of an n-gram index; a match may
+    /// extend past the end of its range. Records are returned in offset
+    /// order and reading stops once `limit` were found (0: unlimited).
+    pub fn collect_ranges<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        file_path: &str,
+        ranges: &[Range<u64>],
+        chunk_size: usize,
+        parallel: bool,
+        limit: usize,
+    ) -> Result<Vec<BinaryMatch>> {
+        let mut matches = scan_ranges(
+            reader,
+            ranges,
+            chunk_size,
+            CHUNK_OVERLAP,
+            parallel,
+            |chunk| {
+                let matches = self.collect_chunk(chunk, file_path);
+                progress::record_matches(matches.len());
+                matches
+            },
+            |matches| limit == 0 || matches.len() < limit,
+        )?;
+
+        if limit > 0 {
+            matches.truncate(limit);
+        }
+        self.hash_outside_chunks(reader, &mut matches)?;
+        Ok(matches)
+    }
+
+    /// Count matches from the current position of `reader` to the end
+    ///
+    /// Stops reading once `limit` matches were found (0: unlimited), so
+    /// existence checks do not scan the whole input.
+    pub fn count<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        chunk_size: usize,
+        parallel: bool,
+        limit: usize,
+    ) -> Result<usize> {
+        let counts = scan_chunks_while(
+            reader,
+            chunk_size,
+            0,
+            CHUNK_OVERLAP,
+            parallel,
+            |chunk| {
+                let count = self.count_chunk(chunk);
+                progress::record_matches(count);
+                vec![count]
+            },
+            |counts| limit == 0 || counts.iter().sum::<usize>() < limit,
+        )?;
+
+        let total: usize = counts.into_iter().sum();
+        Ok(if limit > 0 { total.min(limit) } else { total })
+    }
+
+    /// Count matches starting inside the owned range of a chunk
+    fn count_chunk(&self, chunk: &Chunk) -> usize {
+        self.matcher
+            .find_iter(&chunk.data, chunk.data_offset)
+            .filter(|hit| chunk.owned.contains(&(chunk.data_offset + hit.start as u64)))
+            .count()
+    }
+
+    /// Collect matches starting inside the owned range of a chunk
+    pub fn collect_chunk(&self, chunk: &Chunk, file_path: &str) -> Vec<BinaryMatch> {
+        let data = &chunk.data;
+        let mut matches = Vec::new();
+
+        for hit in self.matcher.find_iter(data, chunk.data_offset) {
+            let offset = chunk.data_offset + hit.start as u64;
+            if !chunk.owned.contains(&offset) {
+                continue;
+            }
+
+            let mut record = BinaryMatch::new(
+                file_path.to_string(),
+                offset,
+                OutputFormatter::format_bytes_as_hex(&data[hit.clone()], " "),
+                hit.len(),
+            );
+            if let Some(inspector) = self.inspector {
+                let end = (hit.start + INSPECT_LEN).min(data.len());
+                record.inspection = Some(inspector.inspect(&data[hit.start..end]));
+            }
+            if let Some(selection) = &self.groups {
+                record.groups = Some(self.capture_groups(data, chunk.data_offset, hit.start, selection));
+            }
+            if let Some((algorithms, window)) = self.hashing {
+                // Regions running past the chunk are hashed after the scan
+                let end = if window == 0 { hit.end } else { hit.start + window };
+                if end <= data.len() {
+                    record.hashes = Some(RegionHashes::of(algorithms, &data[hit.start..end]));
+                }
+            }
+            matches.push(record);
+        }
+
+        matches
+    }
+
+    /// Hash the match regions that did not fit in the chunk of their match
+    fn hash_outside_chunks<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        matches: &mut [BinaryMatch],
+    ) -> Result<()> {
+        let Some((algorithms, window)) = self.hashing else {
+            return Ok(());
+        };
+        for m in matches.iter_mut().filter(|m| m.hashes.is_none()) {
+            let region = read_chunk(reader, m.offset, window)?;
+            m.hashes = Some(RegionHashes::of(algorithms, &region));
+        }
+        Ok(())
+    }
+
+    /// Capture groups of the match starting at `start` in `data`
+    ///
+    /// Groups that did not participate in the match are left out.
+    fn capture_groups(
+        &self,
+        data: &[u8],
+        data_offset: u64,
+        start: usize,
+        selection: &GroupSelection,
+    ) -> Vec<CaptureGroup> {
+        let Some(captures) = self.regex.captures_at(data, start) else {
+            return Vec::new();
+        };
+
+        let indices: Vec<usize> = match selection {
+            GroupSelection::All => (1..captures.len()).collect(),
+            GroupSelection::Only(indices) => indices.clone(),
+        };
+
+        indices
+            .into_iter()
+            .filter_map(|index| {
+                captures.get(index).map(|group| {
+                    CaptureGroup::new(
+                        self.group_names[index].clone(),
+                        data_offset + group.start() as u64,
+                        OutputFormatter::format_bytes_as_hex(group.as_bytes(), " "),
+                        group.len(),
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::InspectType;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_collect_across_chunks() {
+        let mut data = vec![0u8; 300];
+        data[10..14].copy_from_slice(b"MARK");
+        data[98..102].copy_from_slice(b"MARK");
+        data[250..254].copy_from_slice(b"MARK");
+
+        let regex = Regex::new("MARK").unwrap();
+        let collector = MatchCollector::new(&regex, None).unwrap();
+
+        for parallel in [false, true] {
+            let mut reader = Cursor::new(data.clone());
+            let matches = collector.collect(&mut reader, "test.bin", 100, parallel, 0).unwrap();
+            let offsets: Vec<u64> = matches.iter().map(|m| m.offset).collect();
+            assert_eq!(offsets, vec![10, 98, 250]);
+            assert_eq!(matches[1].hex_data, "4D 41 52 4B");
+            assert_eq!(matches[1].ascii_data.as_deref(), Some("MARK"));
+        }
+
+        let mut reader = Cursor::new(data);
+        let matches = collector.collect(&mut reader, "test.bin", 100, false, 2).unwrap();
+        assert_eq!(matches.len(), 2);
+    }
+
+    #[test]
+    fn test_count_stops_at_limit() {
+        let data = b"ab".repeat(500);
+        let regex = Regex::new("ab").unwrap();
+        let collector = MatchCollector::new(&regex, None).unwrap();
+
+        for parallel in [false, true] {
+            let mut reader = Cursor::new(data.clone());
+            assert_eq!(collector.count(&mut reader, 64, parallel, 0).unwrap(), 500);
+
+            let mut reader = Cursor::new(data.clone());
+            assert_eq!(collector.count(&mut reader, 64, parallel, 1).unwrap(), 1);
+        }
+    }
+
+    #[test]
+    fn test_report_mode_lines() {
+        assert_eq!(ReportMode::Count.report_line("a", false, 3).unwrap(), "3");
+        assert_eq!(ReportMode::Count.report_line("a", true, 0).unwrap(), "a:0");
+        assert_eq!(ReportMode::FilesWithMatches.report_line("a", false, 2).unwrap(), "a");
+        assert!(ReportMode::FilesWithMatches.report_line("a", false, 0).is_none());
+        assert_eq!(ReportMode::FilesWithoutMatch.report_line("a", true, 0).unwrap(), "a");
+        assert!(ReportMode::Quiet.report_line("a", false, 1).is_none());
+        assert!(ReportMode::FilesWithoutMatch.is_success(0));
+        assert!(!ReportMode::Count.is_success(0));
+    }
+
+    #[test]
+    fn test_collect_named_groups() {
+        let regex = Regex::new("(?-u)(?P<magic>\\x89PNG)(?P<len>.{4})(x)?").unwrap();
+        let mut data = b"junk".to_vec();
+        data.extend_from_slice(b"\x89PNG\x00\x00\x00\x0D");
+
+        let all = GroupSelection::parse(&regex, &[]).unwrap();
+        let collector = MatchCollector::new(&regex, None).unwrap().with_groups(Some(all));
+        let matches = collector.collect(&mut Cursor::new(data.clone()), "a.png", 1024, false, 0).unwrap();
+        let groups = matches[0].groups.as_ref().unwrap();
+        // The optional third group did not participate
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].name, "magic");
+        assert_eq!(groups[0].offset, 4);
+        assert_eq!(groups[1].name, "len");
+        assert_eq!(groups[1].offset, 8);
+        assert_eq!(groups[1].hex_data, "00 00 00 0D");
+
+        let only = GroupSelection::parse(&regex, &["len".to_string(), "0".to_string()]).unwrap();
+        assert_eq!(only, GroupSelection::Only(vec![2, 0]));
+        assert!(GroupSelection::parse(&regex, &["4".to_string()]).is_err());
+        assert!(GroupSelection::parse(&regex, &["size".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_collect_with_inspector() {
+        let regex = Regex::new("(?-u)\\x01\\x02").unwrap();
+        let inspector = Inspector::new(&[InspectType::U16]);
+        let collector = MatchCollector::new(&regex, None)
+            .unwrap()
+            .with_inspector(Some(&inspector));
+
+        let mut reader = Cursor::new(vec![0x00, 0x01, 0x02]);
+        let matches = collector.collect(&mut reader, "test.bin", 1024, false, 0).unwrap();
+        let inspection = matches[0].inspection.as_ref().unwrap();
+        assert_eq!(inspection[0].value, "513");
+        assert_eq!(inspection[1].value, "258");
+    }
+
+    #[test]
+    fn test_collect_with_hashes() {
+        let mut data = vec![0u8; 200_000];
+        data[10..13].copy_from_slice(b"KEY");
+        data[199_998..].copy_from_slice(b"KE");
+        let regex = Regex::new("KEY?").unwrap();
+        let algorithms = [HashAlgorithm::Sha1];
+
+        let collector = MatchCollector::new(&regex, None)
+            .unwrap()
+            .with_hashes(&algorithms, 0);
+        let matches = collector
+            .collect(&mut Cursor::new(data.clone()), "test.bin", 1024, false, 0)
+            .unwrap();
+        assert_eq!(matches[0].hashes, Some(RegionHashes::of(&algorithms, b"KEY")));
+        assert_eq!(matches[1].hashes, Some(RegionHashes::of(&algorithms, b"KE")));
+
+        // Windows longer than the chunk overlap and windows cut at the end
+        let collector = MatchCollector::new(&regex, None)
+            .unwrap()
+            .with_hashes(&algorithms, 100_000);
+        let matches = collector
+            .collect(&mut Cursor::new(data.clone()), "test.bin", 1024, true, 0)
+            .unwrap();
+        assert_eq!(
+            matches[0].hashes,
+            Some(RegionHashes::of(&algorithms, &data[10..100_010]))
+        );
+        assert_eq!(matches[1].hashes.as_ref().unwrap().length, 2);
+    }
+}
@@ -0,0 +1,494 @@
+//! Byte histogram and statistics
+//!
+//! Profiles an unknown blob before searching it: a 256-bin histogram, the
+//! share of zero, 0xFF and printable bytes, the longest run of each byte
+//! value and the overall entropy, optionally broken down per fixed range.
+
+use crate::entropy::entropy_from_counts;
+use crate::error::{BingrepError, Result};
+use crate::output::OutputFormatter;
+use crate::parallel::scan_chunks;
+use crate::structured_output::StructuredRecord;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Seek};
+
+/// A run of identical bytes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Run {
+    byte: u8,
+    offset: u64,
+    length: u64,
+}
+
+/// Mergeable byte statistics of a contiguous region
+#[derive(Debug, Clone)]
+pub struct ByteStats {
+    offset: u64,
+    length: u64,
+    histogram: [u64; 256],
+    longest: [Run; 256],
+    head: Run,
+    tail: Run,
+}
+
+impl ByteStats {
+    /// Statistics of an empty region starting at `offset`
+    pub fn empty(offset: u64) -> Self {
+        Self {
+            offset,
+            length: 0,
+            histogram: [0; 256],
+            longest: [Run::default(); 256],
+            head: Run::default(),
+            tail: Run::default(),
+        }
+    }
+
+    /// Compute statistics of `data`, which starts at absolute offset `offset`
+    pub fn from_bytes(data: &[u8], offset: u64) -> Self {
+        let mut stats = Self::empty(offset);
+        let mut pos = 0;
+
+        while pos < data.len() {
+            let byte = data[pos];
+            let start = pos;
+            while pos < data.len() && data[pos] == byte {
+                pos += 1;
+            }
+
+            let run = Run {
+                byte,
+                offset: offset + start as u64,
+                length: (pos - start) as u64,
+            };
+            stats.histogram[byte as usize] += run.length;
+            stats.record_run(run);
+            if start == 0 {
+                stats.head = run;
+            }
+            stats.tail = run;
+        }
+
+        stats.length = data.len() as u64;
+        stats
+    }
+
+    /// Keep `run` if it is the longest (earliest on ties) for its byte
+    fn record_run(&mut self, run: Run) {
+        let longest = &mut self.longest[run.byte as usize];
+        if run.length > longest.length {
+            *longest = run;
+        }
+    }
+
+    /// Append the statistics of the region directly following this one
+    pub fn merge(&mut self, next: &ByteStats) {
+        if next.length == 0 {
+            return;
+        }
+        if self.length == 0 {
+            *self = next.clone();
+            return;
+        }
+
+        for (count, next_count) in self.histogram.iter_mut().zip(next.histogram.iter()) {
+            *count += next_count;
+        }
+        for run in next.longest.iter().filter(|run| run.length > 0) {
+            self.record_run(*run);
+        }
+
+        // A run crossing the boundary is split between our tail and their head
+        if self.tail.byte == next.head.byte {
+            let joined = Run {
+                byte: self.tail.byte,
+                offset: self.tail.offset,
+                length: self.tail.length + next.head.length,
+            };
+            self.record_run(joined);
+            if self.head.length == self.length {
+                self.head = joined;
+            }
+            self.tail = if next.tail.length == next.length {
+                joined
+            } else {
+                next.tail
+            };
+        } else {
+            self.tail = next.tail;
+        }
+
+        self.length += next.length;
+    }
+
+    /// Number of bytes covered
+    pub fn len(&self) -> u64 {
+        self.length
+    }
+
+    /// Whether no bytes are covered
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    fn ratio(&self, count: u64) -> f64 {
+        if self.length == 0 {
+            0.0
+        } else {
+            count as f64 / self.length as f64
+        }
+    }
+
+    /// Summary report of this region
+    pub fn report(&self, file_path: &str, scope: StatsScope) -> StatsReport {
+        let printable: u64 = (0..=255u8)
+            .filter(|&b| is_printable(b))
+            .map(|b| self.histogram[b as usize])
+            .sum();
+
+        // Earliest byte value wins ties
+        let (most_common_byte, most_common_count) = self
+            .histogram
+            .iter()
+            .enumerate()
+            .fold((0, 0), |best, (byte, &count)| {
+                if count > best.1 {
+                    (byte, count)
+                } else {
+                    best
+                }
+            });
+
+        let longest = self
+            .longest
+            .iter()
+            .fold(Run::default(), |best, run| {
+                if run.length > best.length
+                    || (run.length == best.length && run.length > 0 && run.offset < best.offset)
+                {
+                    *run
+                } else {
+                    best
+                }
+            });
+
+        StatsReport {
+            file_path: file_path.to_string(),
+            scope,
+            offset: self.offset,
+            length: self.length,
+            entropy: entropy_from_counts(&self.histogram, self.length),
+            zero_ratio: self.ratio(self.histogram[0x00]),
+            ff_ratio: self.ratio(self.histogram[0xFF]),
+            printable_ratio: self.ratio(printable),
+            distinct_bytes: self.histogram.iter().filter(|&&count| count > 0).count(),
+            most_common_byte: format!("0x{:02X}", most_common_byte),
+            most_common_count,
+            longest_run_byte: format!("0x{:02X}", longest.byte),
+            longest_run_length: longest.length,
+            longest_run_offset: longest.offset,
+        }
+    }
+
+    /// All 256 histogram bins of this region
+    pub fn bins(&self, file_path: &str, scope: StatsScope) -> Vec<ByteBin> {
+        (0..=255u8)
+            .map(|byte| {
+                let count = self.histogram[byte as usize];
+                let longest = self.longest[byte as usize];
+                ByteBin {
+                    file_path: file_path.to_string(),
+                    scope,
+                    range_offset: self.offset,
+                    byte: format!("0x{:02X}", byte),
+                    count,
+                    ratio: self.ratio(count),
+                    longest_run: longest.length,
+                    longest_run_offset: (longest.length > 0).then_some(longest.offset),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Printable byte for the printable share (ASCII graphic, space, tab, CR, LF)
+fn is_printable(b: u8) -> bool {
+    b.is_ascii_graphic() || matches!(b, b' ' | b'\t' | b'\r' | b'\n')
+}
+
+/// Whether a record describes one range or the whole input
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatsScope {
+    Total,
+    Range,
+}
+
+/// Statistics summary of a region
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StatsReport {
+    /// File path of the input
+    pub file_path: String,
+    /// Whole input or one range
+    pub scope: StatsScope,
+    /// Start offset of the region
+    pub offset: u64,
+    /// Length of the region in bytes
+    pub length: u64,
+    /// Shannon entropy in bits per byte
+    pub entropy: f64,
+    /// Share of 0x00 bytes (0.0 - 1.0)
+    pub zero_ratio: f64,
+    /// Share of 0xFF bytes (0.0 - 1.0)
+    pub ff_ratio: f64,
+    /// Share of printable ASCII bytes (0.0 - 1.0)
+    pub printable_ratio: f64,
+    /// Number of distinct byte values
+    pub distinct_bytes: usize,
+    /// Most frequent byte value
+    pub most_common_byte: String,
+    /// Occurrences of the most frequent byte value
+    pub most_common_count: u64,
+    /// Byte value of the longest run
+    pub longest_run_byte: String,
+    /// Length of the longest run
+    pub longest_run_length: u64,
+    /// Offset of the longest run
+    pub longest_run_offset: u64,
+}
+
+impl StructuredRecord for StatsReport {
+    fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    fn hex_line(&self, hex_offset_length: usize) -> String {
+        let position = match self.scope {
+            StatsScope::Total => "total".to_string(),
+            StatsScope::Range => OutputFormatter::format_offset(self.offset, hex_offset_length),
+        };
+        format!(
+            "{} : {} bytes, entropy {:.4}, zero {:.2}%, FF {:.2}%, printable {:.2}%, {} distinct, most common {} x {}, longest run {} x {} @ {}",
+            position,
+            self.length,
+            self.entropy,
+            self.zero_ratio * 100.0,
+            self.ff_ratio * 100.0,
+            self.printable_ratio * 100.0,
+            self.distinct_bytes,
+            self.most_common_byte,
+            self.most_common_count,
+            self.longest_run_byte,
+            self.longest_run_length,
+            OutputFormatter::format_offset(self.longest_run_offset, hex_offset_length),
+        )
+    }
+
+    fn plain_line(&self) -> String {
+        format!(
+            "{}:{} {} {:.4} {:.4} {:.4} {:.4}",
+            self.file_path,
+            self.offset,
+            self.length,
+            self.entropy,
+            self.zero_ratio,
+            self.ff_ratio,
+            self.printable_ratio
+        )
+    }
+}
+
+/// One histogram bin
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ByteBin {
+    /// File path of the input
+    pub file_path: String,
+    /// Whole input or one range
+    pub scope: StatsScope,
+    /// Start offset of the region the bin belongs to
+    pub range_offset: u64,
+    /// Byte value
+    pub byte: String,
+    /// Occurrences of the byte value
+    pub count: u64,
+    /// Share of the byte value (0.0 - 1.0)
+    pub ratio: f64,
+    /// Length of the longest run of the byte value
+    pub longest_run: u64,
+    /// Offset of the longest run, if the byte occurs
+    pub longest_run_offset: Option<u64>,
+}
+
+impl StructuredRecord for ByteBin {
+    fn offset(&self) -> u64 {
+        self.range_offset
+    }
+
+    fn hex_line(&self, hex_offset_length: usize) -> String {
+        let position = match self.scope {
+            StatsScope::Total => "total".to_string(),
+            StatsScope::Range => OutputFormatter::format_offset(self.range_offset, hex_offset_length),
+        };
+        let mut line = format!(
+            "{} : {} {:>12} {:>7.3}%",
+            position,
+            &self.byte[2..],
+            self.count,
+            self.ratio * 100.0
+        );
+        if let Some(offset) = self.longest_run_offset {
+            line.push_str(&format!(
+                "  longest run {} @ {}",
+                self.longest_run,
+                OutputFormatter::format_offset(offset, hex_offset_length)
+            ));
+        }
+        line
+    }
+
+    fn plain_line(&self) -> String {
+        format!(
+            "{}:{} {} {} {}",
+            self.file_path, self.range_offset, self.byte, self.count, self.longest_run
+        )
+    }
+}
+
+/// Statistics of a whole input and its ranges
+#[derive(Debug, Clone)]
+pub struct StatsResult {
+    /// Statistics of the whole input
+    pub total: ByteStats,
+    /// Statistics per range (empty without a range size)
+    pub ranges: Vec<ByteStats>,
+}
+
+/// Chunked, optionally parallel statistics collector
+pub struct StatsCollector {
+    range_size: Option<u64>,
+}
+
+impl StatsCollector {
+    /// Create a collector with an optional per-range breakdown
+    pub fn new(range_size: Option<u64>) -> Result<Self> {
+        if range_size == Some(0) {
+            return Err(BingrepError::InvalidArgument(
+                "--range-size must be greater than 0".to_string(),
+            ));
+        }
+        Ok(Self { range_size })
+    }
+
+    /// Collect statistics of `reader` from its current position to the end
+    ///
+    /// Chunks are profiled independently (in parallel with `parallel`) and
+    /// merged in order, so runs crossing chunk boundaries are joined.
+    pub fn scan<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        chunk_size: usize,
+        parallel: bool,
+    ) -> Result<StatsResult> {
+        let origin = reader.stream_position()?;
+        let range_size = self.range_size;
+
+        let pieces = scan_chunks(reader, chunk_size, 0, 0, parallel, |chunk| {
+            // Split the chunk at range boundaries so each piece belongs to one range
+            let mut pieces = Vec::new();
+            let mut start = chunk.owned.start;
+            while start < chunk.owned.end {
+                let end = match range_size {
+                    Some(size) => {
+                        let index = (start - origin) / size;
+                        (origin + (index + 1) * size).min(chunk.owned.end)
+                    }
+                    None => chunk.owned.end,
+                };
+                let data = &chunk.data[(start - chunk.data_offset) as usize..(end - chunk.data_offset) as usize];
+                pieces.push(ByteStats::from_bytes(data, start));
+                start = end;
+            }
+            pieces
+        })?;
+
+        let mut total = ByteStats::empty(origin);
+        let mut ranges: Vec<ByteStats> = Vec::new();
+
+        for piece in &pieces {
+            total.merge(piece);
+
+            if let Some(size) = range_size {
+                let index = ((piece.offset - origin) / size) as usize;
+                if index >= ranges.len() {
+                    ranges.push(ByteStats::empty(origin + index as u64 * size));
+                }
+                ranges[index].merge(piece);
+            }
+        }
+
+        Ok(StatsResult { total, ranges })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_report_ratios_and_runs() {
+        let mut data = vec![0x00; 6];
+        data.extend([0xFF, 0xFF]);
+        data.extend(b"AB");
+
+        let report = ByteStats::from_bytes(&data, 0).report("test.bin", StatsScope::Total);
+        assert_eq!(report.length, 10);
+        assert!((report.zero_ratio - 0.6).abs() < 1e-9);
+        assert!((report.ff_ratio - 0.2).abs() < 1e-9);
+        assert!((report.printable_ratio - 0.2).abs() < 1e-9);
+        assert_eq!(report.distinct_bytes, 4);
+        assert_eq!(report.most_common_byte, "0x00");
+        assert_eq!(report.longest_run_byte, "0x00");
+        assert_eq!(report.longest_run_length, 6);
+    }
+
+    #[test]
+    fn test_merge_joins_runs_across_boundaries() {
+        let data = b"xAAAAAAAAy";
+        let mut merged = ByteStats::from_bytes(&data[..3], 0);
+        merged.merge(&ByteStats::from_bytes(&data[3..5], 3));
+        merged.merge(&ByteStats::from_bytes(&data[5..], 5));
+
+        let whole = ByteStats::from_bytes(data, 0);
+        assert_eq!(merged.histogram, whole.histogram);
+        assert_eq!(merged.longest[b'A' as usize], whole.longest[b'A' as usize]);
+        assert_eq!(merged.longest[b'A' as usize].length, 8);
+        assert_eq!(merged.longest[b'A' as usize].offset, 1);
+    }
+
+    #[test]
+    fn test_scan_ranges_parallel_matches_sequential() {
+        let data: Vec<u8> = (0..5000u32).map(|i| if i % 700 < 300 { 0 } else { (i % 251) as u8 }).collect();
+        let collector = StatsCollector::new(Some(1000)).unwrap();
+
+        let sequential = collector.scan(&mut Cursor::new(&data), 333, false).unwrap();
+        let parallel = collector.scan(&mut Cursor::new(&data), 333, true).unwrap();
+        let whole = ByteStats::from_bytes(&data, 0);
+
+        assert_eq!(sequential.total.histogram, whole.histogram);
+        assert_eq!(sequential.total.longest, whole.longest);
+        assert_eq!(parallel.total.longest, whole.longest);
+        assert_eq!(sequential.ranges.len(), 5);
+        assert_eq!(sequential.ranges[2].offset, 2000);
+        assert_eq!(sequential.ranges[2].histogram, ByteStats::from_bytes(&data[2000..3000], 2000).histogram);
+    }
+
+    #[test]
+    fn test_bins_cover_all_bytes() {
+        let bins = ByteStats::from_bytes(b"AAB", 0).bins("test.bin", StatsScope::Total);
+        assert_eq!(bins.len(), 256);
+        assert_eq!(bins[b'A' as usize].count, 2);
+        assert_eq!(bins[b'A' as usize].longest_run, 2);
+        assert_eq!(bins[0].longest_run_offset, None);
+    }
+}
@@ -1,17 +1,33 @@
+use crate::alignment::AlignedMatcher;
 use crate::buffer_manager::BufferManager;
+use crate::checkpoint::Checkpointer;
 use crate::config::Config;
 use crate::error::Result;
 use crate::forensic_image::{ForensicImageReader, is_forensic_image};
+use crate::inspector::INSPECT_LEN;
 use crate::output::OutputFormatter;
+use crate::progress;
 use regex::bytes::Regex;
 use std::fs::File;
-use std::io::{Read, Seek, SeekFrom};
+use std::io::{self, Read, Seek, SeekFrom, Stdout, Write};
 use std::path::Path;
 
+/// Input size the offset column of a streamed regex search is sized for
+///
+/// Generic readers (forensic images, stdin) do not report their size up
+/// front, so offsets are padded as for a 1 TB input.
+pub const STREAM_OFFSET_SPAN: u64 = 1024 * 1024 * 1024 * 1024;
+
 /// File processor for handling binary file searching and hex dump operations
-pub struct FileProcessor {
+///
+/// Output lines go to stdout by default, or to any writer given to
+/// [`FileProcessor::with_writer`] (e.g. a per-file buffer).
+pub struct FileProcessor<W: Write = Stdout> {
     config: Config,
     buffer_manager: BufferManager,
+    out: W,
+    file_name: Option<String>,
+    checkpoint: Option<Checkpointer>,
 }
 
 impl FileProcessor {
@@ -21,6 +37,18 @@ impl FileProcessor {
     ///
     /// * `config` - Configuration settings for buffer sizes and limits
     pub fn new(config: Config) -> Self {
+        Self::with_writer(config, io::stdout())
+    }
+}
+
+impl<W: Write> FileProcessor<W> {
+    /// Create a new FileProcessor that writes its output to `out`
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Configuration settings for buffer sizes and limits
+    /// * `out` - Destination for hex dump and match lines
+    pub fn with_writer(config: Config, out: W) -> Self {
         let buffer_size = config.buffer_size;
         let max_extra_size = config.max_line_width.max(1024); // At least 1KB for extra buffer
         let buffer_manager = BufferManager::new(buffer_size, max_extra_size);
@@ -28,13 +56,39 @@ impl FileProcessor {
         Self {
             config,
             buffer_manager,
+            out,
+            file_name: None,
+            checkpoint: None,
         }
     }
 
+    /// Set the file name shown in front of match lines
+    ///
+    /// Only used when the configuration asks for file names (`-H`).
+    pub fn with_file_name(mut self, file_name: impl Into<String>) -> Self {
+        self.file_name = Some(file_name.into());
+        self
+    }
+
+    /// Record regex search progress in a checkpoint (`--checkpoint`, `--resume`)
+    ///
+    /// The search starts at the checkpoint's offset instead of the reader's
+    /// position and skips matches the checkpoint already reported.
+    pub fn with_checkpoint(mut self, checkpoint: Checkpointer) -> Self {
+        self.checkpoint = Some(checkpoint);
+        self
+    }
+
+    /// Consume the processor and return its writer
+    pub fn into_writer(self) -> W {
+        self.out
+    }
+
     /// Process file without regex - simple hex dump
     ///
     /// Reads a file and outputs its contents in hexadecimal format.
     /// Automatically detects forensic image files (E01, VMDK) and processes them using appropriate libraries.
+    /// Returns the number of lines printed.
     ///
     /// # Arguments
     ///
@@ -50,18 +104,20 @@ impl FileProcessor {
         limit: usize,
         separator: &str,
         show_offset: bool,
-    ) -> Result<()> {
+    ) -> Result<usize> {
         let file_path = file_path.as_ref();
 
-        if is_forensic_image(&file_path) {
+        if is_forensic_image(file_path) {
             // Process forensic image file (E01, VMDK)
-            let mut forensic_reader = ForensicImageReader::new(&file_path)?;
+            let mut forensic_reader = ForensicImageReader::new(file_path)?;
             let file_size = forensic_reader.size();
+            report_total(file_size);
             self.process_reader_stream(&mut forensic_reader, width, limit, separator, show_offset, file_size)
         } else {
             // Process regular file
-            let mut file = File::open(&file_path)?;
+            let mut file = File::open(file_path)?;
             let file_size = file.metadata()?.len();
+            report_total(file_size);
             self.process_reader_stream(&mut file, width, limit, separator, show_offset, file_size)
         }
     }
@@ -69,6 +125,7 @@ impl FileProcessor {
     /// Process file without regex - simple hex dump
     ///
     /// Reads a file and outputs its contents in hexadecimal format.
+    /// Returns the number of lines printed.
     ///
     /// # Arguments
     ///
@@ -78,15 +135,15 @@ impl FileProcessor {
     /// * `separator` - String to separate hex bytes
     /// * `show_offset` - Whether to display offset values
     /// * `file_size` - Total size of the file for offset formatting
-    pub fn process_file_stream(
+    pub fn process_file_stream<R: Read + Seek>(
         &mut self,
-        file: &mut File,
+        file: &mut R,
         width: usize,
         limit: usize,
         separator: &str,
         show_offset: bool,
         file_size: u64,
-    ) -> Result<()> {
+    ) -> Result<usize> {
         self.process_reader_stream(file, width, limit, separator, show_offset, file_size)
     }
 
@@ -99,7 +156,7 @@ impl FileProcessor {
         separator: &str,
         show_offset: bool,
         file_size: u64,
-    ) -> Result<()> {
+    ) -> Result<usize> {
         let mut pos = reader.stream_position()?;
         let mut line = 0;
         let hex_offset_length = OutputFormatter::calculate_hex_offset_length(file_size);
@@ -114,9 +171,23 @@ impl FileProcessor {
             }
 
             line += 1;
-
-            let hex_string = OutputFormatter::format_bytes_as_hex(&buffer[..bytes_read], separator);
-            OutputFormatter::print_line(pos, &hex_string, show_offset, hex_offset_length);
+            progress::advance(bytes_read as u64);
+
+            let hex_string = OutputFormatter::format_bytes_for_display(&buffer[..bytes_read], separator);
+            writeln!(
+                self.out,
+                "{}",
+                OutputFormatter::format_line_with_match_highlight(
+                    pos,
+                    &hex_string,
+                    show_offset,
+                    hex_offset_length,
+                    crate::color_context::get_color_choice(),
+                    None,
+                    None,
+                    None,
+                )
+            )?;
 
             pos += bytes_read as u64;
 
@@ -126,13 +197,14 @@ impl FileProcessor {
             }
         }
 
-        Ok(())
+        Ok(line)
     }
 
     /// Process file with regex pattern matching from file path
     ///
     /// Searches a file for regex pattern matches and outputs matching regions.
     /// Automatically detects forensic image files (E01, VMDK) and processes them using appropriate libraries.
+    /// Returns the number of matches printed.
     ///
     /// # Arguments
     ///
@@ -150,16 +222,18 @@ impl FileProcessor {
         limit: usize,
         separator: &str,
         show_offset: bool,
-    ) -> Result<()> {
+    ) -> Result<usize> {
         let file_path = file_path.as_ref();
 
-        if is_forensic_image(&file_path) {
+        if is_forensic_image(file_path) {
             // Process forensic image file (E01, VMDK)
-            let mut forensic_reader = ForensicImageReader::new(&file_path)?;
+            let mut forensic_reader = ForensicImageReader::new(file_path)?;
+            report_total(forensic_reader.size());
             self.process_reader_by_regex(&mut forensic_reader, regex, width, limit, separator, show_offset)
         } else {
             // Process regular file
-            let mut file = File::open(&file_path)?;
+            let mut file = File::open(file_path)?;
+            report_total(file.metadata()?.len());
             self.process_reader_by_regex(&mut file, regex, width, limit, separator, show_offset)
         }
     }
@@ -167,6 +241,7 @@ impl FileProcessor {
     /// Process file with regex pattern matching
     ///
     /// Searches a file for regex pattern matches and outputs matching regions.
+    /// Returns the number of matches printed.
     ///
     /// # Arguments
     ///
@@ -176,15 +251,15 @@ impl FileProcessor {
     /// * `limit` - Maximum number of matches to output (0 for unlimited)
     /// * `separator` - String to separate hex bytes
     /// * `show_offset` - Whether to display offset values
-    pub fn process_stream_by_regex(
+    pub fn process_stream_by_regex<R: Read + Seek>(
         &mut self,
-        file: &mut File,
+        file: &mut R,
         regex: &Regex,
         width: usize,
         limit: usize,
         separator: &str,
         show_offset: bool,
-    ) -> Result<()> {
+    ) -> Result<usize> {
         self.process_reader_by_regex(file, regex, width, limit, separator, show_offset)
     }
 
@@ -197,33 +272,52 @@ impl FileProcessor {
         limit: usize,
         separator: &str,
         show_offset: bool,
-    ) -> Result<()> {
-        let buffer_size = self.config.get_buffer_size(width);
+    ) -> Result<usize> {
+        // A full read is what tells a buffer boundary apart from end of input
+        let buffer_size = self.buffer_manager.main_len();
         let buffer_padding = self.config.buffer_padding;
+        let matcher = AlignedMatcher::new(regex, self.config.alignment.as_ref())?;
 
         let mut line = 0;
         let mut last_hit_pos: i64 = -1;
+        let file_name = self.file_name.clone().filter(|_| self.config.with_filename);
 
-        // For EWF files, we need to get size differently
-        // For now, we'll use a large default for generic readers
-        let file_size = 1024 * 1024 * 1024 * 1024u64; // 1TB default
-        let hex_offset_length = OutputFormatter::calculate_hex_offset_length(file_size);
+        if let Some(checkpoint) = &self.checkpoint {
+            // Continue where the checkpoint left off, skipping matches reported before
+            reader.seek(SeekFrom::Start(checkpoint.offset()))?;
+            last_hit_pos = checkpoint.last_match().map_or(-1, |pos| pos as i64);
+        }
+
+        let hex_offset_length = OutputFormatter::calculate_hex_offset_length(STREAM_OFFSET_SPAN);
+
+        // Buffers overlap, so progress is reported up to the furthest byte read
+        let mut scanned_to = reader.stream_position()?;
 
         loop {
             let start_offset = reader.stream_position()?;
+            // Every match before this buffer has been reported
+            self.record_checkpoint(start_offset, last_hit_pos, line, false)?;
             let bytes_read = self.buffer_manager.read_into_main(reader)?;
 
             if bytes_read == 0 {
+                self.record_checkpoint(start_offset, last_hit_pos, line, true)?;
                 break;
             }
 
+            let buffer_end = start_offset + bytes_read as u64;
+            if buffer_end > scanned_to {
+                progress::advance(buffer_end - scanned_to);
+                scanned_to = buffer_end;
+            }
+
             // Process regex matches directly without collecting into vector
             let buffer_slice = self.buffer_manager.get_main_slice(0, bytes_read);
             let mut matches_to_process = Vec::new();
+            let mut resume_at = None;
 
             // Only collect match positions that we actually need to process
-            for mat in regex.find_iter(buffer_slice) {
-                let match_start = mat.start();
+            for hit in matcher.find_iter(buffer_slice, start_offset) {
+                let match_start = hit.start;
                 let new_hit_pos = start_offset + match_start as u64;
 
                 // Skip duplicates early
@@ -244,24 +338,25 @@ impl FileProcessor {
                     continue;
                 }
 
-                // Handle buffer overflow - seek to match position if needed
-                if match_start + width > bytes_read && bytes_read == buffer_size {
-                    reader.seek(SeekFrom::Start(new_hit_pos))?;
-                    last_hit_pos = new_hit_pos as i64;
+                // Handle buffer overflow - restart the next buffer at this match,
+                // which has not been reported yet
+                if match_start > 0 && match_start + width > bytes_read && bytes_read == buffer_size {
+                    resume_at = Some(new_hit_pos);
                     break;
                 }
 
                 line += 1;
+                progress::record_matches(1);
 
                 // Read width bytes from match position
-                let (hex_string, match_info) = self.read_match_data_with_highlight(
+                let (mut hex_string, match_info) = self.read_match_data_with_highlight(
                     reader,
                     match_start,
                     width,
                     bytes_read,
                     start_offset,
                     separator,
-                    &regex,
+                    regex,
                 )?;
 
                 // Calculate match position within the displayed hex string
@@ -272,25 +367,46 @@ impl FileProcessor {
                     None
                 };
 
-                OutputFormatter::print_line_with_match_highlight(
-                    new_hit_pos,
-                    &hex_string,
-                    show_offset,
-                    hex_offset_length,
-                    crate::color_context::get_color_choice(),
-                    match_byte_pos,
-                    match_byte_len,
-                );
+                if let Some(alignment) = &self.config.alignment {
+                    hex_string = OutputFormatter::append_annotation(
+                        &hex_string,
+                        &alignment.describe(new_hit_pos),
+                    );
+                }
+
+                writeln!(
+                    self.out,
+                    "{}",
+                    OutputFormatter::format_line_with_match_highlight(
+                        new_hit_pos,
+                        &hex_string,
+                        show_offset,
+                        hex_offset_length,
+                        crate::color_context::get_color_choice(),
+                        match_byte_pos,
+                        match_byte_len,
+                        file_name.as_deref(),
+                    )
+                )?;
+                if crate::inspector::get_inspector().is_some() {
+                    let bytes = Self::read_at(reader, new_hit_pos, INSPECT_LEN)?;
+                    for line in OutputFormatter::inspection_lines(&bytes) {
+                        writeln!(self.out, "{}", line)?;
+                    }
+                }
                 last_hit_pos = new_hit_pos as i64;
 
                 // Check line limit
                 if limit > 0 && line >= limit {
-                    return Ok(());
+                    self.record_checkpoint(start_offset, last_hit_pos, line, true)?;
+                    return Ok(line);
                 }
             }
 
-            // Read next buffer with overlap to handle patterns spanning boundaries
-            if bytes_read == buffer_size {
+            if let Some(pos) = resume_at {
+                reader.seek(SeekFrom::Start(pos))?;
+            } else if bytes_read == buffer_size {
+                // Read next buffer with overlap to handle patterns spanning boundaries
                 let new_pos = reader
                     .stream_position()?
                     .saturating_sub(buffer_padding as u64);
@@ -298,7 +414,36 @@ impl FileProcessor {
             }
         }
 
-        Ok(())
+        Ok(line)
+    }
+
+    /// Record search progress in the checkpoint, if any
+    ///
+    /// `force` writes the file immediately instead of at the checkpoint interval.
+    fn record_checkpoint(
+        &mut self,
+        offset: u64,
+        last_hit_pos: i64,
+        matches: usize,
+        force: bool,
+    ) -> Result<()> {
+        let Some(checkpoint) = &mut self.checkpoint else {
+            return Ok(());
+        };
+        let last_match = u64::try_from(last_hit_pos).ok();
+        if force {
+            checkpoint.finish(offset, last_match, matches)
+        } else {
+            checkpoint.record(offset, last_match, matches)
+        }
+    }
+
+    /// Read up to `len` bytes at `offset`, restoring the reader position afterwards
+    fn read_at<R: Read + Seek>(reader: &mut R, offset: u64, len: usize) -> Result<Vec<u8>> {
+        let current_pos = reader.stream_position()?;
+        let bytes = crate::parallel::read_chunk(reader, offset, len)?;
+        reader.seek(SeekFrom::Start(current_pos))?;
+        Ok(bytes)
     }
 
     /// Read match data, handling cases where width extends beyond buffer
@@ -316,6 +461,7 @@ impl FileProcessor {
     }
 
     /// Read match data with highlighting information
+    #[allow(clippy::too_many_arguments)]
     fn read_match_data_with_highlight<R: Read + Seek>(
         &mut self,
         reader: &mut R,
@@ -337,11 +483,7 @@ impl FileProcessor {
         let actual_read = reader.read(&mut display_buffer)?;
         reader.seek(SeekFrom::Start(current_pos))?;
 
-        let match_len = if let Some(mat) = regex.find(&display_buffer[..actual_read]) {
-            Some(mat.len())
-        } else {
-            None
-        };
+        let match_len = regex.find(&display_buffer[..actual_read]).map(|mat| mat.len());
 
         Ok((hex_string, match_len))
     }
@@ -374,17 +516,24 @@ impl FileProcessor {
 
             reader.seek(SeekFrom::Start(current_pos))?;
 
-            Ok(OutputFormatter::format_bytes_as_hex(
+            Ok(OutputFormatter::format_bytes_for_display(
                 combined_data,
                 separator,
             ))
         } else {
             let main_slice = self.buffer_manager.get_main_slice(match_start, end_pos);
-            Ok(OutputFormatter::format_bytes_as_hex(main_slice, separator))
+            Ok(OutputFormatter::format_bytes_for_display(main_slice, separator))
         }
     }
 }
 
+/// Add the size of an input opened by path to the `--progress` total
+fn report_total(size: u64) {
+    if let Some(progress) = progress::get_progress() {
+        progress.add_total(size);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -417,4 +566,24 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_process_stream_by_regex_with_writer() -> Result<()> {
+        let mut processor = FileProcessor::with_writer(Config::default(), Vec::new());
+
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(b"\x00\x00MARK\x00MARK").unwrap();
+        let mut file = temp_file.reopen().unwrap();
+
+        let regex = Regex::new("MARK").unwrap();
+        let count = processor.process_stream_by_regex(&mut file, &regex, 4, 0, " ", false)?;
+        assert_eq!(count, 2);
+
+        // Lines end up in the writer instead of stdout
+        let output = String::from_utf8(processor.into_writer()).unwrap();
+        assert_eq!(output.lines().count(), 2);
+        assert!(output.lines().all(|line| line.contains("4D") && line.contains("4B")));
+
+        Ok(())
+    }
 }
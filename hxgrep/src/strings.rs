@@ -0,0 +1,417 @@
+//! Printable string extraction
+//!
+//! Extracts runs of printable characters in ASCII, UTF-8 and UTF-16LE/BE
+//! together with their offsets, similar to `strings -t x`.
+
+use crate::cli::StringEncoding;
+use crate::error::Result;
+use crate::output::OutputFormatter;
+use crate::parallel::{scan_chunks, Chunk};
+use crate::structured_output::StructuredRecord;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Seek};
+
+/// Bytes read before each chunk to detect runs that started in the previous chunk
+const CHUNK_LEAD: usize = 4;
+
+/// Bytes read after each chunk so runs crossing the boundary are not cut short
+const CHUNK_OVERLAP: usize = 64 * 1024;
+
+impl StringEncoding {
+    /// Name used in output records
+    pub fn name(&self) -> &'static str {
+        match self {
+            StringEncoding::Ascii => "ascii",
+            StringEncoding::Utf8 => "utf-8",
+            StringEncoding::Utf16le => "utf-16le",
+            StringEncoding::Utf16be => "utf-16be",
+        }
+    }
+}
+
+/// A printable string found in the input
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExtractedString {
+    /// File path of the source file
+    pub file_path: String,
+    /// Byte offset where the string starts
+    pub offset: u64,
+    /// Length of the encoded string in bytes
+    pub length: usize,
+    /// Encoding the string was decoded with
+    pub encoding: String,
+    /// Decoded text
+    pub text: String,
+}
+
+impl StructuredRecord for ExtractedString {
+    fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    fn hex_line(&self, hex_offset_length: usize) -> String {
+        format!(
+            "{} : [{}] {}",
+            OutputFormatter::format_offset(self.offset, hex_offset_length),
+            self.encoding,
+            self.text
+        )
+    }
+
+    fn plain_line(&self) -> String {
+        format!("{}:{} {} {}", self.file_path, self.offset, self.encoding, self.text)
+    }
+}
+
+/// Printable run within a buffer: byte range and decoded text
+struct Run {
+    start: usize,
+    end: usize,
+    text: String,
+}
+
+/// Extractor for printable strings
+pub struct StringExtractor {
+    min_len: usize,
+    encodings: Vec<StringEncoding>,
+    filter: Option<Regex>,
+}
+
+impl StringExtractor {
+    /// Create a new extractor
+    ///
+    /// UTF-8 runs include pure ASCII runs, so ASCII is dropped when both are
+    /// requested to avoid reporting the same string twice.
+    ///
+    /// # Arguments
+    ///
+    /// * `min_len` - Minimum number of characters in a string
+    /// * `encodings` - Encodings to extract
+    /// * `filter` - Optional regex the decoded text must match
+    pub fn new(
+        min_len: usize,
+        encodings: &[StringEncoding],
+        filter: Option<&str>,
+    ) -> Result<Self> {
+        let mut selected: Vec<StringEncoding> = Vec::new();
+        for &encoding in encodings {
+            if !selected.contains(&encoding) {
+                selected.push(encoding);
+            }
+        }
+        if selected.contains(&StringEncoding::Utf8) {
+            selected.retain(|&e| e != StringEncoding::Ascii);
+        }
+
+        let filter = match filter {
+            Some(pattern) => Some(Regex::new(pattern)?),
+            None => None,
+        };
+
+        Ok(Self {
+            min_len: min_len.max(1),
+            encodings: selected,
+            filter,
+        })
+    }
+
+    /// Extract strings from a reader, from its current position to the end
+    pub fn extract<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        file_path: &str,
+        chunk_size: usize,
+        parallel: bool,
+    ) -> Result<Vec<ExtractedString>> {
+        scan_chunks(reader, chunk_size, CHUNK_LEAD, CHUNK_OVERLAP, parallel, |chunk| {
+            self.extract_chunk(chunk, file_path)
+        })
+    }
+
+    /// Extract strings starting inside the owned range of a chunk
+    pub fn extract_chunk(&self, chunk: &Chunk, file_path: &str) -> Vec<ExtractedString> {
+        let mut strings = Vec::new();
+
+        for &encoding in &self.encodings {
+            let data = &chunk.data;
+            let runs = match encoding {
+                StringEncoding::Ascii => ascii_runs(data, self.min_len),
+                StringEncoding::Utf8 => utf8_runs(data, self.min_len),
+                StringEncoding::Utf16le => utf16_runs(data, self.min_len, u16::from_le_bytes),
+                StringEncoding::Utf16be => utf16_runs(data, self.min_len, u16::from_be_bytes),
+            };
+
+            for run in runs {
+                let offset = chunk.data_offset + run.start as u64;
+                if !chunk.owned.contains(&offset) {
+                    continue;
+                }
+                if let Some(filter) = &self.filter {
+                    if !filter.is_match(&run.text) {
+                        continue;
+                    }
+                }
+
+                strings.push(ExtractedString {
+                    file_path: file_path.to_string(),
+                    offset,
+                    length: run.end - run.start,
+                    encoding: encoding.name().to_string(),
+                    text: run.text,
+                });
+            }
+        }
+
+        strings.sort_by_key(|s| s.offset);
+        strings
+    }
+}
+
+/// Printable ASCII: graphic characters, space and tab
+fn is_printable_ascii(b: u8) -> bool {
+    b == b'\t' || (0x20..=0x7E).contains(&b)
+}
+
+/// Printable character for UTF-8 runs
+fn is_printable_char(c: char) -> bool {
+    let private_use = ('\u{E000}'..='\u{F8FF}').contains(&c);
+    c == '\t' || !(c.is_control() || c == char::REPLACEMENT_CHARACTER || private_use)
+}
+
+/// Printable character for UTF-16 runs
+///
+/// Almost every 16-bit value is a valid character, so UTF-16 runs are limited
+/// to ASCII, Latin, Greek, Cyrillic and Hangul to keep random data from
+/// turning into one long string.
+fn is_printable_utf16_char(c: char) -> bool {
+    if c.is_ascii() {
+        return is_printable_ascii(c as u8);
+    }
+    matches!(
+        c,
+        '\u{00A0}'..='\u{024F}'     // Latin-1 Supplement, Latin Extended-A/B
+            | '\u{0370}'..='\u{04FF}' // Greek, Cyrillic
+            | '\u{3131}'..='\u{318E}' // Hangul Compatibility Jamo
+            | '\u{AC00}'..='\u{D7A3}' // Hangul Syllables
+    )
+}
+
+/// Build a run from a byte range that is known to be valid UTF-8
+fn byte_run(data: &[u8], start: usize, end: usize) -> Run {
+    Run {
+        start,
+        end,
+        text: String::from_utf8_lossy(&data[start..end]).into_owned(),
+    }
+}
+
+/// Find runs of at least `min_len` printable ASCII bytes
+fn ascii_runs(data: &[u8], min_len: usize) -> Vec<Run> {
+    let mut runs = Vec::new();
+    let mut start = None;
+
+    for (i, &b) in data.iter().enumerate() {
+        match (is_printable_ascii(b), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                if i - s >= min_len {
+                    runs.push(byte_run(data, s, i));
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        if data.len() - s >= min_len {
+            runs.push(byte_run(data, s, data.len()));
+        }
+    }
+
+    runs
+}
+
+/// Find runs of at least `min_len` printable, validly encoded UTF-8 characters
+fn utf8_runs(data: &[u8], min_len: usize) -> Vec<Run> {
+    let mut runs = Vec::new();
+    let mut start = None;
+    let mut chars = 0;
+    let mut i = 0;
+
+    while i < data.len() {
+        let decoded = utf8_char_at(data, i).filter(|&(c, _)| is_printable_char(c));
+        match decoded {
+            Some((_, len)) => {
+                start.get_or_insert(i);
+                chars += 1;
+                i += len;
+            }
+            None => {
+                if let Some(s) = start.take() {
+                    if chars >= min_len {
+                        runs.push(byte_run(data, s, i));
+                    }
+                }
+                chars = 0;
+                i += 1;
+            }
+        }
+    }
+    if let Some(s) = start {
+        if chars >= min_len {
+            runs.push(byte_run(data, s, data.len()));
+        }
+    }
+
+    runs
+}
+
+/// Decode the UTF-8 character starting at `i`, returning it and its length
+fn utf8_char_at(data: &[u8], i: usize) -> Option<(char, usize)> {
+    let len = match data[i] {
+        0x00..=0x7F => 1,
+        0xC2..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        0xF0..=0xF4 => 4,
+        _ => return None,
+    };
+    let bytes = data.get(i..i + len)?;
+    std::str::from_utf8(bytes)
+        .ok()
+        .and_then(|s| s.chars().next())
+        .map(|c| (c, len))
+}
+
+/// Find runs of at least `min_len` printable UTF-16 code units at both byte parities
+fn utf16_runs(data: &[u8], min_len: usize, decode: fn([u8; 2]) -> u16) -> Vec<Run> {
+    let mut runs = Vec::new();
+
+    for parity in 0..2 {
+        let mut start = None;
+        let mut i = parity;
+
+        while i + 1 < data.len() {
+            let unit = decode([data[i], data[i + 1]]);
+            let printable = char::from_u32(unit as u32).is_some_and(is_printable_utf16_char);
+            match (printable, start) {
+                (true, None) => start = Some(i),
+                (false, Some(s)) => {
+                    if (i - s) / 2 >= min_len {
+                        runs.push(utf16_run(data, s, i, decode));
+                    }
+                    start = None;
+                }
+                _ => {}
+            }
+            i += 2;
+        }
+        if let Some(s) = start {
+            if (i - s) / 2 >= min_len {
+                runs.push(utf16_run(data, s, i, decode));
+            }
+        }
+    }
+
+    runs
+}
+
+fn utf16_run(data: &[u8], start: usize, end: usize, decode: fn([u8; 2]) -> u16) -> Run {
+    let text = data[start..end]
+        .chunks_exact(2)
+        .filter_map(|pair| char::from_u32(decode([pair[0], pair[1]]) as u32))
+        .collect();
+    Run { start, end, text }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn texts(strings: &[ExtractedString]) -> Vec<(&str, u64, &str)> {
+        strings
+            .iter()
+            .map(|s| (s.encoding.as_str(), s.offset, s.text.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_ascii_and_utf16le() {
+        let mut data = b"\x00\x01Hello\x00\x00abc\x00\x00\x00\x00".to_vec();
+        data.extend("World".encode_utf16().flat_map(|u| u.to_le_bytes()));
+        data.push(0xFF);
+
+        let extractor = StringExtractor::new(
+            4,
+            &[StringEncoding::Ascii, StringEncoding::Utf16le],
+            None,
+        )
+        .unwrap();
+        let strings = extractor
+            .extract(&mut Cursor::new(&data), "test.bin", 1024, false)
+            .unwrap();
+
+        assert_eq!(
+            texts(&strings),
+            vec![("ascii", 2, "Hello"), ("utf-16le", 16, "World")]
+        );
+        assert_eq!(strings[1].length, 10);
+    }
+
+    #[test]
+    fn test_utf8_and_utf16be_hangul() {
+        let mut data = vec![0xFFu8; 3];
+        data.extend("한글 문자열".as_bytes());
+        data.extend([0x00, 0x00]);
+        data.extend("검색어".encode_utf16().flat_map(|u| u.to_be_bytes()));
+
+        let extractor = StringExtractor::new(
+            3,
+            &[StringEncoding::Utf8, StringEncoding::Utf16be],
+            None,
+        )
+        .unwrap();
+        let strings = extractor
+            .extract(&mut Cursor::new(&data), "test.bin", 1024, false)
+            .unwrap();
+
+        assert_eq!(
+            texts(&strings),
+            vec![("utf-8", 3, "한글 문자열"), ("utf-16be", 21, "검색어")]
+        );
+    }
+
+    #[test]
+    fn test_filter() {
+        let data = b"password=1234\x00username=admin\x00";
+        let extractor =
+            StringExtractor::new(4, &[StringEncoding::Ascii], Some("^user")).unwrap();
+        let strings = extractor
+            .extract(&mut Cursor::new(&data[..]), "test.bin", 1024, false)
+            .unwrap();
+
+        assert_eq!(texts(&strings), vec![("ascii", 14, "username=admin")]);
+    }
+
+    #[test]
+    fn test_chunk_boundaries_do_not_split_or_duplicate() {
+        let mut data = Vec::new();
+        for i in 0..200 {
+            data.extend(format!("string-{:04}", i).as_bytes());
+            data.extend([0x00, 0x00, 0x00]);
+        }
+
+        let extractor = StringExtractor::new(4, &[StringEncoding::Ascii], None).unwrap();
+        let whole = extractor
+            .extract(&mut Cursor::new(&data), "test.bin", data.len(), false)
+            .unwrap();
+        // Odd chunk size so boundaries fall inside strings
+        let chunked = extractor
+            .extract(&mut Cursor::new(&data), "test.bin", 37, true)
+            .unwrap();
+
+        assert_eq!(whole.len(), 200);
+        assert_eq!(texts(&whole), texts(&chunked));
+    }
+}
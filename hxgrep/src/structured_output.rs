@@ -1,3 +1,6 @@
+use crate::hashing::{render_hashes, RegionHashes};
+use crate::inspector::{render_values, InspectedValue};
+use crate::output::OutputFormatter;
 use serde::{Deserialize, Serialize};
 use std::io::Write;
 
@@ -16,6 +19,7 @@ pub enum OutputFormat {
 
 impl OutputFormat {
     /// Parse output format from string
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
             "hex" => Some(Self::Hex),
@@ -40,6 +44,30 @@ pub struct BinaryMatch {
     pub length: usize,
     /// ASCII representation of the data (if printable)
     pub ascii_data: Option<String>,
+    /// Interpretations of the bytes at the match (with `--inspect`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inspection: Option<Vec<InspectedValue>>,
+    /// Capture groups of the match (structured output or `--capture`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub groups: Option<Vec<CaptureGroup>>,
+    /// Digests of the match region (with `--hash-matches`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hashes: Option<RegionHashes>,
+}
+
+/// A capture group of a match
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CaptureGroup {
+    /// Group name, or its index for unnamed groups
+    pub name: String,
+    /// Byte offset in the file where the group starts
+    pub offset: u64,
+    /// Hexadecimal representation of the group bytes
+    pub hex_data: String,
+    /// Length of the group in bytes
+    pub length: usize,
+    /// ASCII representation of the data (if printable)
+    pub ascii_data: Option<String>,
 }
 
 /// Represents a line of hex dump output
@@ -57,6 +85,21 @@ pub struct HexDumpLine {
     pub byte_count: usize,
 }
 
+/// A record that can be emitted through [`StructuredFormatter::output_records`]
+///
+/// JSON and CSV output are derived from the `Serialize` implementation;
+/// the text formats are provided by the record itself.
+pub trait StructuredRecord: Serialize {
+    /// Offset used to size the offset column in hex output
+    fn offset(&self) -> u64;
+
+    /// Render the record for the default hex format
+    fn hex_line(&self, hex_offset_length: usize) -> String;
+
+    /// Render the record for the plain format
+    fn plain_line(&self) -> String;
+}
+
 /// Structured output formatter
 pub struct StructuredFormatter {
     format: OutputFormat,
@@ -96,6 +139,40 @@ impl StructuredFormatter {
         }
     }
 
+    /// Output arbitrary structured records in the specified format
+    pub fn output_records<T: StructuredRecord, W: Write>(
+        &self,
+        records: &[T],
+        writer: &mut W,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        match self.format {
+            OutputFormat::Hex => {
+                let max_offset = records.iter().map(|r| r.offset()).max().unwrap_or(0);
+                let hex_offset_length = OutputFormatter::calculate_hex_offset_length(max_offset);
+                for record in records {
+                    writeln!(writer, "{}", record.hex_line(hex_offset_length))?;
+                }
+            }
+            OutputFormat::Json => {
+                serde_json::to_writer_pretty(&mut *writer, records)?;
+                writeln!(writer)?;
+            }
+            OutputFormat::Csv => {
+                let mut csv_writer = csv::Writer::from_writer(writer);
+                for record in records {
+                    csv_writer.serialize(record)?;
+                }
+                csv_writer.flush()?;
+            }
+            OutputFormat::Plain => {
+                for record in records {
+                    writeln!(writer, "{}", record.plain_line())?;
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Output matches in hex format (default)
     fn output_matches_hex<W: Write>(
         &self,
@@ -103,14 +180,22 @@ impl StructuredFormatter {
         writer: &mut W,
     ) -> Result<(), Box<dyn std::error::Error>> {
         for m in matches {
-            let hex_offset_length = format!("{:X}", m.offset).len();
+            let hex_offset_length = OutputFormatter::calculate_hex_offset_length(m.offset);
             writeln!(
                 writer,
-                "{:0width$X}h : {}",
-                m.offset,
-                m.hex_data,
-                width = hex_offset_length
+                "{}",
+                OutputFormatter::format_line_with_offset(m.offset, &m.hex_data, hex_offset_length)
             )?;
+            if let Some(inspection) = &m.inspection {
+                for line in render_values(inspection) {
+                    writeln!(writer, "{}", line)?;
+                }
+            }
+            if let Some(hashes) = &m.hashes {
+                for line in render_hashes(hashes) {
+                    writeln!(writer, "{}", line)?;
+                }
+            }
         }
         Ok(())
     }
@@ -134,18 +219,39 @@ impl StructuredFormatter {
     ) -> Result<(), Box<dyn std::error::Error>> {
         let mut csv_writer = csv::Writer::from_writer(writer);
 
+        // Digest columns are added when the matches were hashed
+        let algorithms: Vec<_> = matches
+            .iter()
+            .find_map(|m| m.hashes.as_ref())
+            .map(|hashes| hashes.digests().into_iter().map(|(algorithm, _)| algorithm).collect())
+            .unwrap_or_default();
+
         // Write header
-        csv_writer.write_record(&["file_path", "offset", "hex_data", "length", "ascii_data"])?;
+        let mut header = vec!["file_path", "offset", "hex_data", "length", "ascii_data"];
+        if !algorithms.is_empty() {
+            header.push("hash_length");
+            header.extend(algorithms.iter().map(|algorithm| algorithm.name()));
+        }
+        csv_writer.write_record(&header)?;
 
         // Write data
         for m in matches {
-            csv_writer.write_record(&[
-                &m.file_path,
-                &m.offset.to_string(),
-                &m.hex_data,
-                &m.length.to_string(),
-                &m.ascii_data.as_ref().unwrap_or(&"".to_string()),
-            ])?;
+            let mut record = vec![
+                m.file_path.clone(),
+                m.offset.to_string(),
+                m.hex_data.clone(),
+                m.length.to_string(),
+                m.ascii_data.clone().unwrap_or_default(),
+            ];
+            if !algorithms.is_empty() {
+                let hashes = m.hashes.as_ref();
+                record.push(hashes.map(|h| h.length.to_string()).unwrap_or_default());
+                for algorithm in &algorithms {
+                    let hash = hashes.and_then(|h| h.get(*algorithm));
+                    record.push(hash.unwrap_or_default().to_string());
+                }
+            }
+            csv_writer.write_record(&record)?;
         }
 
         csv_writer.flush()?;
@@ -159,7 +265,13 @@ impl StructuredFormatter {
         writer: &mut W,
     ) -> Result<(), Box<dyn std::error::Error>> {
         for m in matches {
-            writeln!(writer, "{}:{} {}", m.file_path, m.offset, m.hex_data)?;
+            write!(writer, "{}:{} {}", m.file_path, m.offset, m.hex_data)?;
+            if let Some(hashes) = &m.hashes {
+                for (algorithm, hash) in hashes.digests() {
+                    write!(writer, " {}={}", algorithm.name(), hash)?;
+                }
+            }
+            writeln!(writer)?;
         }
         Ok(())
     }
@@ -171,13 +283,11 @@ impl StructuredFormatter {
         writer: &mut W,
     ) -> Result<(), Box<dyn std::error::Error>> {
         for line in lines {
-            let hex_offset_length = format!("{:X}", line.offset).len();
+            let hex_offset_length = OutputFormatter::calculate_hex_offset_length(line.offset);
             writeln!(
                 writer,
-                "{:0width$X}h : {}",
-                line.offset,
-                line.hex_data,
-                width = hex_offset_length
+                "{}",
+                OutputFormatter::format_line_with_offset(line.offset, &line.hex_data, hex_offset_length)
             )?;
         }
         Ok(())
@@ -203,7 +313,7 @@ impl StructuredFormatter {
         let mut csv_writer = csv::Writer::from_writer(writer);
 
         // Write header
-        csv_writer.write_record(&[
+        csv_writer.write_record([
             "file_path",
             "offset",
             "hex_data",
@@ -213,12 +323,12 @@ impl StructuredFormatter {
 
         // Write data
         for line in lines {
-            csv_writer.write_record(&[
+            csv_writer.write_record([
                 &line.file_path,
                 &line.offset.to_string(),
                 &line.hex_data,
                 &line.byte_count.to_string(),
-                &line.ascii_data.as_ref().unwrap_or(&"".to_string()),
+                line.ascii_data.as_ref().unwrap_or(&"".to_string()),
             ])?;
         }
 
@@ -254,18 +364,30 @@ impl BinaryMatch {
             hex_data,
             length,
             ascii_data,
+            inspection: None,
+            groups: None,
+            hashes: None,
         }
     }
 
-    /// Convert hex string to ASCII if all bytes are printable
-    fn bytes_to_ascii_if_printable(hex_data: &str) -> Option<String> {
-        let hex_bytes: Result<Vec<u8>, _> = hex_data
+    /// Matched bytes, decoded from `hex_data`
+    pub fn bytes(&self) -> Vec<u8> {
+        Self::parse_hex_data(&self.hex_data).unwrap_or_default()
+    }
+
+    /// Parse a whitespace separated hex string back into bytes
+    fn parse_hex_data(hex_data: &str) -> Option<Vec<u8>> {
+        hex_data
             .split_whitespace()
             .map(|hex_byte| u8::from_str_radix(hex_byte, 16))
-            .collect();
+            .collect::<Result<Vec<u8>, _>>()
+            .ok()
+    }
 
-        match hex_bytes {
-            Ok(bytes) => {
+    /// Convert hex string to ASCII if all bytes are printable
+    fn bytes_to_ascii_if_printable(hex_data: &str) -> Option<String> {
+        match Self::parse_hex_data(hex_data) {
+            Some(bytes) => {
                 if bytes
                     .iter()
                     .all(|&b| b.is_ascii() && (b.is_ascii_graphic() || b == b' '))
@@ -275,9 +397,28 @@ impl BinaryMatch {
                     None
                 }
             }
-            Err(_) => None,
+            None => None,
+        }
+    }
+}
+
+impl CaptureGroup {
+    /// Create a new CaptureGroup
+    pub fn new(name: String, offset: u64, hex_data: String, length: usize) -> Self {
+        let ascii_data = BinaryMatch::bytes_to_ascii_if_printable(&hex_data);
+        Self {
+            name,
+            offset,
+            hex_data,
+            length,
+            ascii_data,
         }
     }
+
+    /// Group bytes, decoded from `hex_data`
+    pub fn bytes(&self) -> Vec<u8> {
+        BinaryMatch::parse_hex_data(&self.hex_data).unwrap_or_default()
+    }
 }
 
 impl HexDumpLine {
@@ -316,7 +457,7 @@ mod tests {
             OutputFormat::from_str("plain"),
             Some(OutputFormat::Plain)
         ));
-        assert!(matches!(OutputFormat::from_str("invalid"), None));
+        assert!(OutputFormat::from_str("invalid").is_none());
     }
 
     #[test]
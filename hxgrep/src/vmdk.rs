@@ -0,0 +1,259 @@
+//! VMDK descriptor metadata
+//!
+//! A VMDK image is described by a text descriptor, either in a file of its
+//! own or embedded in the first sparse extent. The descriptor lists the
+//! extents that make up the virtual disk and the disk database (`ddb.*`)
+//! with the geometry and adapter of the virtual machine.
+
+use crate::error::{BingrepError, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Magic number of a hosted sparse extent ("KDMV")
+const SPARSE_MAGIC: &[u8; 4] = b"KDMV";
+
+/// First line of a text descriptor
+const DESCRIPTOR_SIGNATURE: &str = "# Disk DescriptorFile";
+
+/// Largest descriptor file that is read
+const MAX_DESCRIPTOR_LEN: u64 = 1024 * 1024;
+
+const SECTOR_SIZE: u64 = 512;
+
+/// Header of a hosted sparse extent
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SparseHeader {
+    pub version: u32,
+    pub capacity_sectors: u64,
+    pub grain_size_sectors: u64,
+    /// Grain compression ("none", "deflate")
+    pub compression: String,
+}
+
+impl SparseHeader {
+    fn parse(data: &[u8]) -> Option<Self> {
+        let u64_at = |at: usize| Some(u64::from_le_bytes(data.get(at..at + 8)?.try_into().ok()?));
+        Some(SparseHeader {
+            version: u32::from_le_bytes(data.get(4..8)?.try_into().ok()?),
+            capacity_sectors: u64_at(12)?,
+            grain_size_sectors: u64_at(20)?,
+            compression: match u16::from_le_bytes(data.get(77..79)?.try_into().ok()?) {
+                0 => "none".to_string(),
+                1 => "deflate".to_string(),
+                other => format!("unknown ({})", other),
+            },
+        })
+    }
+}
+
+/// An extent line of the descriptor
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VmdkExtent {
+    /// Access mode ("RW", "RDONLY", "NOACCESS")
+    pub access: String,
+    pub sectors: u64,
+    /// Extent type ("SPARSE", "FLAT", "VMFS", "ZERO", ...)
+    pub kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// Start of the data in a flat extent file, in sectors
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u64>,
+}
+
+impl VmdkExtent {
+    /// Parse `RW 4192256 SPARSE "disk-s001.vmdk" [offset]`
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.splitn(3, char::is_whitespace);
+        let access = fields.next()?.to_string();
+        let sectors = fields.next()?.parse().ok()?;
+        let rest = fields.next()?.trim_start();
+        let (kind, rest) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+
+        let rest = rest.trim();
+        let (file, rest) = match rest.strip_prefix('"').and_then(|quoted| quoted.split_once('"')) {
+            Some((file, rest)) => (Some(file.to_string()), rest),
+            None => (None, rest),
+        };
+        Some(VmdkExtent {
+            access,
+            sectors,
+            kind: kind.to_string(),
+            file,
+            offset: rest.trim().parse().ok(),
+        })
+    }
+}
+
+/// Metadata of a VMDK image
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct VmdkInfo {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sparse_header: Option<SparseHeader>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_cid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub create_type: Option<String>,
+    pub extents: Vec<VmdkExtent>,
+    /// `ddb.*` entries without the prefix
+    pub disk_database: BTreeMap<String, String>,
+}
+
+impl VmdkInfo {
+    /// Read the descriptor of a descriptor file or of a sparse extent
+    pub fn read(path: &Path) -> Result<Self> {
+        let mut file = File::open(path)?;
+        let mut header = vec![0u8; SECTOR_SIZE as usize];
+        let header_len = file.read(&mut header)?;
+        header.truncate(header_len);
+
+        if header.starts_with(SPARSE_MAGIC) {
+            let sparse_header = SparseHeader::parse(&header).ok_or_else(|| not_vmdk(path))?;
+            let offset = u64::from_le_bytes(header[28..36].try_into().unwrap());
+            let size = u64::from_le_bytes(header[36..44].try_into().unwrap());
+
+            // Extents of a split image do not embed the descriptor
+            let mut info = if offset > 0 && size > 0 && size * SECTOR_SIZE <= MAX_DESCRIPTOR_LEN {
+                let mut descriptor = vec![0u8; (size * SECTOR_SIZE) as usize];
+                file.seek(SeekFrom::Start(offset * SECTOR_SIZE))?;
+                file.read_exact(&mut descriptor)?;
+                let end = descriptor.iter().position(|&b| b == 0).unwrap_or(descriptor.len());
+                Self::parse(&String::from_utf8_lossy(&descriptor[..end]))
+            } else {
+                VmdkInfo::default()
+            };
+            info.sparse_header = Some(sparse_header);
+            return Ok(info);
+        }
+
+        let text = String::from_utf8_lossy(&header);
+        if !text.starts_with(DESCRIPTOR_SIGNATURE) || file.metadata()?.len() > MAX_DESCRIPTOR_LEN {
+            return Err(not_vmdk(path));
+        }
+        let mut descriptor = header;
+        file.read_to_end(&mut descriptor)?;
+        Ok(Self::parse(&String::from_utf8_lossy(&descriptor)))
+    }
+
+    /// Parse the text of a descriptor
+    pub fn parse(text: &str) -> Self {
+        let mut info = VmdkInfo::default();
+
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if ["RW ", "RDONLY ", "NOACCESS "].iter().any(|access| line.starts_with(access)) {
+                info.extents.extend(VmdkExtent::parse(line));
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = value.trim().trim_matches('"').to_string();
+            match key.trim() {
+                "version" => info.version = Some(value),
+                "CID" => info.cid = Some(value),
+                "parentCID" => info.parent_cid = Some(value),
+                "createType" => info.create_type = Some(value),
+                key => {
+                    if let Some(key) = key.strip_prefix("ddb.") {
+                        info.disk_database.insert(key.to_string(), value);
+                    }
+                }
+            }
+        }
+
+        info
+    }
+
+    /// Size of the virtual disk in bytes
+    ///
+    /// The sum of the extents, or the capacity of the sparse header when the
+    /// descriptor is not available.
+    pub fn media_size(&self) -> u64 {
+        let sectors: u64 = self.extents.iter().map(|extent| extent.sectors).sum();
+        if sectors > 0 {
+            return sectors * SECTOR_SIZE;
+        }
+        self.sparse_header
+            .as_ref()
+            .map_or(0, |header| header.capacity_sectors * SECTOR_SIZE)
+    }
+}
+
+fn not_vmdk(path: &Path) -> BingrepError {
+    BingrepError::InvalidArgument(format!(
+        "{} is neither a VMDK descriptor nor a sparse extent",
+        path.display()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const DESCRIPTOR: &str = "# Disk DescriptorFile\n\
+        version=1\n\
+        CID=fffffffe\n\
+        parentCID=ffffffff\n\
+        createType=\"twoGbMaxExtentSparse\"\n\
+        \n\
+        # Extent description\n\
+        RW 4192256 SPARSE \"disk-s001.vmdk\"\n\
+        RW 2048 FLAT \"disk flat.vmdk\" 128\n\
+        RDONLY 0 ZERO\n\
+        \n\
+        ddb.adapterType = \"lsilogic\"\n\
+        ddb.geometry.cylinders = \"261\"\n";
+
+    #[test]
+    fn test_parse_descriptor() {
+        let info = VmdkInfo::parse(DESCRIPTOR);
+        assert_eq!(info.version.as_deref(), Some("1"));
+        assert_eq!(info.create_type.as_deref(), Some("twoGbMaxExtentSparse"));
+        assert_eq!(info.extents.len(), 3);
+        assert_eq!(info.extents[1].file.as_deref(), Some("disk flat.vmdk"));
+        assert_eq!(info.extents[1].offset, Some(128));
+        assert_eq!(info.extents[2].kind, "ZERO");
+        assert_eq!(info.extents[2].file, None);
+        assert_eq!(info.disk_database["adapterType"], "lsilogic");
+        assert_eq!(info.media_size(), (4192256 + 2048) * 512);
+    }
+
+    #[test]
+    fn test_read_sparse_extent_with_embedded_descriptor() {
+        let mut extent = vec![0u8; 512 * 3];
+        extent[..4].copy_from_slice(SPARSE_MAGIC);
+        extent[4..8].copy_from_slice(&1u32.to_le_bytes());
+        extent[12..20].copy_from_slice(&8192u64.to_le_bytes());
+        extent[20..28].copy_from_slice(&128u64.to_le_bytes());
+        extent[28..36].copy_from_slice(&1u64.to_le_bytes());
+        extent[36..44].copy_from_slice(&2u64.to_le_bytes());
+        extent[77..79].copy_from_slice(&1u16.to_le_bytes());
+        let descriptor = "# Disk DescriptorFile\nCID=1234\nRW 8192 SPARSE \"disk.vmdk\"\n";
+        extent[512..512 + descriptor.len()].copy_from_slice(descriptor.as_bytes());
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(&extent).unwrap();
+        let info = VmdkInfo::read(file.path()).unwrap();
+        let header = info.sparse_header.as_ref().unwrap();
+        assert_eq!(header.capacity_sectors, 8192);
+        assert_eq!(header.compression, "deflate");
+        assert_eq!(info.cid.as_deref(), Some("1234"));
+        assert_eq!(info.extents[0].file.as_deref(), Some("disk.vmdk"));
+        assert_eq!(info.media_size(), 8192 * 512);
+
+        let mut other = tempfile::NamedTempFile::new().unwrap();
+        other.write_all(b"not a disk").unwrap();
+        assert!(VmdkInfo::read(other.path()).is_err());
+    }
+}
@@ -0,0 +1,271 @@
+//! Recursive directory traversal for multi-file mode
+//!
+//! Expands glob patterns and directory arguments into the list of regular
+//! files to search, applying include/exclude globs, size and depth limits,
+//! symlink handling and optional `.gitignore`/`.ignore` rules. Errors met
+//! while walking (unreadable directories, broken links) are reported on
+//! stderr and the walk carries on.
+
+use crate::cli::Cli;
+use crate::error::{BingrepError, Result};
+use glob::glob;
+use ignore::overrides::{Override, OverrideBuilder};
+use ignore::WalkBuilder;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Options controlling how inputs are expanded into files
+#[derive(Debug, Clone, Default)]
+pub struct WalkOptions {
+    /// Descend into directories instead of skipping them
+    pub recursive: bool,
+    /// Only search files matching one of these globs (empty: all files)
+    pub include: Vec<String>,
+    /// Skip files and directories matching any of these globs
+    pub exclude: Vec<String>,
+    /// Skip files smaller than this many bytes
+    pub min_size: Option<u64>,
+    /// Skip files larger than this many bytes
+    pub max_size: Option<u64>,
+    /// Maximum directory depth below each root (0: the root only)
+    pub max_depth: Option<usize>,
+    /// Follow symbolic links (otherwise links are skipped)
+    pub follow_links: bool,
+    /// Honour `.gitignore`, `.ignore` and `.git/info/exclude` files
+    pub respect_ignore: bool,
+}
+
+impl WalkOptions {
+    /// Build walk options from CLI arguments
+    pub fn from_cli(cli: &Cli) -> Result<Self> {
+        if let (Some(min), Some(max)) = (cli.min_size, cli.max_size) {
+            if min > max {
+                return Err(BingrepError::InvalidArgument(format!(
+                    "--min-size ({}) is larger than --max-size ({})",
+                    min, max
+                )));
+            }
+        }
+
+        Ok(Self {
+            recursive: cli.recursive,
+            include: cli.include.clone(),
+            exclude: cli.exclude.clone(),
+            min_size: cli.min_size,
+            max_size: cli.max_size,
+            max_depth: cli.max_depth,
+            follow_links: cli.follow,
+            respect_ignore: cli.gitignore,
+        })
+    }
+
+    /// Expand a glob pattern (or plain path) into the files to search
+    ///
+    /// Files named directly or matched by the pattern are always kept;
+    /// directories are walked with `recursive` and skipped with a warning
+    /// otherwise. An existing path is taken literally, so file names that
+    /// contain glob characters (`[`, `*`) still work. A path without glob
+    /// characters that does not exist is a `NotFound` I/O error, while a
+    /// pattern that matches nothing expands to no files.
+    pub fn expand(&self, pattern: &str) -> Result<Vec<PathBuf>> {
+        if Path::new(pattern).exists() {
+            return self.expand_path(Path::new(pattern));
+        }
+        if !pattern.contains(['*', '?', '[']) {
+            return Err(BingrepError::Io(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{}: No such file or directory", pattern),
+            )));
+        }
+
+        let mut files = Vec::new();
+
+        for path_result in glob(pattern)? {
+            match path_result {
+                Ok(path) => files.extend(self.expand_path(&path)?),
+                Err(e) => eprintln!("Warning: {}", e),
+            }
+        }
+
+        Ok(files)
+    }
+
+    /// Expand a literal path into the files to search
+    ///
+    /// Directories are walked with `recursive`; directories without it and
+    /// missing paths are skipped with a warning.
+    pub fn expand_path(&self, path: &Path) -> Result<Vec<PathBuf>> {
+        if path.is_dir() {
+            if self.recursive {
+                return self.walk(path);
+            }
+            eprintln!(
+                "Warning: {} is a directory, skipping (use -r to search it)",
+                path.display()
+            );
+            return Ok(Vec::new());
+        }
+
+        if !path.exists() {
+            eprintln!("Warning: File {} does not exist, skipping", path.display());
+            return Ok(Vec::new());
+        }
+
+        Ok(vec![path.to_path_buf()])
+    }
+
+    /// Walk a directory and return the regular files that pass the filters
+    pub fn walk(&self, root: &Path) -> Result<Vec<PathBuf>> {
+        let mut builder = WalkBuilder::new(root);
+        builder
+            .standard_filters(false)
+            .hidden(false)
+            .parents(self.respect_ignore)
+            .ignore(self.respect_ignore)
+            .git_ignore(self.respect_ignore)
+            .git_exclude(self.respect_ignore)
+            .require_git(false)
+            .follow_links(self.follow_links)
+            .max_depth(self.max_depth)
+            .overrides(self.overrides(root)?)
+            .sort_by_file_name(|a, b| a.cmp(b));
+
+        let mut files = Vec::new();
+        for entry in builder.build() {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    eprintln!("Warning: {}", e);
+                    continue;
+                }
+            };
+
+            // Skips directories, unfollowed symlinks and special files
+            // (devices, FIFOs, sockets)
+            if !entry.file_type().is_some_and(|t| t.is_file()) {
+                continue;
+            }
+
+            match entry.metadata() {
+                Ok(metadata) if self.size_matches(metadata.len()) => {
+                    files.push(entry.into_path());
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("Warning: {}", e),
+            }
+        }
+
+        Ok(files)
+    }
+
+    /// Whether a file of `size` bytes is within the size limits
+    fn size_matches(&self, size: u64) -> bool {
+        self.min_size.is_none_or(|min| size >= min) && self.max_size.is_none_or(|max| size <= max)
+    }
+
+    /// Compile include/exclude globs relative to `root`
+    fn overrides(&self, root: &Path) -> Result<Override> {
+        let mut builder = OverrideBuilder::new(root);
+        for pattern in &self.include {
+            builder.add(pattern).map_err(glob_error)?;
+        }
+        for pattern in &self.exclude {
+            builder.add(&format!("!{}", pattern)).map_err(glob_error)?;
+        }
+        builder.build().map_err(glob_error)
+    }
+}
+
+fn glob_error(err: ignore::Error) -> BingrepError {
+    BingrepError::GlobPattern(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn create_tree() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("sub/deep")).unwrap();
+        fs::write(root.join("a.bin"), [0u8; 10]).unwrap();
+        fs::write(root.join("b.txt"), [0u8; 100]).unwrap();
+        fs::write(root.join("sub/c.bin"), [0u8; 1000]).unwrap();
+        fs::write(root.join("sub/deep/d.bin"), [0u8; 10]).unwrap();
+        dir
+    }
+
+    fn names(files: &[PathBuf]) -> Vec<String> {
+        files
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_walk_include_exclude() {
+        let dir = create_tree();
+
+        let options = WalkOptions {
+            include: vec!["*.bin".to_string()],
+            exclude: vec!["deep".to_string()],
+            ..Default::default()
+        };
+        let files = options.walk(dir.path()).unwrap();
+        assert_eq!(names(&files), vec!["a.bin", "c.bin"]);
+    }
+
+    #[test]
+    fn test_walk_size_and_depth() {
+        let dir = create_tree();
+
+        let options = WalkOptions {
+            min_size: Some(50),
+            ..Default::default()
+        };
+        assert_eq!(names(&options.walk(dir.path()).unwrap()), vec!["b.txt", "c.bin"]);
+
+        let options = WalkOptions {
+            max_depth: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(names(&options.walk(dir.path()).unwrap()), vec!["a.bin", "b.txt"]);
+    }
+
+    #[test]
+    fn test_walk_respects_ignore_files() {
+        let dir = create_tree();
+        fs::write(dir.path().join(".gitignore"), "sub/\n").unwrap();
+
+        let options = WalkOptions::default();
+        assert_eq!(options.walk(dir.path()).unwrap().len(), 5);
+
+        let options = WalkOptions {
+            respect_ignore: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            names(&options.walk(dir.path()).unwrap()),
+            vec![".gitignore", "a.bin", "b.txt"]
+        );
+    }
+
+    #[test]
+    fn test_expand_missing_literal_path_is_an_error() {
+        let dir = create_tree();
+        let options = WalkOptions::default();
+
+        let missing = dir.path().join("missing.bin");
+        match options.expand(missing.to_str().unwrap()) {
+            Err(BingrepError::Io(e)) => assert_eq!(e.kind(), io::ErrorKind::NotFound),
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        // A pattern that matches nothing is not an error
+        let pattern = dir.path().join("missing*.bin");
+        assert!(options.expand(pattern.to_str().unwrap()).unwrap().is_empty());
+        let pattern = dir.path().join("*.bin");
+        assert_eq!(names(&options.expand(pattern.to_str().unwrap()).unwrap()), vec!["a.bin"]);
+    }
+}
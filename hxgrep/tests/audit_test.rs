@@ -0,0 +1,137 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("hxgrep");
+    path
+}
+
+fn create_test_file(content: &[u8], extension: &str) -> PathBuf {
+    let temp_dir = std::env::temp_dir();
+    let file_path = temp_dir.join(format!("audit_test_{}.{}", uuid::Uuid::new_v4(), extension));
+    let mut file = File::create(&file_path).unwrap();
+    file.write_all(content).unwrap();
+    file_path
+}
+
+fn run(args: &[&str], log: &Path) -> Output {
+    Command::new(get_binary_path())
+        .args(args)
+        .arg("--audit-log")
+        .arg(log)
+        .output()
+        .expect("Failed to execute command")
+}
+
+fn records(log: &Path) -> Vec<serde_json::Value> {
+    fs::read_to_string(log)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_audit_log_records_every_run() {
+    let mut data = vec![0u8; 200_000];
+    for pos in [10, 70_000, 150_000] {
+        data[pos..pos + 5].copy_from_slice(b"FILE0");
+    }
+    let test_file = create_test_file(&data, "bin");
+    let path = test_file.to_str().unwrap();
+    let log = test_file.with_extension("audit");
+
+    // 매칭 있음, 매칭 없음(-n 적용), 오류, 하위 명령
+    assert_eq!(run(&[path, "-e", "FILE0", "-p", "--chunk-size", "65536"], &log).status.code(), Some(0));
+    assert_eq!(run(&[path, "-e", "FILE0", "-n", "2", "--hash", "md5"], &log).status.code(), Some(0));
+    assert_eq!(run(&[path, "-e", "NOTHERE"], &log).status.code(), Some(1));
+    assert_eq!(run(&["/nonexistent/evidence.bin", "-e", "X"], &log).status.code(), Some(2));
+    assert_eq!(run(&["stats", path], &log).status.code(), Some(0));
+    assert_eq!(run(&["strings", path, "--filter", "FILE0"], &log).status.code(), Some(0));
+
+    let records = records(&log);
+    assert_eq!(records.len(), 6);
+    assert_eq!(records[0]["sequence"], 1);
+    assert_eq!(records[0]["matches"], 3);
+    assert_eq!(records[0]["patterns"][0], "FILE0");
+    assert_eq!(records[0]["inputs"][0]["size"], 200_000);
+    // --hash 없이도 검색 중에 계산한 SHA-256을 기록
+    assert_eq!(records[0]["inputs"][0]["hash_scope"], "input");
+    assert_eq!(records[0]["inputs"][0]["hashes"]["sha256"], sha256_hex(&data));
+    assert!(records[0]["argv"].as_array().unwrap().contains(&serde_json::json!("-p")));
+    assert_eq!(records[1]["matches"], 2);
+    assert_eq!(records[1]["inputs"][0]["hash_scope"], "input");
+    assert_eq!(records[1]["previous_hash"], records[0]["record_hash"]);
+    assert_eq!(records[2]["exit_status"], 1);
+    assert_eq!(records[3]["exit_status"], 2);
+    assert!(!records[3]["errors"].as_array().unwrap().is_empty());
+    assert!(records[3]["inputs"][0]["error"].is_string());
+    assert_eq!(records[4]["inputs"][0]["path"], path);
+    assert_eq!(records[4]["matches"], 0);
+    assert_eq!(records[4]["inputs"][0]["hashes"]["sha256"], sha256_hex(&data));
+    // 하위 명령도 찾은 개수를 기록
+    assert_eq!(records[5]["matches"], 3);
+    let output = Command::new(get_binary_path())
+        .arg("verify-audit")
+        .arg(&log)
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("6 record(s), hash chain intact"));
+
+    // 정리
+    fs::remove_file(&test_file).ok();
+    fs::remove_file(&log).ok();
+}
+
+#[test]
+fn test_verify_audit_detects_tampering() {
+    let test_file = create_test_file(b"..MZ..MZ..", "bin");
+    let path = test_file.to_str().unwrap();
+    let log = test_file.with_extension("audit");
+    for _ in 0..3 {
+        run(&[path, "-e", "MZ"], &log);
+    }
+
+    // 매칭 수를 고친 기록
+    let original = fs::read_to_string(&log).unwrap();
+    fs::write(&log, original.replacen("\"matches\":2", "\"matches\":0", 1)).unwrap();
+    let output = Command::new(get_binary_path())
+        .arg("verify-audit")
+        .arg(&log)
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("line 1: record_hash"));
+
+    // 삭제된 기록
+    let lines: Vec<&str> = original.lines().collect();
+    fs::write(&log, format!("{}\n{}\n", lines[0], lines[2])).unwrap();
+    let output = Command::new(get_binary_path())
+        .arg("verify-audit")
+        .arg(&log)
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("line 2: previous_hash"));
+
+    // 손상된 로그에는 기록을 추가하지 않고 실행을 거부
+    fs::write(&log, "{broken\n").unwrap();
+    let output = run(&[path, "-e", "MZ"], &log);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+
+    // 정리
+    fs::remove_file(&test_file).ok();
+    fs::remove_file(&log).ok();
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    use sha2::Digest;
+    hex::encode(sha2::Sha256::digest(data))
+}
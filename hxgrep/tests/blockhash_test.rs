@@ -0,0 +1,145 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("hxgrep");
+    path
+}
+
+fn create_test_file(content: &[u8], extension: &str) -> PathBuf {
+    let temp_dir = std::env::temp_dir();
+    let file_path = temp_dir.join(format!(
+        "blockhash_test_{}.{}",
+        uuid::Uuid::new_v4(),
+        extension
+    ));
+    let mut file = File::create(&file_path).unwrap();
+    file.write_all(content).unwrap();
+    file_path
+}
+
+/// 알려진 파일의 512바이트 블록
+fn known_block(seed: u8) -> Vec<u8> {
+    (0..512u32)
+        .map(|i| (i as u8).wrapping_mul(31).wrapping_add(seed))
+        .collect()
+}
+
+#[test]
+fn test_blockhash_reports_known_blocks() {
+    let binary_path = get_binary_path();
+    let mut data = vec![0u8; 512 * 20];
+    data[512 * 3..512 * 4].copy_from_slice(&known_block(1));
+    data[512 * 11..512 * 12].copy_from_slice(&known_block(2));
+    // 정렬되지 않은 위치의 블록은 보고하지 않음
+    data[7000..7512].copy_from_slice(&known_block(1));
+    let test_file = create_test_file(&data, "bin");
+
+    let nsrl = format!(
+        "\"SHA-1\",\"MD5\",\"CRC32\",\"FileName\",\"FileSize\"\n\"{}\",\"{}\",\"00000000\",\"a.doc\",512\n\"{}\",\"{}\",\"00000000\",\"b.jpg\",512\n",
+        sha1_hex(&known_block(1)),
+        "00".repeat(16),
+        sha1_hex(&known_block(2)),
+        "00".repeat(16),
+    );
+    let hashset = create_test_file(nsrl.as_bytes(), "csv");
+
+    let output = Command::new(&binary_path)
+        .arg("blockhash")
+        .arg(&test_file)
+        .arg("--hashset")
+        .arg(&hashset)
+        .args([
+            "--algorithm",
+            "sha1",
+            "--block-size",
+            "512",
+            "--chunk-size",
+            "1000",
+            "-p",
+        ])
+        .args(["-f", "json"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let matches: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let matches = matches.as_array().unwrap();
+    assert_eq!(matches.len(), 2);
+    assert_eq!(matches[0]["offset"], 1536);
+    assert_eq!(matches[0]["known_file"], "a.doc");
+    assert_eq!(matches[1]["offset"], 5632);
+    assert_eq!(matches[1]["known_file"], "b.jpg");
+    assert_eq!(matches[1]["hash"], sha1_hex(&known_block(2)));
+
+    // 정리
+    fs::remove_file(&test_file).ok();
+    fs::remove_file(&hashset).ok();
+}
+
+#[test]
+fn test_blockhash_block_offset_and_invalid_hashset() {
+    let binary_path = get_binary_path();
+    // 파티션이 100바이트 위치에서 시작하는 이미지
+    let mut data = vec![0u8; 100 + 512 * 4];
+    data[100 + 512..100 + 1024].copy_from_slice(&known_block(3));
+    let test_file = create_test_file(&data, "bin");
+
+    let list = format!("{}  known.bin\n", md5_hex(&known_block(3)));
+    let hashset = create_test_file(list.as_bytes(), "txt");
+
+    let output = Command::new(&binary_path)
+        .arg("blockhash")
+        .arg(&test_file)
+        .arg("--hashset")
+        .arg(&hashset)
+        .args([
+            "--block-size",
+            "512",
+            "--block-offset",
+            "100",
+            "-f",
+            "plain",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(":612 "), "{}", stdout);
+    assert!(stdout.trim_end().ends_with("known.bin"));
+
+    // 알고리즘과 길이가 맞지 않는 해시는 오류
+    let output = Command::new(&binary_path)
+        .arg("blockhash")
+        .arg(&test_file)
+        .arg("--hashset")
+        .arg(&hashset)
+        .args(["--algorithm", "sha256"])
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("line 1"));
+
+    // 정리
+    fs::remove_file(&test_file).ok();
+    fs::remove_file(&hashset).ok();
+}
+
+fn sha1_hex(data: &[u8]) -> String {
+    use sha1::Digest;
+    hex::encode(sha1::Sha1::digest(data))
+}
+
+fn md5_hex(data: &[u8]) -> String {
+    use md5::Digest;
+    hex::encode(md5::Md5::digest(data))
+}
@@ -0,0 +1,124 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("hxgrep");
+    path
+}
+
+fn create_test_file(content: &[u8]) -> PathBuf {
+    let temp_dir = std::env::temp_dir();
+    let file_path = temp_dir.join(format!("capture_test_{}.bin", uuid::Uuid::new_v4()));
+    let mut file = File::create(&file_path).unwrap();
+    file.write_all(content).unwrap();
+    file_path
+}
+
+fn png_like_data() -> Vec<u8> {
+    let mut data = b"junk".to_vec();
+    data.extend_from_slice(b"\x89PNG\x00\x00\x00\x0DIHDR");
+    data.extend(vec![0u8; 16]);
+    data.extend_from_slice(b"\x89PNG\x00\x00\x01\x00");
+    data
+}
+
+const PNG_PATTERN: &str = "(?-u)(?P<magic>\\x89PNG)(?P<len>.{4})";
+
+#[test]
+fn test_only_matching() {
+    let binary_path = get_binary_path();
+    let test_file = create_test_file(&png_like_data());
+
+    let output = Command::new(&binary_path)
+        .arg(&test_file)
+        .arg("-e")
+        .arg(PNG_PATTERN)
+        .arg("-o")
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    // -w 16 대신 매칭된 8바이트만 출력
+    assert_eq!(lines, vec!["04h : 89 50 4E 47 00 00 00 0D", "20h : 89 50 4E 47 00 00 01 00"]);
+
+    // 정리
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_capture_by_name_and_index() {
+    let binary_path = get_binary_path();
+    let test_file = create_test_file(&png_like_data());
+
+    for capture in ["len", "2"] {
+        let output = Command::new(&binary_path)
+            .arg(&test_file)
+            .arg("-e")
+            .arg(PNG_PATTERN)
+            .arg("--capture")
+            .arg(capture)
+            .arg("-p")
+            .arg("--chunk-size")
+            .arg("16")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(lines, vec!["08h : 00 00 00 0D  [len]", "24h : 00 00 01 00  [len]"]);
+    }
+
+    // 존재하지 않는 그룹은 오류
+    let output = Command::new(&binary_path)
+        .arg(&test_file)
+        .arg("-e")
+        .arg(PNG_PATTERN)
+        .arg("--capture")
+        .arg("size")
+        .output()
+        .expect("Failed to execute command");
+    assert!(!output.status.success());
+
+    // 정리
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_named_groups_in_json() {
+    let binary_path = get_binary_path();
+    let test_file = create_test_file(&png_like_data());
+
+    let output = Command::new(&binary_path)
+        .arg(&test_file)
+        .arg("-e")
+        .arg(PNG_PATTERN)
+        .arg("-f")
+        .arg("json")
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let records: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let records = records.as_array().unwrap();
+    assert_eq!(records.len(), 2);
+
+    let groups = records[0]["groups"].as_array().unwrap();
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups[0]["name"], "magic");
+    assert_eq!(groups[0]["offset"], 4);
+    assert_eq!(groups[1]["name"], "len");
+    assert_eq!(groups[1]["offset"], 8);
+    assert_eq!(groups[1]["length"], 4);
+    assert_eq!(groups[1]["hex_data"], "00 00 00 0D");
+
+    // 정리
+    fs::remove_file(test_file).ok();
+}
@@ -0,0 +1,133 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("hxgrep");
+    path
+}
+
+fn create_test_file(content: &[u8]) -> PathBuf {
+    let temp_dir = std::env::temp_dir();
+    let file_path = temp_dir.join(format!("checkpoint_test_{}.bin", uuid::Uuid::new_v4()));
+    let mut file = File::create(&file_path).unwrap();
+    file.write_all(content).unwrap();
+    file_path
+}
+
+fn checkpoint_path() -> PathBuf {
+    std::env::temp_dir().join(format!("checkpoint_test_{}.ckpt", uuid::Uuid::new_v4()))
+}
+
+/// 여러 청크/버퍼에 걸쳐 패턴이 흩어진 3MB 데이터
+fn test_data() -> Vec<u8> {
+    let mut data = vec![0u8; 3 * 1024 * 1024];
+    for pos in [10, 70_000, 1_048_570, 1_048_580, 1_500_000, 3_000_000] {
+        data[pos..pos + 4].copy_from_slice(b"MARK");
+    }
+    data
+}
+
+fn search(file: &Path, args: &[&str]) -> Output {
+    Command::new(get_binary_path())
+        .arg(file)
+        .args(["-e", "MARK"])
+        .args(args)
+        .output()
+        .expect("Failed to execute search")
+}
+
+#[test]
+fn test_resume_reports_remaining_matches() {
+    let test_file = create_test_file(&test_data());
+
+    for mode in [vec![], vec!["-p", "--chunk-size", "100000"]] {
+        let full = search(&test_file, &mode);
+        let checkpoint = checkpoint_path();
+        let checkpoint_arg = checkpoint.to_str().unwrap();
+
+        // 처음 3개에서 중단된 검색을 흉내냄
+        let first = search(
+            &test_file,
+            &[&mode[..], &["-n", "3", "--checkpoint", checkpoint_arg]].concat(),
+        );
+        assert!(first.status.success());
+        let saved: serde_json::Value =
+            serde_json::from_slice(&fs::read(&checkpoint).unwrap()).unwrap();
+        assert_eq!(saved["matches"], 3);
+
+        // 재개하면 나머지 매칭만 출력
+        let resumed = search(
+            &test_file,
+            &[&mode[..], &["--resume", checkpoint_arg]].concat(),
+        );
+        assert!(resumed.status.success());
+
+        let mut combined = first.stdout.clone();
+        combined.extend_from_slice(&resumed.stdout);
+        assert_eq!(
+            String::from_utf8_lossy(&combined),
+            String::from_utf8_lossy(&full.stdout),
+            "{:?}",
+            mode
+        );
+
+        // 완료된 체크포인트에는 전체 매칭 수가 기록됨
+        let saved: serde_json::Value =
+            serde_json::from_slice(&fs::read(&checkpoint).unwrap()).unwrap();
+        assert_eq!(
+            saved["matches"],
+            String::from_utf8_lossy(&full.stdout).lines().count()
+        );
+        assert_eq!(saved["offset"], 3 * 1024 * 1024);
+
+        // 정리
+        fs::remove_file(&checkpoint).ok();
+    }
+
+    // 정리
+    fs::remove_file(&test_file).ok();
+}
+
+#[test]
+fn test_resume_refuses_other_pattern_and_input() {
+    let test_file = create_test_file(&test_data());
+    let other_file = create_test_file(&[0u8; 1024]);
+    let checkpoint = checkpoint_path();
+    let checkpoint_arg = checkpoint.to_str().unwrap();
+
+    let first = search(&test_file, &["-n", "1", "--checkpoint", checkpoint_arg]);
+    assert!(first.status.success());
+
+    // 다른 패턴
+    let output = Command::new(get_binary_path())
+        .arg(&test_file)
+        .args(["-e", "OTHER", "--resume", checkpoint_arg])
+        .output()
+        .expect("Failed to execute search");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("different pattern"));
+
+    // 다른 입력
+    let output = search(&other_file, &["--resume", checkpoint_arg]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("changed since the checkpoint"));
+
+    // 다중 파일 모드는 지원하지 않음
+    let output = Command::new(get_binary_path())
+        .arg(&test_file)
+        .arg(&other_file)
+        .args(["-e", "MARK", "--checkpoint", checkpoint_arg])
+        .output()
+        .expect("Failed to execute search");
+    assert_eq!(output.status.code(), Some(2));
+
+    // 정리
+    fs::remove_file(&test_file).ok();
+    fs::remove_file(&other_file).ok();
+    fs::remove_file(&checkpoint).ok();
+}
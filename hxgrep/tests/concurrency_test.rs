@@ -1,7 +1,7 @@
 use std::fs::{self, File};
 use std::io::Write;
 use std::path::PathBuf;
-use std::process::Command;
+use std::process::{Command, Stdio};
 use std::sync::{Arc, Barrier};
 use std::thread;
 use std::time::Duration;
@@ -50,7 +50,7 @@ fn test_concurrent_file_access() {
                 .arg("-e")
                 .arg("\\x00\\x01\\x02\\x03")
                 .output()
-                .expect(&format!("Failed to execute command in thread {}", i));
+                .unwrap_or_else(|_| panic!("Failed to execute command in thread {}", i));
 
             (
                 output.status.success(),
@@ -122,7 +122,7 @@ fn test_concurrent_different_files() {
                 .arg("-e")
                 .arg(&pattern_str)
                 .output()
-                .expect(&format!("Failed to execute command in thread {}", i));
+                .unwrap_or_else(|_| panic!("Failed to execute command in thread {}", i));
 
             let result = (
                 output.status.success(),
@@ -191,7 +191,7 @@ fn test_concurrent_different_patterns() {
                 .arg("-e")
                 .arg(&pattern_str)
                 .output()
-                .expect(&format!("Failed to execute command for pattern {}", i));
+                .unwrap_or_else(|_| panic!("Failed to execute command for pattern {}", i));
 
             (
                 i,
@@ -256,7 +256,7 @@ fn test_stress_concurrent_execution() {
                     .arg("-e")
                     .arg("\\x42\\x42\\x42\\x42")
                     .output()
-                    .expect(&format!(
+                    .unwrap_or_else(|_| panic!(
                         "Failed to execute command in thread {} iteration {}",
                         thread_id, iteration
                     ));
@@ -353,7 +353,7 @@ fn test_concurrent_with_different_options() {
 
             let output = cmd
                 .output()
-                .expect(&format!("Failed to execute command for {}", test_name));
+                .unwrap_or_else(|_| panic!("Failed to execute command for {}", test_name));
 
             (
                 test_name,
@@ -380,3 +380,133 @@ fn test_concurrent_with_different_options() {
     // 정리
     fs::remove_file(test_file).ok();
 }
+
+/// 파일마다 고유한 표식 바이트를 가진 매칭을 여러 개 포함하는 파일 생성
+fn create_marked_files(count: usize) -> (String, Vec<PathBuf>) {
+    let temp_dir = std::env::temp_dir();
+    let test_id = uuid::Uuid::new_v4();
+    let mut files = Vec::new();
+
+    for i in 0..count {
+        let mut data = Vec::new();
+        for _ in 0..200 {
+            data.extend_from_slice(b"MARK");
+            data.push(i as u8);
+            data.extend_from_slice(&[0xEE; 27]);
+        }
+        let file_path = temp_dir.join(format!("bingrep_ordered_test_{}_{:02}.bin", test_id, i));
+        let mut file = File::create(&file_path).unwrap();
+        file.write_all(&data).unwrap();
+        files.push(file_path);
+    }
+
+    let pattern = temp_dir.join(format!("bingrep_ordered_test_{}_*.bin", test_id));
+    (pattern.to_string_lossy().into_owned(), files)
+}
+
+/// 출력을 파일별 블록으로 나누고, 각 블록의 매칭 줄이 모두 해당 파일의 것인지 확인
+fn check_blocks(stdout: &str, files: &[PathBuf]) -> Vec<String> {
+    let body = stdout.split("=== Summary ===").next().unwrap();
+    let blocks: Vec<String> = body
+        .split("=== Processing: ")
+        .skip(1)
+        .map(|block| block.to_string())
+        .collect();
+    assert_eq!(blocks.len(), files.len(), "{}", stdout);
+
+    for block in &blocks {
+        let header = block.lines().next().unwrap();
+        let index = files
+            .iter()
+            .position(|f| header.starts_with(&f.display().to_string()))
+            .expect("block header should name one of the files");
+        let marker = format!("4D 41 52 4B {:02X}", index);
+
+        let lines: Vec<&str> = block.lines().skip(1).filter(|l| !l.is_empty()).collect();
+        assert_eq!(lines.len(), 200, "file {} block is incomplete", index);
+        for line in lines {
+            assert!(line.contains(&marker), "line from another file in block {}: {}", index, line);
+        }
+    }
+
+    blocks
+}
+
+#[test]
+fn test_parallel_multi_file_output_integrity() {
+    let binary_path = get_binary_path();
+    let (pattern, files) = create_marked_files(12);
+
+    let run = |extra: &[&str]| {
+        let output = Command::new(&binary_path)
+            .arg(&pattern)
+            .args(["-m", "-e", "(?s)MARK.", "-w", "5", "--color", "never"])
+            .args(extra)
+            .output()
+            .expect("Failed to execute multi-file search");
+        assert!(output.status.success());
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+
+    let sequential = run(&[]);
+    check_blocks(&sequential, &files);
+
+    // 입력 순서: 순차 처리와 완전히 같은 출력
+    for _ in 0..3 {
+        let parallel = run(&["-p", "--threads", "4"]);
+        assert_eq!(parallel, sequential);
+    }
+
+    // 완료 순서: 블록 순서는 달라도 블록 내용은 섞이지 않음
+    let completion = run(&["-p", "--threads", "4", "--file-order", "completion"]);
+    let mut completion_blocks = check_blocks(&completion, &files);
+    let mut sequential_blocks = check_blocks(&sequential, &files);
+    completion_blocks.sort();
+    sequential_blocks.sort();
+    assert_eq!(completion_blocks, sequential_blocks);
+    assert!(completion.contains("Total matches/lines processed: 2400"));
+
+    // 정리
+    for file in files {
+        fs::remove_file(file).ok();
+    }
+}
+
+#[test]
+fn test_parallel_global_limit_defers_files() {
+    let binary_path = get_binary_path();
+    let (pattern, files) = create_marked_files(12);
+
+    // 첫 파일이 전역 제한을 모두 예약하면 나머지 파일은 기다리지 않고 미뤄졌다가
+    // 남은 예산으로 처리됨
+    let mut child = Command::new(&binary_path)
+        .arg(&pattern)
+        .args(["-m", "-p", "--threads", "2", "--chunk-size", "1024"])
+        .args(["-e", "(?s)MARK.", "-w", "5", "--color", "never", "--global-limit", "450"])
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to spawn multi-file search");
+
+    // 작업자가 예산을 기다리며 멈추면 제한 시간 안에 끝나지 않음
+    let started = std::time::Instant::now();
+    while child.try_wait().unwrap().is_none() {
+        if started.elapsed() > Duration::from_secs(60) {
+            child.kill().ok();
+            panic!("multi-file search with --global-limit did not finish");
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines = stdout.lines().filter(|line| line.contains("4D 41 52 4B")).count();
+    assert_eq!(lines, 450);
+    assert!(stdout.contains("=== Global limit of 450 reached ==="));
+    assert!(stdout.contains("Total matches/lines processed: 450"));
+
+    // 정리
+    for file in files {
+        fs::remove_file(file).ok();
+    }
+}
@@ -0,0 +1,107 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("hxgrep");
+    path
+}
+
+fn create_test_file(content: &[u8]) -> PathBuf {
+    let temp_dir = std::env::temp_dir();
+    let file_path = temp_dir.join(format!("diff_test_{}.bin", uuid::Uuid::new_v4()));
+    let mut file = File::create(&file_path).unwrap();
+    file.write_all(content).unwrap();
+    file_path
+}
+
+#[test]
+fn test_diff_aligned_reports_changed_bytes() {
+    let binary_path = get_binary_path();
+    let mut old = vec![0u8; 64];
+    let mut new = old.clone();
+    new[0x20] = 0xAA;
+    new[0x21] = 0xBB;
+    old.extend(b"TAIL");
+
+    let file_a = create_test_file(&old);
+    let file_b = create_test_file(&new);
+
+    let output = Command::new(&binary_path)
+        .arg("diff")
+        .arg(&file_a)
+        .arg(&file_b)
+        .arg("--chunk-size")
+        .arg("7")
+        .output()
+        .expect("Failed to execute command");
+
+    // 차이가 있으면 cmp처럼 종료 코드 1
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+
+    // 변경된 두 바이트와 첫 파일에만 있는 꼬리 부분이 보고되어야 함
+    assert_eq!(
+        lines,
+        vec!["20h : - 00 00", "20h : + AA BB", "40h : - 54 41 49 4C"]
+    );
+
+    // 정리
+    fs::remove_file(file_a).ok();
+    fs::remove_file(file_b).ok();
+}
+
+#[test]
+fn test_diff_insert_mode_and_summary() {
+    let binary_path = get_binary_path();
+    let mut old = Vec::new();
+    for i in 0..200u32 {
+        old.extend(i.to_le_bytes());
+    }
+    let mut new = old.clone();
+    new.splice(100..100, b"INSERTED".iter().copied());
+
+    let file_a = create_test_file(&old);
+    let file_b = create_test_file(&new);
+
+    let output = Command::new(&binary_path)
+        .arg("diff")
+        .arg(&file_a)
+        .arg(&file_b)
+        .arg("--mode")
+        .arg("insert")
+        .arg("-f")
+        .arg("json")
+        .output()
+        .expect("Failed to execute command");
+
+    assert_eq!(output.status.code(), Some(1));
+    let ranges: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    // 삽입 이후에는 재동기화되어 하나의 삽입 범위만 남아야 함
+    let ranges = ranges.as_array().unwrap();
+    assert_eq!(ranges.len(), 1);
+    assert_eq!(ranges[0]["kind"], "inserted");
+    assert_eq!(ranges[0]["offset_b"], 100);
+    assert_eq!(ranges[0]["length_b"], 8);
+
+    let output = Command::new(&binary_path)
+        .arg("diff")
+        .arg(&file_a)
+        .arg(&file_a)
+        .arg("--summary")
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("identical (800 bytes)"));
+
+    // 정리
+    fs::remove_file(file_a).ok();
+    fs::remove_file(file_b).ok();
+}
@@ -226,7 +226,7 @@ fn test_invalid_regex_pattern() {
     // 명령이 실패하거나 적절한 에러 메시지를 출력해야 함
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        assert!(stderr.len() > 0); // 에러 메시지가 있어야 함
+        assert!(!stderr.is_empty()); // 에러 메시지가 있어야 함
     }
 
     // 정리
@@ -277,7 +277,7 @@ fn test_zero_line_width() {
     // 0은 유효하지 않은 값이므로 에러가 발생하거나 기본값이 사용되어야 함
     let stderr = String::from_utf8_lossy(&output.stderr);
     if !output.status.success() {
-        assert!(stderr.len() > 0);
+        assert!(!stderr.is_empty());
     }
 
     // 정리
@@ -305,3 +305,41 @@ fn test_extremely_large_position() {
     // 정리
     fs::remove_file(test_file).ok();
 }
+
+#[test]
+fn test_matches_at_buffer_boundaries() {
+    let binary_path = get_binary_path();
+
+    // 64KB 버퍼 경계에 걸친 매칭과, 출력 폭이 버퍼 끝을 넘는 매칭
+    let mut data = vec![0u8; 0x30000];
+    let positions = [0x64, 0xFFFC, 0x1FFF0, 0x1FFFE, 0x2FFFB];
+    for pos in positions {
+        data[pos..pos + 5].copy_from_slice(b"FILE0");
+    }
+    let test_file = create_test_file(&data);
+
+    let output = Command::new(&binary_path)
+        .arg(&test_file)
+        .args(["-e", "FILE0"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let offsets: Vec<&str> = stdout.lines().map(|line| line.split(' ').next().unwrap()).collect();
+    assert_eq!(
+        offsets,
+        ["00000000064h", "0000000FFFCh", "0000001FFF0h", "0000001FFFEh", "0000002FFFBh"]
+    );
+    assert!(stdout.contains("0000001FFFEh : 46 49 4C 45 30 00 00 00"));
+
+    // -c도 같은 엔진 결과와 일치
+    let output = Command::new(&binary_path)
+        .arg(&test_file)
+        .args(["-c", "-e", "FILE0"])
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), positions.len().to_string());
+
+    // 정리
+    fs::remove_file(test_file).ok();
+}
@@ -0,0 +1,91 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("hxgrep");
+    path
+}
+
+fn create_test_file(content: &[u8]) -> PathBuf {
+    let temp_dir = std::env::temp_dir();
+    let file_path = temp_dir.join(format!("entropy_test_{}.bin", uuid::Uuid::new_v4()));
+    let mut file = File::create(&file_path).unwrap();
+    file.write_all(content).unwrap();
+    file_path
+}
+
+/// 0으로 채운 영역 사이에 모든 바이트 값이 고르게 분포한 영역을 둔 데이터
+fn create_mixed_entropy_data() -> Vec<u8> {
+    let mut data = vec![0u8; 8192];
+    data.extend((0..=255u8).cycle().take(8192));
+    data.extend(vec![0u8; 4096]);
+    data
+}
+
+#[test]
+fn test_entropy_regions() {
+    let binary_path = get_binary_path();
+    let test_file = create_test_file(&create_mixed_entropy_data());
+
+    let output = Command::new(&binary_path)
+        .arg("entropy")
+        .arg(&test_file)
+        .arg("--high")
+        .arg("7.5")
+        .arg("--low")
+        .arg("0.5")
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+
+    // 저엔트로피 - 고엔트로피 - 저엔트로피 세 영역이 보고되어야 함
+    assert_eq!(lines.len(), 3, "stdout: {}", stdout);
+    assert!(lines[0].starts_with("0000h - 2000h : low entropy"));
+    assert!(lines[1].starts_with("2000h - 4000h : high entropy"));
+    assert!(lines[2].starts_with("4000h - 5000h : low entropy"));
+
+    // 정리
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_entropy_json_parallel() {
+    let binary_path = get_binary_path();
+    let test_file = create_test_file(&create_mixed_entropy_data());
+
+    let output = Command::new(&binary_path)
+        .arg("entropy")
+        .arg(&test_file)
+        .arg("--window")
+        .arg("1024")
+        .arg("--step")
+        .arg("512")
+        .arg("-p")
+        .arg("--chunk-size")
+        .arg("3000")
+        .arg("-f")
+        .arg("json")
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let blocks: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let blocks = blocks.as_array().unwrap();
+
+    // 20480 바이트, 1024 윈도우, 512 간격 -> 39개 윈도우
+    assert_eq!(blocks.len(), 39);
+    assert_eq!(blocks[0]["offset"], 0);
+    assert_eq!(blocks[38]["offset"], 19456);
+    assert_eq!(blocks[20]["entropy"], 8.0);
+
+    // 정리
+    fs::remove_file(test_file).ok();
+}
@@ -0,0 +1,291 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("hxgrep");
+    path
+}
+
+fn create_test_file(content: &[u8]) -> PathBuf {
+    let temp_dir = std::env::temp_dir();
+    let file_path = temp_dir.join(format!("exit_code_test_{}.bin", uuid::Uuid::new_v4()));
+    let mut file = File::create(&file_path).unwrap();
+    file.write_all(content).unwrap();
+    file_path
+}
+
+fn test_data() -> Vec<u8> {
+    let mut data = vec![0u8; 4096];
+    data[100..104].copy_from_slice(b"MARK");
+    data[2000..2004].copy_from_slice(b"MARK");
+    data[4000..4004].copy_from_slice(b"MARK");
+    data
+}
+
+#[test]
+fn test_exit_codes_single_and_parallel() {
+    let binary_path = get_binary_path();
+    let test_file = create_test_file(&test_data());
+
+    for parallel in [false, true] {
+        let run = |pattern: &str| {
+            let mut command = Command::new(&binary_path);
+            command.arg(&test_file).arg("-e").arg(pattern);
+            if parallel {
+                command.arg("-p").arg("--chunk-size").arg("1024");
+            }
+            command.output().expect("Failed to execute command")
+        };
+
+        // 매칭 있음: 0, 매칭 없음: 1
+        assert_eq!(run("MARK").status.code(), Some(0));
+        assert_eq!(run("NOPE").status.code(), Some(1));
+        // 잘못된 정규표현식: 2
+        assert_eq!(run("(MARK").status.code(), Some(2));
+    }
+
+    // 존재하지 않는 파일: 2
+    let output = Command::new(&binary_path)
+        .arg("/nonexistent/exit_code_test.bin")
+        .arg("-e")
+        .arg("MARK")
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(output.status.code(), Some(2));
+
+    // 정리
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_count_and_quiet() {
+    let binary_path = get_binary_path();
+    let test_file = create_test_file(&test_data());
+
+    for parallel in [false, true] {
+        let mut command = Command::new(&binary_path);
+        command.arg(&test_file).arg("-e").arg("MARK").arg("-c");
+        if parallel {
+            command.arg("-p").arg("--chunk-size").arg("1024");
+        }
+        let output = command.output().expect("Failed to execute command");
+        assert_eq!(output.status.code(), Some(0));
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "3");
+    }
+
+    let output = Command::new(&binary_path)
+        .arg(&test_file)
+        .arg("-e")
+        .arg("NOPE")
+        .arg("--count")
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "0");
+
+    let output = Command::new(&binary_path)
+        .arg(&test_file)
+        .arg("-e")
+        .arg("MARK")
+        .arg("-q")
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(output.status.code(), Some(0));
+    assert!(output.stdout.is_empty());
+
+    // 정리
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_stdin_quiet_and_count() {
+    let binary_path = get_binary_path();
+
+    for (args, expected_code, expected_stdout) in [
+        (vec!["-e", "MARK", "-q"], 0, ""),
+        (vec!["-e", "NOPE", "-q"], 1, ""),
+        (vec!["-e", "MARK", "-c"], 0, "3"),
+        (vec!["-e", "NOPE"], 1, ""),
+    ] {
+        let mut child = Command::new(&binary_path)
+            .arg("-")
+            .args(&args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Failed to spawn command");
+        child.stdin.take().unwrap().write_all(&test_data()).unwrap();
+        let output = child.wait_with_output().unwrap();
+
+        assert_eq!(output.status.code(), Some(expected_code), "{:?}", args);
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), expected_stdout);
+    }
+}
+
+#[test]
+fn test_files_with_and_without_matches() {
+    let binary_path = get_binary_path();
+    let temp_dir = std::env::temp_dir().join(format!("exit_code_test_{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&temp_dir).unwrap();
+
+    let hit = temp_dir.join("hit.bin");
+    let miss = temp_dir.join("miss.bin");
+    fs::write(&hit, test_data()).unwrap();
+    fs::write(&miss, vec![0u8; 100]).unwrap();
+    let pattern = temp_dir.join("*.bin");
+
+    let run = |flag: &str| {
+        Command::new(&binary_path)
+            .arg(pattern.to_string_lossy().as_ref())
+            .arg("--multi")
+            .arg("-e")
+            .arg("MARK")
+            .arg(flag)
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    let output = run("-l");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), hit.display().to_string());
+
+    let output = run("-L");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), miss.display().to_string());
+
+    // 파일이 여러 개면 "파일:개수" 형식
+    let output = run("-c");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(&format!("{}:3", hit.display())), "{}", stdout);
+    assert!(stdout.contains(&format!("{}:0", miss.display())), "{}", stdout);
+
+    // 정리
+    fs::remove_dir_all(temp_dir).ok();
+}
+
+#[test]
+fn test_missing_positional_input() {
+    let binary_path = get_binary_path();
+    let test_file = create_test_file(&test_data());
+    let missing = std::env::temp_dir().join(format!("exit_code_test_{}_missing.bin", uuid::Uuid::new_v4()));
+
+    // 없는 파일은 오류를 출력하고, 나머지 입력을 처리한 뒤 종료 코드 2
+    for extra in [&[][..], &["-c"][..], &["-p"][..]] {
+        let output = Command::new(&binary_path)
+            .arg(&test_file)
+            .arg(&missing)
+            .args(["-e", "MARK"])
+            .args(extra)
+            .output()
+            .expect("Failed to execute command");
+        assert_eq!(output.status.code(), Some(2), "{:?}", extra);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            stderr.contains(&format!("{}: No such file or directory", missing.display())),
+            "{}",
+            stderr
+        );
+        assert!(!String::from_utf8_lossy(&output.stdout).is_empty());
+    }
+
+    // -q는 매칭이 있으면 0 (grep과 동일)
+    let output = Command::new(&binary_path)
+        .arg(&missing)
+        .arg(&test_file)
+        .args(["-e", "MARK", "-q"])
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(output.status.code(), Some(0));
+
+    // glob 패턴이 아무것도 찾지 못한 것은 오류가 아님
+    let output = Command::new(&binary_path)
+        .arg(&test_file)
+        .arg(std::env::temp_dir().join("exit_code_test_nothing_*.none"))
+        .args(["-e", "MARK"])
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(output.status.code(), Some(0));
+
+    // 정리
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_subcommand_exit_codes() {
+    let binary_path = get_binary_path();
+    let hit = create_test_file(&test_data());
+    let miss = create_test_file(&[0u8; 4096]);
+    let (hit_path, miss_path) = (hit.to_str().unwrap(), miss.to_str().unwrap());
+
+    for (args, expected_code) in [
+        // diff: 같으면 0, 다르면 1
+        (vec!["diff", hit_path, hit_path], 0),
+        (vec!["diff", hit_path, miss_path], 1),
+        (vec!["diff", hit_path, miss_path, "--summary"], 1),
+        // 찾는 하위 명령: 찾은 것이 없으면 1
+        (vec!["strings", hit_path], 0),
+        (vec!["strings", miss_path], 1),
+        (vec!["report", hit_path, "-e", "MARK"], 0),
+        (vec!["report", miss_path, "-e", "MARK"], 1),
+        (vec!["entropy", miss_path, "--high", "7.5"], 1),
+        // 분석 하위 명령은 항상 0
+        (vec!["stats", miss_path], 0),
+        (vec!["entropy", miss_path], 0),
+        // 오류는 2
+        (vec!["diff", hit_path, "/nonexistent/file.bin"], 2),
+    ] {
+        let output = Command::new(&binary_path)
+            .args(&args)
+            .output()
+            .expect("Failed to execute command");
+        assert_eq!(
+            output.status.code(),
+            Some(expected_code),
+            "{:?}: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    // 정리
+    fs::remove_file(hit).ok();
+    fs::remove_file(miss).ok();
+}
+
+#[test]
+fn test_closed_stdout_is_not_an_error() {
+    let binary_path = get_binary_path();
+    let test_file = create_test_file(&b"MARK".repeat(20_000));
+    let path = test_file.to_str().unwrap();
+
+    // 읽는 쪽이 먼저 닫힌 파이프 (`| head`와 같은 상황)
+    for args in [
+        vec![path, "-e", "MARK", "-f", "json"],
+        vec![path, "-e", "MARK", "-f", "csv"],
+        vec![path, "-e", "MARK", "-o"],
+        vec![path, "-e", "(MA)RK", "--capture", "1"],
+        vec![path, path, "--multi", "-e", "MARK", "--global-limit", "30000"],
+        vec![path, path, "--multi", "-p", "-e", "MARK", "--global-limit", "30000"],
+    ] {
+        let mut child = Command::new(&binary_path)
+            .args(&args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("Failed to spawn command");
+        drop(child.stdout.take());
+        let output = child.wait_with_output().unwrap();
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert_eq!(output.status.code(), Some(0), "{:?}: {}", args, stderr);
+        assert!(!stderr.contains("panicked"), "{}", stderr);
+    }
+
+    // 정리
+    fs::remove_file(test_file).ok();
+}
@@ -0,0 +1,136 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("hxgrep");
+    path
+}
+
+fn create_test_file(content: &[u8]) -> PathBuf {
+    let temp_dir = std::env::temp_dir();
+    let file_path = temp_dir.join(format!("filename_test_{}.bin", uuid::Uuid::new_v4()));
+    let mut file = File::create(&file_path).unwrap();
+    file.write_all(content).unwrap();
+    file_path
+}
+
+fn test_data() -> Vec<u8> {
+    let mut data = vec![0u8; 4096];
+    data[100..104].copy_from_slice(b"MARK");
+    data[3000..3004].copy_from_slice(b"MARK");
+    data
+}
+
+fn match_lines(stdout: &str) -> Vec<&str> {
+    stdout.lines().filter(|l| l.contains("4D 41 52 4B")).collect()
+}
+
+#[test]
+fn test_filename_default_with_several_inputs() {
+    let binary_path = get_binary_path();
+    let first = create_test_file(&test_data());
+    let second = create_test_file(&test_data());
+
+    let output = Command::new(&binary_path)
+        .arg(&first)
+        .arg(&second)
+        .args(["-e", "MARK", "-w", "4", "--color", "never"])
+        .output()
+        .expect("Failed to execute hxgrep");
+    assert!(output.status.success());
+
+    // 입력이 여러 개면 모든 매칭 줄에 파일 이름이 붙음
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines = match_lines(&stdout);
+    assert_eq!(lines.len(), 4);
+    assert!(lines[0].starts_with(&format!("{}:", first.display())), "{}", lines[0]);
+    assert!(lines[3].starts_with(&format!("{}:", second.display())), "{}", lines[3]);
+
+    // --no-filename 으로 끌 수 있음
+    let output = Command::new(&binary_path)
+        .arg(&first)
+        .arg(&second)
+        .args(["-e", "MARK", "-w", "4", "--no-filename"])
+        .output()
+        .expect("Failed to execute hxgrep");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(match_lines(&stdout).iter().all(|l| !l.contains("filename_test_")));
+
+    // 정리
+    fs::remove_file(first).ok();
+    fs::remove_file(second).ok();
+}
+
+#[test]
+fn test_with_filename_single_input() {
+    let binary_path = get_binary_path();
+    let test_file = create_test_file(&test_data());
+    let prefix = format!("{}:", test_file.display());
+
+    for parallel in [false, true] {
+        let mut command = Command::new(&binary_path);
+        command
+            .arg(&test_file)
+            .args(["-e", "MARK", "-w", "4", "-H", "--color", "never"]);
+        if parallel {
+            command.args(["-p", "--chunk-size", "1024"]);
+        }
+        let output = command.output().expect("Failed to execute hxgrep");
+        assert!(output.status.success());
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let lines = match_lines(&stdout);
+        assert_eq!(lines.len(), 2, "parallel={}: {}", parallel, stdout);
+        assert!(lines.iter().all(|l| l.starts_with(&prefix)), "parallel={}: {}", parallel, stdout);
+    }
+
+    // 파일 이름은 색상 모드에서 별도 색(마젠타)으로 표시
+    let output = Command::new(&binary_path)
+        .arg(&test_file)
+        .args(["-e", "MARK", "-H", "--color", "always"])
+        .output()
+        .expect("Failed to execute hxgrep");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(&format!("\x1b[35m{}\x1b[0m:", test_file.display())), "{}", stdout);
+
+    // 정리
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_stdin_label() {
+    let binary_path = get_binary_path();
+
+    // stdin은 출력 형식과 관계없이 항상 "(standard input)"으로 표시
+    for (args, expected) in [
+        (vec!["-e", "MARK", "-H"], "(standard input):"),
+        (vec!["-e", "MARK", "-o", "-H"], "(standard input):"),
+        (vec!["-e", "MARK", "-c", "-H"], "(standard input):2"),
+        (vec!["-e", "MARK", "-l"], "(standard input)"),
+        (vec!["-e", "MARK", "-f", "json"], "\"file_path\": \"(standard input)\""),
+        (vec!["strings", "-f", "json"], "\"file_path\": \"(standard input)\""),
+    ] {
+        let mut command = Command::new(&binary_path);
+        match args.first() {
+            Some(&"strings") => command.args(&args[..1]).arg("-").args(&args[1..]),
+            _ => command.arg("-").args(&args),
+        };
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Failed to spawn command");
+        child.stdin.take().unwrap().write_all(&test_data()).unwrap();
+        let output = child.wait_with_output().unwrap();
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let labelled = stdout.lines().any(|line| line.trim().starts_with(expected));
+        assert!(labelled, "{:?}: {}", args, stdout);
+        assert!(!stdout.contains("\"-\"") && !stdout.starts_with("-:"), "{:?}: {}", args, stdout);
+    }
+}
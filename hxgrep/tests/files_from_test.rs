@@ -0,0 +1,168 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("hxgrep");
+    path
+}
+
+fn create_test_file(name: &str, content: &[u8]) -> PathBuf {
+    let temp_dir = std::env::temp_dir();
+    let file_path = temp_dir.join(format!("files_from_test_{}_{}", uuid::Uuid::new_v4(), name));
+    let mut file = File::create(&file_path).unwrap();
+    file.write_all(content).unwrap();
+    file_path
+}
+
+fn run_with_stdin(args: &[&str], stdin: &[u8]) -> std::process::Output {
+    let mut child = Command::new(get_binary_path())
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to spawn hxgrep");
+    child.stdin.take().unwrap().write_all(stdin).unwrap();
+    child.wait_with_output().expect("Failed to wait for hxgrep")
+}
+
+#[test]
+fn test_multiple_positional_inputs() {
+    let binary_path = get_binary_path();
+    let first = create_test_file("a.bin", b"\x00\x00MARK\x00");
+    let second = create_test_file("b.bin", b"MARK\x00MARK");
+
+    // 입력이 여러 개면 -m 없이도 다중 파일 모드
+    let output = Command::new(&binary_path)
+        .arg(&first)
+        .arg(&second)
+        .args(["-e", "MARK", "-c"])
+        .output()
+        .expect("Failed to execute hxgrep");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(&format!("{}:1", first.display())), "{}", stdout);
+    assert!(stdout.contains(&format!("{}:2", second.display())), "{}", stdout);
+
+    let output = Command::new(&binary_path)
+        .arg(&first)
+        .arg(&second)
+        .args(["-e", "MARK"])
+        .output()
+        .expect("Failed to execute hxgrep");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.matches("=== Processing:").count(), 2);
+    assert!(stdout.contains("Total matches/lines processed: 3"));
+
+    // 정리
+    fs::remove_file(first).ok();
+    fs::remove_file(second).ok();
+}
+
+#[test]
+fn test_files_from_stdin_null_separated() {
+    // 공백과 줄바꿈이 들어간 파일 이름도 -0 이면 그대로 전달됨
+    let first = create_test_file("with space.bin", b"MARK");
+    let second = create_test_file("with\nnewline.bin", b"\x00MARK");
+
+    let mut list = Vec::new();
+    for path in [&first, &second] {
+        list.extend_from_slice(path.to_string_lossy().as_bytes());
+        list.push(0);
+    }
+
+    let output = run_with_stdin(&["--files-from", "-", "-0", "-e", "MARK", "-l"], &list);
+
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(&first.display().to_string()), "{}", stdout);
+    assert!(stdout.contains(&second.display().to_string()), "{}", stdout);
+
+    // 정리
+    fs::remove_file(first).ok();
+    fs::remove_file(second).ok();
+}
+
+#[test]
+fn test_files_from_missing_entry_is_an_error() {
+    let binary_path = get_binary_path();
+    let data = create_test_file("data.bin", b"\x00\x00MARK");
+    let missing = std::env::temp_dir().join(format!("files_from_test_{}_missing", uuid::Uuid::new_v4()));
+    let list = create_test_file(
+        "list.txt",
+        format!("{}\n{}\n", data.display(), missing.display()).as_bytes(),
+    );
+
+    let output = Command::new(&binary_path)
+        .arg("--files-from")
+        .arg(&list)
+        .args(["-e", "MARK"])
+        .output()
+        .expect("Failed to execute hxgrep");
+
+    // 목록의 없는 파일도 위치 인자와 같이 처리: 나머지 입력을 검색한 뒤 종료 코드 2
+    assert_eq!(output.status.code(), Some(2));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Total matches/lines processed: 1"), "{}", stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(&format!("{}: No such file or directory", missing.display())),
+        "{}",
+        stderr
+    );
+
+    // 개수 모드도 동일
+    let output = Command::new(&binary_path)
+        .arg("--files-from")
+        .arg(&list)
+        .args(["-e", "MARK", "-c"])
+        .output()
+        .expect("Failed to execute hxgrep");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stdout).contains(&format!("{}:1", data.display())));
+
+    // 정리
+    fs::remove_file(data).ok();
+    fs::remove_file(list).ok();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_non_utf8_file_names() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let binary_path = get_binary_path();
+    let dir = std::env::temp_dir().join(format!("files_from_test_{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    // UTF-8이 아닌 파일 이름 (Latin-1 "café.bin")
+    let name = dir.join(OsStr::from_bytes(b"caf\xE9.bin"));
+    fs::write(&name, b"\x00MARK\x00MARK").unwrap();
+
+    // NUL 구분 목록으로 전달
+    let mut list = name.as_os_str().as_bytes().to_vec();
+    list.push(0);
+    let output = run_with_stdin(&["--files-from", "-", "-0", "-e", "MARK", "-c"], &list);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).ends_with(":2\n"));
+
+    // 디렉터리 탐색으로 찾은 경우
+    let output = Command::new(&binary_path)
+        .arg(&dir)
+        .args(["-r", "-e", "MARK"])
+        .output()
+        .expect("Failed to execute hxgrep");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Total matches/lines processed: 2"), "{}", stdout);
+
+    // 정리
+    fs::remove_dir_all(&dir).ok();
+}
@@ -0,0 +1,137 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("hxgrep");
+    path
+}
+
+fn create_test_file(content: &[u8]) -> PathBuf {
+    let temp_dir = std::env::temp_dir();
+    let file_path = temp_dir.join(format!("hash_test_{}.bin", uuid::Uuid::new_v4()));
+    let mut file = File::create(&file_path).unwrap();
+    file.write_all(content).unwrap();
+    file_path
+}
+
+/// 청크 경계를 넘는 위치에 매칭이 있는 3MB 데이터
+fn test_data() -> Vec<u8> {
+    let mut data: Vec<u8> = (0..3 * 1024 * 1024u32).map(|i| (i * 13 % 251) as u8).collect();
+    for pos in [100, 1_048_570, 2_500_000] {
+        data[pos..pos + 4].copy_from_slice(b"HASH");
+    }
+    data
+}
+
+#[test]
+fn test_hash_whole_input_in_every_mode() {
+    let binary_path = get_binary_path();
+    let data = test_data();
+    let test_file = create_test_file(&data);
+    let md5_line = format!("MD5 ({}) = {}", test_file.display(), md5_hex(&data));
+    let sha1_line = format!("SHA1 ({}) = {}", test_file.display(), sha1_hex(&data));
+
+    // 순차/병렬 검색, 시작 위치, 조기 종료, hex 덤프, JSON 출력 모두 입력 전체의 해시
+    let modes: [&[&str]; 6] = [
+        &["-e", "HASH"],
+        &["-e", "HASH", "-p", "--chunk-size", "100000"],
+        &["-e", "HASH", "-s", "2000000"],
+        &["-e", "HASH", "-n", "1"],
+        &["-n", "3"],
+        &["-e", "HASH", "-f", "json", "-c"],
+    ];
+    for args in modes {
+        let output = Command::new(&binary_path)
+            .arg(&test_file)
+            .args(args)
+            .args(["--hash", "md5,sha1"])
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success(), "{:?}", args);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains(&md5_line), "{:?}: {}", args, stderr);
+        assert!(stderr.contains(&sha1_line), "{:?}: {}", args, stderr);
+        assert!(!String::from_utf8_lossy(&output.stdout).contains(&md5_hex(&data)));
+    }
+
+    // 다중 파일 모드는 지원하지 않음
+    let output = Command::new(&binary_path)
+        .arg(&test_file)
+        .arg(&test_file)
+        .args(["-e", "HASH", "--hash", "md5"])
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(output.status.code(), Some(2));
+
+    // 정리
+    fs::remove_file(&test_file).ok();
+}
+
+#[test]
+fn test_hash_matches_attached_to_records() {
+    let binary_path = get_binary_path();
+    let data = test_data();
+    let test_file = create_test_file(&data);
+
+    let output = Command::new(&binary_path)
+        .arg(&test_file)
+        .args(["-e", "HASH", "-f", "json", "--hash", "sha1", "--hash-matches"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+    let matches: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let matches = matches.as_array().unwrap();
+    assert_eq!(matches.len(), 3);
+    assert_eq!(matches[0]["hashes"]["length"], 4);
+    assert_eq!(matches[0]["hashes"]["sha1"], sha1_hex(b"HASH"));
+    assert!(matches[0]["hashes"].get("md5").is_none());
+
+    // 매칭 시작부터 200KB 영역 (청크 겹침보다 긴 영역)
+    let output = Command::new(&binary_path)
+        .arg(&test_file)
+        .args(["-e", "HASH", "-f", "json", "--hash", "md5", "--hash-matches=200000"])
+        .args(["--chunk-size", "100000"])
+        .output()
+        .expect("Failed to execute command");
+    let matches: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(matches[1]["offset"], 1_048_570);
+    assert_eq!(
+        matches[1]["hashes"]["md5"],
+        md5_hex(&data[1_048_570..1_248_570])
+    );
+
+    // hex 출력은 매칭 아래에 해시 줄을 표시
+    let output = Command::new(&binary_path)
+        .arg(&test_file)
+        .args(["-e", "HASH", "-n", "1", "--hash", "md5", "--hash-matches"])
+        .output()
+        .expect("Failed to execute command");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(&format!("md5      {}", md5_hex(b"HASH"))), "{}", stdout);
+
+    // --hash 없이 --hash-matches만 지정하면 오류
+    let output = Command::new(&binary_path)
+        .arg(&test_file)
+        .args(["-e", "HASH", "--hash-matches"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!output.status.success());
+
+    // 정리
+    fs::remove_file(&test_file).ok();
+}
+
+fn sha1_hex(data: &[u8]) -> String {
+    use sha1::Digest;
+    hex::encode(sha1::Sha1::digest(data))
+}
+
+fn md5_hex(data: &[u8]) -> String {
+    use md5::Digest;
+    hex::encode(md5::Md5::digest(data))
+}
@@ -0,0 +1,144 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("hxgrep");
+    path
+}
+
+fn create_test_file(content: &[u8]) -> PathBuf {
+    let temp_dir = std::env::temp_dir();
+    let file_path = temp_dir.join(format!("index_test_{}.bin", uuid::Uuid::new_v4()));
+    let mut file = File::create(&file_path).unwrap();
+    file.write_all(content).unwrap();
+    file_path
+}
+
+/// 블록 경계에 걸친 매칭을 포함해 여러 블록에 흩어진 1MB 데이터
+fn test_data() -> Vec<u8> {
+    let mut data: Vec<u8> = (0..1024 * 1024).map(|i| (i * 7 % 251) as u8).collect();
+    for pos in [10, 4094, 70_000, 500_000, 1_048_000] {
+        data[pos..pos + 9].copy_from_slice(b"KEY=value");
+    }
+    // 같은 n-gram이 있지만 리터럴 전체는 없는 블록
+    data[800_000..800_004].copy_from_slice(b"KEY=");
+    data
+}
+
+fn build_index(file: &Path) -> PathBuf {
+    let index = file.with_extension("hxidx");
+    let output = Command::new(get_binary_path())
+        .args(["index", "--block-size", "4096", "-o"])
+        .arg(&index)
+        .arg(file)
+        .output()
+        .expect("Failed to execute index");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    index
+}
+
+fn search(file: &Path, pattern: &str, args: &[&str]) -> Output {
+    Command::new(get_binary_path())
+        .arg(file)
+        .args(["-e", pattern, "-o"])
+        .args(args)
+        .output()
+        .expect("Failed to execute search")
+}
+
+#[test]
+fn test_indexed_search_matches_full_scan() {
+    let test_file = create_test_file(&test_data());
+    let index = build_index(&test_file);
+    let index_arg = index.to_str().unwrap();
+
+    for pattern in ["KEY=value", "(?i)key=VAL[a-z]+", r"\x00?KEY=(value|other)", "K.Y"] {
+        let full = search(&test_file, pattern, &[]);
+        let indexed = search(&test_file, pattern, &["--index", index_arg]);
+        assert!(indexed.status.success(), "{}", pattern);
+        assert_eq!(
+            String::from_utf8_lossy(&indexed.stdout),
+            String::from_utf8_lossy(&full.stdout),
+            "{}",
+            pattern
+        );
+    }
+
+    // 리터럴이 없는 패턴은 경고 후 전체 검색
+    let fallback = search(&test_file, "K.Y", &["--index", index_arg]);
+    assert!(String::from_utf8_lossy(&fallback.stderr).contains("scanning the whole input"));
+
+    // -c와 -n도 색인 검색에 적용
+    let count = search(&test_file, "KEY=value", &["--index", index_arg, "-c"]);
+    assert_eq!(String::from_utf8_lossy(&count.stdout).trim(), "5");
+    let limited = search(&test_file, "KEY=value", &["--index", index_arg, "-n", "2"]);
+    assert_eq!(String::from_utf8_lossy(&limited.stdout).lines().count(), 2);
+
+    // 정리
+    fs::remove_file(&test_file).ok();
+    fs::remove_file(&index).ok();
+}
+
+#[test]
+fn test_indexed_hex_output_matches_full_scan() {
+    let test_file = create_test_file(&test_data());
+    let index = build_index(&test_file);
+
+    // -o 없는 hex 출력: 오프셋 폭과 -w 창이 스트리밍 검색과 같아야 함
+    for args in [&[][..], &["-w", "4"][..], &["-H", "--hideoffset"][..]] {
+        let run = |extra: &[&str]| {
+            Command::new(get_binary_path())
+                .arg(&test_file)
+                .args(["-e", "KEY=val", "--color", "never"])
+                .args(args)
+                .args(extra)
+                .output()
+                .expect("Failed to execute search")
+        };
+        let full = run(&[]);
+        let indexed = run(&["--index", index.to_str().unwrap()]);
+        assert!(indexed.status.success(), "{}", String::from_utf8_lossy(&indexed.stderr));
+        assert!(!full.stdout.is_empty());
+        assert_eq!(indexed.stdout, full.stdout, "{:?}", args);
+    }
+
+    // 읽는 쪽이 먼저 닫힌 파이프도 오류가 아님
+    let mut child = Command::new(get_binary_path())
+        .arg(&test_file)
+        .args(["-e", "KEY=value", "--index", index.to_str().unwrap()])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to spawn search");
+    drop(child.stdout.take());
+    let output = child.wait_with_output().unwrap();
+    assert_eq!(output.status.code(), Some(0), "{}", String::from_utf8_lossy(&output.stderr));
+
+    // 정리
+    fs::remove_file(&test_file).ok();
+    fs::remove_file(&index).ok();
+}
+
+#[test]
+fn test_index_rejects_changed_input() {
+    let test_file = create_test_file(&test_data());
+    let index = build_index(&test_file);
+
+    let mut data = test_data();
+    data.extend_from_slice(b"KEY=value");
+    fs::write(&test_file, data).unwrap();
+
+    let output = search(&test_file, "KEY=value", &["--index", index.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("out of date"));
+
+    // 정리
+    fs::remove_file(&test_file).ok();
+    fs::remove_file(&index).ok();
+}
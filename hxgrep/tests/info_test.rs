@@ -0,0 +1,138 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("hxgrep");
+    path
+}
+
+fn create_test_file(content: &[u8], extension: &str) -> PathBuf {
+    let temp_dir = std::env::temp_dir();
+    let file_path = temp_dir.join(format!("info_test_{}.{}", uuid::Uuid::new_v4(), extension));
+    let mut file = File::create(&file_path).unwrap();
+    file.write_all(content).unwrap();
+    file_path
+}
+
+/// header, volume, digest 섹션만 있는 E01 세그먼트
+fn ewf_segment() -> Vec<u8> {
+    let header = "1\nmain\nc\tn\ta\te\tt\tav\tov\tm\tu\tp\tr\n\
+                  2024-017\tHDD-1\tSuspect laptop\tLee\tseized\t6.19\tWin 7\t2024 3 5 9 30 0\t2024 3 5 9 30 0\t0\tf\n\n";
+    let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(header.as_bytes()).unwrap();
+    let header = encoder.finish().unwrap();
+
+    let mut volume = vec![0u8; 1052];
+    volume[0] = 0x01;
+    volume[4..8].copy_from_slice(&2u32.to_le_bytes());
+    volume[8..12].copy_from_slice(&64u32.to_le_bytes());
+    volume[12..16].copy_from_slice(&512u32.to_le_bytes());
+    volume[16..24].copy_from_slice(&128u64.to_le_bytes());
+    volume[52] = 1;
+
+    let mut digest = vec![0x5au8; 16];
+    digest.extend_from_slice(&[0xa5u8; 20]);
+    digest.extend_from_slice(&[0u8; 44]);
+
+    let mut file = b"EVF\x09\x0d\x0a\xff\x00\x01\x01\x00\x00\x00".to_vec();
+    for (kind, data) in [("header", header), ("volume", volume), ("digest", digest), ("done", vec![])] {
+        let offset = file.len() as u64;
+        let size = 76 + data.len() as u64;
+        let next = if kind == "done" { offset } else { offset + size };
+        let mut descriptor = vec![0u8; 76];
+        descriptor[..kind.len()].copy_from_slice(kind.as_bytes());
+        descriptor[16..24].copy_from_slice(&next.to_le_bytes());
+        descriptor[24..32].copy_from_slice(&size.to_le_bytes());
+        file.extend_from_slice(&descriptor);
+        file.extend_from_slice(&data);
+    }
+    file
+}
+
+#[test]
+fn test_info_ewf_text_and_json() {
+    let binary_path = get_binary_path();
+    let image = create_test_file(&ewf_segment(), "E01");
+
+    let output = Command::new(&binary_path)
+        .arg("info")
+        .arg(&image)
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Format:             EWF (E01)"));
+    assert!(stdout.contains("Media size:         65536 bytes"));
+    assert!(stdout.contains("Chunk size:         32768 bytes (64 sectors)"));
+    assert!(stdout.contains("Compression:        fast"));
+    assert!(stdout.contains("Case number:        2024-017"));
+    assert!(stdout.contains("Examiner:           Lee"));
+    assert!(stdout.contains("Acquired:           2024-03-05 09:30:00"));
+    assert!(stdout.contains(&format!("SHA1:               {}", "a5".repeat(20))));
+
+    let output = Command::new(&binary_path)
+        .arg("info")
+        .arg(&image)
+        .args(["-f", "json"])
+        .output()
+        .expect("Failed to execute command");
+    let info: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(info["format"], "ewf");
+    assert_eq!(info["segments"].as_array().unwrap().len(), 1);
+    assert_eq!(info["media"]["sector_size"], 512);
+    assert_eq!(info["case"]["evidence_number"], "HDD-1");
+    assert_eq!(info["stored_hashes"]["md5"], "5a".repeat(16));
+
+    // 정리
+    fs::remove_file(&image).ok();
+}
+
+#[test]
+fn test_info_vmdk_descriptor_and_invalid_input() {
+    let binary_path = get_binary_path();
+    let descriptor = "# Disk DescriptorFile\nversion=1\nCID=7a1b2c3d\nparentCID=ffffffff\n\
+                      createType=\"twoGbMaxExtentFlat\"\n\n# Extent description\n\
+                      RW 4194304 FLAT \"vm-f001.vmdk\" 0\nRW 2097152 FLAT \"vm-f002.vmdk\" 0\n\n\
+                      ddb.adapterType = \"lsilogic\"\nddb.geometry.heads = \"255\"\n";
+    let image = create_test_file(descriptor.as_bytes(), "vmdk");
+
+    let output = Command::new(&binary_path)
+        .arg("info")
+        .arg(&image)
+        .args(["-f", "json"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let info: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(info["format"], "vmdk");
+    assert_eq!(info["create_type"], "twoGbMaxExtentFlat");
+    assert_eq!(info["extents"][1]["file"], "vm-f002.vmdk");
+    assert_eq!(info["disk_database"]["geometry.heads"], "255");
+
+    let output = Command::new(&binary_path)
+        .arg("info")
+        .arg(&image)
+        .output()
+        .expect("Failed to execute command");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Media size:         3221225472 bytes"));
+    assert!(stdout.contains("ddb.adapterType:    lsilogic"));
+
+    // 포렌식 이미지가 아닌 파일은 오류
+    let raw = create_test_file(b"plain data", "bin");
+    let output = Command::new(&binary_path)
+        .arg("info")
+        .arg(&raw)
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(output.status.code(), Some(2));
+
+    // 정리
+    fs::remove_file(&image).ok();
+    fs::remove_file(&raw).ok();
+}
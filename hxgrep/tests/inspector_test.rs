@@ -0,0 +1,94 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("hxgrep");
+    path
+}
+
+fn create_test_file(content: &[u8]) -> PathBuf {
+    let temp_dir = std::env::temp_dir();
+    let file_path = temp_dir.join(format!("inspector_test_{}.bin", uuid::Uuid::new_v4()));
+    let mut file = File::create(&file_path).unwrap();
+    file.write_all(content).unwrap();
+    file_path
+}
+
+#[test]
+fn test_inspect_block_under_hex_match() {
+    let binary_path = get_binary_path();
+    // 오프셋 0x20에 Unix 시간 1000000000 (2001-09-09 01:46:40 UTC)
+    let mut test_data = vec![0x00u8; 32];
+    test_data.extend_from_slice(&1_000_000_000u32.to_le_bytes());
+    test_data.extend(vec![0x00u8; 28]);
+    let test_file = create_test_file(&test_data);
+
+    let output = Command::new(&binary_path)
+        .arg(&test_file)
+        .arg("-e")
+        .arg("(?-u)\\x00\\xCA\\x9A\\x3B")
+        .arg("--inspect")
+        .arg("u8,unix")
+        .arg("--color")
+        .arg("never")
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 3, "{}", stdout);
+    assert!(lines[0].starts_with("00000000020h : 00 CA 9A 3B"), "{}", lines[0]);
+    assert_eq!(lines[1].trim(), "u8       0");
+    assert!(lines[2].trim().starts_with("unix     le 2001-09-09 01:46:40 UTC"), "{}", lines[2]);
+
+    // 정리
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_inspect_field_in_json_records() {
+    let binary_path = get_binary_path();
+    let mut test_data = b"HDR!".to_vec();
+    test_data.extend_from_slice(&[0x33, 0x22, 0x11, 0x00, 0x55, 0x44, 0x77, 0x66]);
+    test_data.extend_from_slice(&[0x88, 0x99, 0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF]);
+    let test_file = create_test_file(&test_data);
+
+    for parallel in [false, true] {
+        let mut command = Command::new(&binary_path);
+        command
+            .arg(&test_file)
+            .arg("-e")
+            .arg("(?-u)\\x33\\x22")
+            .arg("--inspect")
+            .arg("guid,u16")
+            .arg("-f")
+            .arg("json");
+        if parallel {
+            command.arg("-p").arg("--chunk-size").arg("4");
+        }
+
+        let output = command.output().expect("Failed to execute command");
+        assert!(output.status.success());
+        let records: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let records = records.as_array().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["offset"], 4);
+        assert_eq!(records[0]["hex_data"], "33 22");
+
+        let inspection = records[0]["inspection"].as_array().unwrap();
+        assert_eq!(inspection[0]["type"], "guid");
+        assert_eq!(inspection[0]["value"], "{00112233-4455-6677-8899-AABBCCDDEEFF}");
+        assert_eq!(inspection[1]["type"], "u16");
+        assert_eq!(inspection[1]["endian"], "le");
+        assert_eq!(inspection[1]["value"], "8755");
+    }
+
+    // 정리
+    fs::remove_file(test_file).ok();
+}
@@ -267,6 +267,44 @@ fn test_large_file_handling() {
     fs::remove_file(test_file).ok();
 }
 
+#[test]
+fn test_aligned_search_with_lba() {
+    let binary_path = get_binary_path();
+    let mut test_data = vec![0u8; 4096];
+    // 정렬되지 않은 위치와 1024 경계에 각각 시그니처 삽입
+    test_data[100..105].copy_from_slice(b"FILE0");
+    test_data[1024..1029].copy_from_slice(b"FILE0");
+    test_data[3072..3077].copy_from_slice(b"FILE0");
+
+    let test_file = create_test_file(&test_data);
+
+    let output = Command::new(&binary_path)
+        .arg(&test_file)
+        .arg("-e")
+        .arg("FILE0")
+        .arg("--align")
+        .arg("1024")
+        .arg("--cluster-size")
+        .arg("2048")
+        .arg("--color")
+        .arg("never")
+        .output()
+        .expect("Failed to execute command");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+
+    // 1024 경계의 매칭만 LBA/클러스터 정보와 함께 출력되어야 함
+    assert_eq!(lines.len(), 2, "stdout: {}", stdout);
+    assert!(lines[0].contains("400h :"));
+    assert!(lines[0].contains("[LBA 2, cluster 0]"));
+    assert!(lines[1].contains("C00h :"));
+    assert!(lines[1].contains("[LBA 6, cluster 1]"));
+
+    // 정리
+    fs::remove_file(test_file).ok();
+}
+
 #[test]
 fn test_empty_file() {
     let binary_path = get_binary_path();
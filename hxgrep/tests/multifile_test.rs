@@ -180,8 +180,11 @@ fn test_multi_file_with_limit() {
         "Should show file processing messages"
     );
 
-    // Note: Global limit functionality needs to be properly implemented in the multifile processor
-    // For now, just verify the command runs successfully
+    // 전역 제한은 파일 경계를 넘어 적용됨
+    assert!(
+        stdout.contains("Total matches/lines processed: 2"),
+        "Global limit should cap the total number of matches"
+    );
 
     // 정리 (지연 추가)
     std::thread::sleep(std::time::Duration::from_millis(100));
@@ -290,9 +293,11 @@ fn test_multi_file_no_matches() {
         .output()
         .expect("Failed to execute multi-file no matches");
 
-    assert!(
-        output.status.success(),
-        "Multi-file no matches should still succeed"
+    // grep과 같이 매칭이 없으면 종료 코드 1
+    assert_eq!(
+        output.status.code(),
+        Some(1),
+        "Multi-file no matches should exit with status 1"
     );
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -332,10 +337,11 @@ fn test_multi_file_nonexistent_pattern() {
         .output()
         .expect("Failed to execute multi-file nonexistent pattern");
 
-    // Should succeed but find no files
-    assert!(
-        output.status.success(),
-        "Multi-file with nonexistent pattern should succeed"
+    // No files means no matches: exit status 1, not an error
+    assert_eq!(
+        output.status.code(),
+        Some(1),
+        "Multi-file with nonexistent pattern should exit with status 1"
     );
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -346,3 +352,99 @@ fn test_multi_file_nonexistent_pattern() {
         "Should show 0 total processed"
     );
 }
+
+fn create_files_with_repeated_pattern(prefix: &str, count: usize) -> (String, Vec<PathBuf>) {
+    let temp_dir = std::env::temp_dir();
+    let test_id = uuid::Uuid::new_v4();
+    let mut files = Vec::new();
+
+    // 각 파일에 패턴을 두 번씩 삽입
+    for i in 0..count {
+        let file_path = temp_dir.join(format!("{}_{}_{}.bin", prefix, test_id, i));
+        let mut test_data = vec![0xFF; 512];
+        test_data[64..68].copy_from_slice(b"\x01\x02\x03\x04");
+        test_data[256..260].copy_from_slice(b"\x01\x02\x03\x04");
+
+        let mut file = File::create(&file_path).unwrap();
+        file.write_all(&test_data).unwrap();
+        files.push(file_path);
+    }
+
+    let glob_pattern = temp_dir.join(format!("{}_{}_*.bin", prefix, test_id));
+    (glob_pattern.to_string_lossy().into_owned(), files)
+}
+
+#[test]
+fn test_multi_file_global_limit_across_files() {
+    let binary_path = get_binary_path();
+    let (glob_pattern, files) = create_files_with_repeated_pattern("multifile_glimit", 3);
+
+    let output = Command::new(&binary_path)
+        .arg(&glob_pattern)
+        .arg("-e")
+        .arg("\\x01\\x02\\x03\\x04")
+        .arg("--multi")
+        .arg("--global-limit")
+        .arg("3")
+        .output()
+        .expect("Failed to execute multi-file with global limit");
+
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    // 첫 파일에서 2개, 두 번째 파일에서 1개만 출력되고 세 번째 파일은 건너뜀
+    assert_eq!(stdout.matches("01 02 03 04").count(), 3, "{}", stdout);
+    assert_eq!(stdout.matches("=== Processing:").count(), 2, "{}", stdout);
+    assert!(stdout.contains("=== Global limit of 3 reached ==="));
+    assert!(stdout.contains("Total matches/lines processed: 3"));
+
+    // 정리
+    for file in files {
+        fs::remove_file(file).ok();
+    }
+}
+
+#[test]
+fn test_multi_file_summary_counts() {
+    let binary_path = get_binary_path();
+    let (glob_pattern, files) = create_files_with_repeated_pattern("multifile_summary", 2);
+
+    let output = Command::new(&binary_path)
+        .arg(&glob_pattern)
+        .arg("-e")
+        .arg("\\x01\\x02\\x03\\x04")
+        .arg("--multi")
+        .output()
+        .expect("Failed to execute multi-file summary");
+
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    // 파일별 매치 수와 스캔한 바이트 수가 요약에 표시되어야 함
+    assert!(stdout.contains("=== Summary ==="));
+    for file in &files {
+        let line = format!("{}: 2 matches, 512 bytes scanned", file.display());
+        assert!(stdout.contains(&line), "missing '{}' in {}", line, stdout);
+    }
+    assert!(stdout.contains("Total matches/lines processed: 4"));
+    assert!(stdout.contains("Total bytes scanned: 1024"));
+
+    // 헥스 덤프 모드에서는 출력한 줄 수를 집계
+    let output = Command::new(&binary_path)
+        .arg(&glob_pattern)
+        .arg("--multi")
+        .arg("-n")
+        .arg("5")
+        .output()
+        .expect("Failed to execute multi-file hex dump");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Total matches/lines processed: 10"), "{}", stdout);
+
+    // 정리
+    for file in files {
+        fs::remove_file(file).ok();
+    }
+}
@@ -0,0 +1,102 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("hxgrep");
+    path
+}
+
+fn create_test_file(content: &[u8]) -> PathBuf {
+    let temp_dir = std::env::temp_dir();
+    let file_path = temp_dir.join(format!("output_style_test_{}.bin", uuid::Uuid::new_v4()));
+    let mut file = File::create(&file_path).unwrap();
+    file.write_all(content).unwrap();
+    file_path
+}
+
+#[test]
+fn test_decimal_offsets_and_little_endian_words() {
+    let binary_path = get_binary_path();
+    let test_data: Vec<u8> = (0u8..=0x1F).collect();
+    let test_file = create_test_file(&test_data);
+
+    let output = Command::new(&binary_path)
+        .arg(&test_file)
+        .arg("--offset-radix")
+        .arg("dec")
+        .arg("-g")
+        .arg("4")
+        .arg("--endian")
+        .arg("little")
+        .arg("--lowercase")
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0], "00 : 03020100 07060504 0b0a0908 0f0e0d0c");
+    assert_eq!(lines[1], "16 : 13121110 17161514 1b1a1918 1f1e1d1c");
+
+    // 정리
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_group_style_applies_to_search_and_parallel() {
+    let binary_path = get_binary_path();
+    let mut test_data = vec![0x00u8; 64];
+    test_data.extend_from_slice(&[0xDE, 0xAD, 0xBE, 0xEF]);
+    test_data.extend(vec![0x00u8; 60]);
+    let test_file = create_test_file(&test_data);
+
+    for parallel in [false, true] {
+        let mut command = Command::new(&binary_path);
+        command
+            .arg(&test_file)
+            .arg("-e")
+            .arg("(?-u)\\xDE\\xAD")
+            .arg("-g")
+            .arg("2")
+            .arg("--offset-style")
+            .arg("prefix");
+        if parallel {
+            command.arg("-p").arg("--chunk-size").arg("32");
+        }
+
+        let output = command.output().expect("Failed to execute command");
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let first = stdout.lines().next().unwrap();
+        assert!(first.starts_with("0x"), "parallel={}: {}", parallel, first);
+        assert!(first.contains("40 : DEAD BEEF 0000"), "parallel={}: {}", parallel, first);
+    }
+
+    // 정리
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_invalid_group_size() {
+    let binary_path = get_binary_path();
+    let test_file = create_test_file(&[0u8; 16]);
+
+    let output = Command::new(&binary_path)
+        .arg(&test_file)
+        .arg("-g")
+        .arg("3")
+        .output()
+        .expect("Failed to execute command");
+
+    // 1, 2, 4, 8 이외의 묶음 크기는 거부되어야 함
+    assert!(!output.status.success());
+
+    // 정리
+    fs::remove_file(test_file).ok();
+}
@@ -0,0 +1,152 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("hxgrep");
+    path
+}
+
+fn create_test_file(content: &[u8]) -> PathBuf {
+    let temp_dir = std::env::temp_dir();
+    let file_path = temp_dir.join(format!("progress_test_{}.bin", uuid::Uuid::new_v4()));
+    let mut file = File::create(&file_path).unwrap();
+    file.write_all(content).unwrap();
+    file_path
+}
+
+fn test_data() -> Vec<u8> {
+    let mut data = vec![0u8; 256 * 1024];
+    data[1000..1004].copy_from_slice(b"MARK");
+    data[200_000..200_004].copy_from_slice(b"MARK");
+    data
+}
+
+#[test]
+fn test_progress_suppressed_when_stderr_is_not_a_tty() {
+    let binary_path = get_binary_path();
+    let first = create_test_file(&test_data());
+    let second = create_test_file(&test_data());
+
+    // 단일 파일, 병렬 처리, 다중 파일, 개수 모드 모두 결과는 --progress 유무와 동일해야 함
+    let runs: Vec<Vec<&str>> = vec![
+        vec!["-e", "MARK"],
+        vec!["-e", "MARK", "-p", "--chunk-size", "65536"],
+        vec!["-e", "MARK", "-c"],
+    ];
+    for args in runs {
+        for inputs in [vec![&first], vec![&first, &second]] {
+            let plain = Command::new(&binary_path)
+                .args(&inputs)
+                .args(&args)
+                .output()
+                .expect("Failed to execute search");
+            let with_progress = Command::new(&binary_path)
+                .args(&inputs)
+                .args(&args)
+                .arg("--progress")
+                .output()
+                .expect("Failed to execute search with --progress");
+
+            assert!(with_progress.status.success());
+            assert_eq!(plain.stdout, with_progress.stdout, "{:?}", args);
+            // stderr가 파이프이므로 진행률 막대가 출력되지 않음
+            let stderr = String::from_utf8_lossy(&with_progress.stderr);
+            assert!(
+                !stderr.contains('\r') && !stderr.contains("ETA"),
+                "{}",
+                stderr
+            );
+        }
+    }
+
+    // 정리
+    fs::remove_file(&first).ok();
+    fs::remove_file(&second).ok();
+}
+
+fn json_events(stderr: &[u8]) -> Vec<serde_json::Value> {
+    String::from_utf8_lossy(stderr)
+        .lines()
+        .filter(|line| line.starts_with('{'))
+        .map(|line| serde_json::from_str(line).expect("progress event is not JSON"))
+        .collect()
+}
+
+#[test]
+fn test_progress_json_events() {
+    let binary_path = get_binary_path();
+    let first = create_test_file(&test_data());
+    let second = create_test_file(&test_data());
+
+    // stderr가 파이프여도 JSON 이벤트는 출력됨
+    let output = Command::new(&binary_path)
+        .arg(&first)
+        .arg(&second)
+        .args(["-e", "MARK", "--progress-format", "json"])
+        .output()
+        .expect("Failed to execute search with JSON progress");
+    assert!(output.status.success());
+
+    let events = json_events(&output.stderr);
+    assert_eq!(events.first().unwrap()["event"], "start");
+    let finish = events.last().unwrap();
+    assert_eq!(finish["event"], "finish");
+    assert_eq!(finish["bytes_scanned"], 2 * 256 * 1024);
+    assert_eq!(finish["total_bytes"], 2 * 256 * 1024);
+    assert_eq!(finish["matches"], 4);
+    assert_eq!(finish["files_total"], 2);
+    assert_eq!(finish["current_file"], second.display().to_string());
+
+    // 오류로 끝나면 error 이벤트에 메시지 포함
+    let missing = std::env::temp_dir().join(format!("progress_test_{}.bin", uuid::Uuid::new_v4()));
+    let output = Command::new(&binary_path)
+        .arg(&missing)
+        .args(["-e", "MARK", "--progress-format", "json"])
+        .output()
+        .expect("Failed to execute search");
+    assert_eq!(output.status.code(), Some(2));
+
+    let events = json_events(&output.stderr);
+    let error = events.last().unwrap();
+    assert_eq!(error["event"], "error");
+    assert!(error["message"].as_str().unwrap().contains("No such file"));
+
+    // 정리
+    fs::remove_file(&first).ok();
+    fs::remove_file(&second).ok();
+}
+
+#[test]
+fn test_progress_json_for_subcommands() {
+    let binary_path = get_binary_path();
+    let test_file = create_test_file(&test_data());
+    let path = test_file.to_str().unwrap();
+
+    // 진행률 옵션은 하위 명령 뒤에도 쓸 수 있고, 전체 크기가 finish 이벤트에 기록됨
+    for args in [
+        vec!["strings", path, "--progress-format", "json"],
+        vec!["stats", path, "--progress-format", "json"],
+        vec!["entropy", path, "--progress-format", "json"],
+        vec!["entropy", path, "--step", "1024", "-p", "--progress-format", "json"],
+        vec!["strings", path, "-p", "--chunk-size", "65536", "--progress-format", "json"],
+    ] {
+        let output = Command::new(&binary_path)
+            .args(&args)
+            .output()
+            .expect("Failed to execute subcommand");
+        assert_eq!(output.status.code(), Some(0), "{:?}", args);
+        let events = json_events(&output.stderr);
+        let finish = events.last().expect("no progress events");
+        assert_eq!(finish["event"], "finish", "{:?}", args);
+        assert_eq!(finish["total_bytes"], 256 * 1024, "{:?}", args);
+        assert_eq!(finish["bytes_scanned"], 256 * 1024, "{:?}", args);
+    }
+
+    // 정리
+    fs::remove_file(&test_file).ok();
+}
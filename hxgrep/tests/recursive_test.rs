@@ -0,0 +1,135 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("hxgrep");
+    path
+}
+
+/// 다음 구조의 테스트 디렉터리 생성:
+///   top.bin, notes.txt, sub/mid.bin (큰 파일), sub/deep/low.bin, .gitignore (sub/deep/)
+fn create_test_tree() -> PathBuf {
+    let root = std::env::temp_dir().join(format!("recursive_test_{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(root.join("sub/deep")).unwrap();
+
+    let with_marker = |size: usize| {
+        let mut data = vec![0u8; size];
+        data[16..20].copy_from_slice(b"MARK");
+        data
+    };
+    fs::write(root.join("top.bin"), with_marker(64)).unwrap();
+    fs::write(root.join("notes.txt"), with_marker(64)).unwrap();
+    fs::write(root.join("sub/mid.bin"), with_marker(4096)).unwrap();
+    fs::write(root.join("sub/deep/low.bin"), with_marker(64)).unwrap();
+    fs::write(root.join(".gitignore"), "deep/\n").unwrap();
+    root
+}
+
+fn run_count(root: &Path, extra: &[&str]) -> String {
+    let output = Command::new(get_binary_path())
+        .arg(root)
+        .args(["-r", "-e", "MARK", "-c"])
+        .args(extra)
+        .output()
+        .expect("Failed to execute recursive search");
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn searched_files(stdout: &str) -> Vec<String> {
+    stdout
+        .lines()
+        .filter_map(|line| line.rsplit_once(':'))
+        .map(|(path, _)| {
+            Path::new(path)
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect()
+}
+
+#[test]
+fn test_recursive_walks_directories() {
+    let root = create_test_tree();
+
+    let output = Command::new(get_binary_path())
+        .arg(&root)
+        .args(["-r", "-e", "MARK"])
+        .output()
+        .expect("Failed to execute recursive search");
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // .gitignore 포함 5개 파일, 매칭은 4개
+    assert_eq!(stdout.matches("=== Processing:").count(), 5, "{}", stdout);
+    assert!(stdout.contains("Total matches/lines processed: 4"));
+
+    // -r 없이 디렉터리를 주면 경고 후 건너뜀
+    let output = Command::new(get_binary_path())
+        .arg(&root)
+        .args(["-m", "-e", "MARK"])
+        .output()
+        .expect("Failed to execute multi-file search");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("is a directory"));
+
+    // 정리
+    fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn test_recursive_filters() {
+    let root = create_test_tree();
+
+    let include = run_count(&root, &["--include", "*.bin"]);
+    assert_eq!(searched_files(&include), vec!["low.bin", "mid.bin", "top.bin"]);
+
+    let exclude = run_count(&root, &["--exclude", "sub"]);
+    assert_eq!(searched_files(&exclude), vec![".gitignore", "notes.txt", "top.bin"]);
+
+    let size = run_count(&root, &["--min-size", "1000"]);
+    assert_eq!(searched_files(&size), vec!["mid.bin"]);
+
+    let depth = run_count(&root, &["--max-depth", "1"]);
+    assert_eq!(searched_files(&depth), vec![".gitignore", "notes.txt", "top.bin"]);
+
+    let ignored = run_count(&root, &["--gitignore", "--include", "*.bin"]);
+    assert_eq!(searched_files(&ignored), vec!["mid.bin", "top.bin"]);
+
+    // 정리
+    fs::remove_dir_all(&root).ok();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_recursive_symlinks_and_walk_errors() {
+    let root = create_test_tree();
+    std::os::unix::fs::symlink(root.join("top.bin"), root.join("link.bin")).unwrap();
+    std::os::unix::fs::symlink(root.join("missing.bin"), root.join("broken.bin")).unwrap();
+
+    // 기본값은 링크를 따라가지 않음
+    let stdout = run_count(&root, &["--include", "*.bin", "--max-depth", "1"]);
+    assert_eq!(searched_files(&stdout), vec!["top.bin"]);
+
+    // --follow: 링크를 따라가고, 깨진 링크는 경고만 출력하고 계속 진행
+    let output = Command::new(get_binary_path())
+        .arg(&root)
+        .args(["-r", "-e", "MARK", "-c", "--follow", "--include", "*.bin"])
+        .args(["--max-depth", "1"])
+        .output()
+        .expect("Failed to execute recursive search");
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(searched_files(&stdout), vec!["link.bin", "top.bin"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Warning:") && stderr.contains("broken.bin"), "{}", stderr);
+
+    // 정리
+    fs::remove_dir_all(&root).ok();
+}
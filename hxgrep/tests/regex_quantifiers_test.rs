@@ -70,7 +70,7 @@ fn test_range_quantifier() {
     // 범위 수량자가 작동하면 매치가 있어야 함
     let lines: Vec<&str> = stdout.lines().collect();
 
-    if lines.len() > 0 {
+    if !lines.is_empty() {
         println!("Range quantifier works! Found {} matches", lines.len());
         assert!(stdout.contains("00 00"));
     } else {
@@ -259,7 +259,7 @@ fn test_hex_digit_class_with_quantifiers() {
     println!("Hex digit class output: {}", stdout);
 
     // "34"가 들어간 패턴이 있어야 함 (123에서 또는 4567에서)
-    if stdout.len() > 0 {
+    if !stdout.is_empty() {
         println!("Found expected digit pattern");
     } else {
         println!("Character class patterns may not work as expected");
@@ -305,7 +305,7 @@ fn test_non_greedy_quantifiers() {
     println!("Non-greedy quantifier output: {}", stdout_non_greedy);
 
     // 둘 다 매치가 있어야 하지만 다를 수 있음
-    if stdout_greedy.len() > 0 || stdout_non_greedy.len() > 0 {
+    if !stdout_greedy.is_empty() || !stdout_non_greedy.is_empty() {
         println!("At least one quantifier type works");
         assert!(stdout_greedy.contains("00") || stdout_non_greedy.contains("00"));
     } else {
@@ -86,7 +86,7 @@ fn test_complex_quantifier_patterns() {
 
         // 일부 패턴은 예상과 다르게 작동할 수 있으므로 최소한의 매치는 있어야 함
         assert!(
-            matches.len() > 0,
+            !matches.is_empty(),
             "Pattern '{}' should find at least one match",
             pattern
         );
@@ -0,0 +1,136 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("hxgrep");
+    path
+}
+
+fn create_test_file(content: &[u8], extension: &str) -> PathBuf {
+    let temp_dir = std::env::temp_dir();
+    let file_path = temp_dir.join(format!("replace_test_{}.{}", uuid::Uuid::new_v4(), extension));
+    let mut file = File::create(&file_path).unwrap();
+    file.write_all(content).unwrap();
+    file_path
+}
+
+fn backup_of(path: &Path) -> PathBuf {
+    let mut backup = path.as_os_str().to_os_string();
+    backup.push(".bak");
+    PathBuf::from(backup)
+}
+
+#[test]
+fn test_replace_dry_run_leaves_file_untouched() {
+    let binary_path = get_binary_path();
+    let test_data = b"xxDEBUG=\x00yyDEBUG=\x00".to_vec();
+    let test_file = create_test_file(&test_data, "bin");
+
+    let output = Command::new(&binary_path)
+        .arg(&test_file)
+        .arg("-e")
+        .arg("DEBUG=(\\x00)")
+        .arg("--replace")
+        .arg("DEBUG=\\x01")
+        .arg("--dry-run")
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    // 두 매칭 모두 -/+ 형태로 미리보기가 출력되어야 함
+    assert!(stdout.contains("2h : - 44 45 42 55 47 3D 00"), "stdout: {}", stdout);
+    assert!(stdout.contains("2h : + 44 45 42 55 47 3D 01"), "stdout: {}", stdout);
+    assert!(stdout.contains("Bh : + 44 45 42 55 47 3D 01"), "stdout: {}", stdout);
+
+    // 파일은 변경되지 않고 백업도 생성되지 않아야 함
+    assert_eq!(fs::read(&test_file).unwrap(), test_data);
+    assert!(!backup_of(&test_file).exists());
+
+    // 정리
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_replace_with_backup_and_limit() {
+    let binary_path = get_binary_path();
+    let test_data = b"ID1=\xFFID2=\xFFID3=\xFF".to_vec();
+    let test_file = create_test_file(&test_data, "bin");
+
+    let output = Command::new(&binary_path)
+        .arg(&test_file)
+        .arg("-e")
+        .arg("(?-u)(?P<tag>ID[0-9])=\\xFF")
+        .arg("--replace")
+        .arg("${tag}=\\x00")
+        .arg("--same-length")
+        .arg("--max-replacements")
+        .arg("2")
+        .arg("--backup")
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+
+    // 처음 두 개만 치환되고 원본은 .bak으로 보존되어야 함
+    assert_eq!(fs::read(&test_file).unwrap(), b"ID1=\x00ID2=\x00ID3=\xFF");
+    assert_eq!(fs::read(backup_of(&test_file)).unwrap(), test_data);
+
+    // 정리
+    fs::remove_file(backup_of(&test_file)).ok();
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_replace_same_length_violation_aborts() {
+    let binary_path = get_binary_path();
+    let test_data = b"AAAA".to_vec();
+    let test_file = create_test_file(&test_data, "bin");
+
+    let output = Command::new(&binary_path)
+        .arg(&test_file)
+        .arg("-e")
+        .arg("A+")
+        .arg("--replace")
+        .arg("B")
+        .arg("--same-length")
+        .output()
+        .expect("Failed to execute command");
+
+    // 길이가 다르면 실패하고 파일은 그대로여야 함
+    assert!(!output.status.success());
+    assert_eq!(fs::read(&test_file).unwrap(), test_data);
+
+    // 정리
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_replace_refuses_forensic_image() {
+    let binary_path = get_binary_path();
+    let test_data = b"EVF\x09\x0d\x0a\xff\x00AAAA".to_vec();
+    let test_file = create_test_file(&test_data, "E01");
+
+    let output = Command::new(&binary_path)
+        .arg(&test_file)
+        .arg("-e")
+        .arg("AAAA")
+        .arg("--replace")
+        .arg("BBBB")
+        .output()
+        .expect("Failed to execute command");
+
+    // 포렌식 이미지는 절대 수정하지 않아야 함
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("forensic image"));
+    assert_eq!(fs::read(&test_file).unwrap(), test_data);
+
+    // 정리
+    fs::remove_file(test_file).ok();
+}
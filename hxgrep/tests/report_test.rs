@@ -0,0 +1,108 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("hxgrep");
+    path
+}
+
+fn create_test_file(content: &[u8], extension: &str) -> PathBuf {
+    let temp_dir = std::env::temp_dir();
+    let file_path = temp_dir.join(format!("report_test_{}.{}", uuid::Uuid::new_v4(), extension));
+    let mut file = File::create(&file_path).unwrap();
+    file.write_all(content).unwrap();
+    file_path
+}
+
+#[test]
+fn test_report_writes_self_contained_html() {
+    let binary_path = get_binary_path();
+    let mut data = vec![0u8; 300_000];
+    for pos in [100, 70_000, 200_000] {
+        data[pos..pos + 5].copy_from_slice(b"FILE0");
+    }
+    data[150_000..150_003].copy_from_slice(&[0xFF, 0xD8, 0xFF]);
+    let test_file = create_test_file(&data, "bin");
+    let report = test_file.with_extension("html");
+
+    let output = Command::new(&binary_path)
+        .arg("report")
+        .arg(&test_file)
+        .args(["-e", "FILE0", "-e", "(?-u)\\xFF\\xD8\\xFF", "-e", "NOTHERE"])
+        .args(["--case", "2024-017", "--examiner", "<Lee>", "--hash", "md5"])
+        .args(["--chunk-size", "65536", "-o"])
+        .arg(&report)
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Wrote 4 hit(s) of 3 pattern(s) in 1 input(s)"));
+
+    let html = fs::read_to_string(&report).unwrap();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<tr><th>Case number</th><td>2024-017</td></tr>"));
+    assert!(html.contains("<tr><th>Examiner</th><td>&lt;Lee&gt;</td></tr>"));
+    assert!(html.contains(&format!("MD5 <code>{}</code>", md5_hex(&data))));
+    assert!(html.contains("<h2 id=\"pattern-1\">Pattern 1: <code>FILE0</code></h2>"));
+    assert!(html.contains("<h2 id=\"pattern-2\">Pattern 2: <code>(?-u)\\xFF\\xD8\\xFF</code></h2>"));
+    assert!(html.contains("<p class=\"note\">No hits.</p>"));
+    // 매칭 위치 (청크 경계 이후 포함)
+    assert!(html.contains("<code>0x11170</code><br><span class=\"dec\">70000</span>"));
+    assert!(html.contains("<code>0x249F0</code><br><span class=\"dec\">150000</span>"));
+    // 문맥 덤프에서 매칭 강조
+    assert!(html.contains("00000060  00 00 00 00 <mark>46 49 4C 45  30</mark> 00"));
+    assert!(html.contains("....<mark>FILE0</mark>......."));
+    // 외부 자원 없음
+    assert!(!html.contains("<script") && !html.contains("http") && !html.contains("<link"));
+
+    // 정리
+    fs::remove_file(&test_file).ok();
+    fs::remove_file(&report).ok();
+}
+
+#[test]
+fn test_report_multiple_inputs_and_limit() {
+    let binary_path = get_binary_path();
+    let first = create_test_file(b"MZ..MZ..MZ..", "bin");
+    let second = create_test_file(b"..........MZ", "bin");
+
+    // -o 없이 stdout으로 출력
+    let output = Command::new(&binary_path)
+        .arg("report")
+        .arg(&first)
+        .arg(&second)
+        .args(["-e", "MZ", "-n", "2"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let html = String::from_utf8_lossy(&output.stdout);
+    assert!(html.contains("<th>Input</th><th>Offset</th>"));
+    assert_eq!(html.matches("<details><summary>hex/ASCII</summary>").count(), 3);
+    assert!(html.contains("3 <span class=\"note\">(first 2 per input)</span>"));
+    assert!(html.contains(&format!("<td><code>{}</code></td><td><code>0xA</code>", second.display())));
+    // 기본 해시 알고리즘은 SHA-256
+    assert!(html.contains("SHA256 <code>"));
+
+    // 잘못된 정규표현식은 오류
+    let output = Command::new(&binary_path)
+        .arg("report")
+        .arg(&first)
+        .args(["-e", "(unclosed"])
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(output.status.code(), Some(2));
+
+    // 정리
+    fs::remove_file(&first).ok();
+    fs::remove_file(&second).ok();
+}
+
+fn md5_hex(data: &[u8]) -> String {
+    use md5::Digest;
+    hex::encode(md5::Md5::digest(data))
+}
@@ -0,0 +1,190 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("hxgrep");
+    path
+}
+
+fn create_test_file(content: &[u8], extension: &str) -> PathBuf {
+    let temp_dir = std::env::temp_dir();
+    let file_path = temp_dir.join(format!("reverse_test_{}.{}", uuid::Uuid::new_v4(), extension));
+    let mut file = File::create(&file_path).unwrap();
+    file.write_all(content).unwrap();
+    file_path
+}
+
+#[test]
+fn test_reverse_round_trip_of_hxgrep_dump() {
+    let binary_path = get_binary_path();
+    let test_data: Vec<u8> = (0..1000u32).map(|i| (i * 7 % 256) as u8).collect();
+    let test_file = create_test_file(&test_data, "bin");
+
+    for separator in [" ", "-", ""] {
+        let dump = Command::new(&binary_path)
+            .arg(&test_file)
+            .arg("-t")
+            .arg(separator)
+            .output()
+            .expect("Failed to execute command");
+        assert!(dump.status.success());
+
+        let dump_file = create_test_file(&dump.stdout, "txt");
+        let restored = test_file.with_extension(format!("restored{}", separator.len()));
+
+        let output = Command::new(&binary_path)
+            .arg("reverse")
+            .arg(&dump_file)
+            .arg("-o")
+            .arg(&restored)
+            .output()
+            .expect("Failed to execute command");
+
+        // 구분자와 관계없이 원본과 동일하게 복원되어야 함
+        assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+        assert_eq!(fs::read(&restored).unwrap(), test_data, "separator: {:?}", separator);
+
+        // 정리
+        fs::remove_file(dump_file).ok();
+        fs::remove_file(restored).ok();
+    }
+
+    // 정리
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_reverse_patch_and_sparse_xxd() {
+    let binary_path = get_binary_path();
+    let target = create_test_file(b"0123456789", "bin");
+    let dump = create_test_file(b"00000004: 4142  AB\n", "txt");
+
+    let output = Command::new(&binary_path)
+        .arg("reverse")
+        .arg(&dump)
+        .arg("-o")
+        .arg(&target)
+        .arg("--patch")
+        .output()
+        .expect("Failed to execute command");
+
+    // 기존 파일을 자르지 않고 해당 오프셋만 덮어써야 함
+    assert!(output.status.success());
+    assert_eq!(fs::read(&target).unwrap(), b"0123AB6789");
+
+    let output = Command::new(&binary_path)
+        .arg("reverse")
+        .arg(&dump)
+        .output()
+        .expect("Failed to execute command");
+
+    // stdout 출력 시 앞부분은 0으로 채워져야 함
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"\0\0\0\0AB");
+
+    // 정리
+    fs::remove_file(target).ok();
+    fs::remove_file(dump).ok();
+}
+
+#[test]
+fn test_reverse_reports_line_and_column() {
+    let binary_path = get_binary_path();
+    let dump = create_test_file(b"0h : 41 42\n2h : 43 4Q\n", "txt");
+
+    let output = Command::new(&binary_path)
+        .arg("reverse")
+        .arg(&dump)
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("line 2, column 10"), "stderr: {}", stderr);
+
+    // 정리
+    fs::remove_file(dump).ok();
+}
+
+#[test]
+fn test_reverse_round_trip_of_output_styles() {
+    let binary_path = get_binary_path();
+    let test_data: Vec<u8> = (0..1003u32).map(|i| (i * 13 % 251) as u8).collect();
+    let test_file = create_test_file(&test_data, "bin");
+
+    // (덤프 옵션, 복원 옵션)
+    let styles: [(&[&str], &[&str]); 11] = [
+        (&["--offset-radix", "dec"], &["--offset-radix", "dec"]),
+        (&["--offset-radix", "oct"], &[]),
+        (&["--offset-radix", "oct", "--offset-style", "prefix"], &[]),
+        (&["--offset-style", "prefix", "--lowercase"], &[]),
+        (&["--offset-style", "none"], &["--offset-radix", "hex"]),
+        (&["-g", "4", "--endian", "little"], &["--endian", "little"]),
+        (&["-g", "8", "--endian", "little", "-t", "-"], &["--endian", "little"]),
+        (&["-g", "2"], &["--endian", "big"]),
+        // --endian 없이 묶인 덤프는 기본값 big으로 복원
+        (&["-g", "4"], &[]),
+        (&["-g", "8", "--hideoffset"], &[]),
+        (&["-g", "4", "--endian", "little", "--hideoffset"], &["--endian", "little"]),
+    ];
+    for (dump_args, reverse_args) in styles {
+        let dump = Command::new(&binary_path)
+            .arg(&test_file)
+            .args(dump_args)
+            .output()
+            .expect("Failed to execute command");
+        assert!(dump.status.success());
+        let dump_file = create_test_file(&dump.stdout, "txt");
+
+        let output = Command::new(&binary_path)
+            .arg("reverse")
+            .arg(&dump_file)
+            .args(reverse_args)
+            .output()
+            .expect("Failed to execute command");
+        assert!(
+            output.status.success(),
+            "{:?}: {}",
+            dump_args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert_eq!(output.stdout, test_data, "{:?}", dump_args);
+
+        // 정리
+        fs::remove_file(dump_file).ok();
+    }
+
+    // 진법을 알 수 없는 덤프는 추측하지 않고 오류
+    for (dump_args, message) in [
+        (&["--offset-radix", "dec"][..], "--offset-radix"),
+        (&["--offset-style", "none"][..], "--offset-radix"),
+    ] {
+        let dump = Command::new(&binary_path)
+            .arg(&test_file)
+            .args(dump_args)
+            .output()
+            .expect("Failed to execute command");
+        let dump_file = create_test_file(&dump.stdout, "txt");
+
+        let output = Command::new(&binary_path)
+            .arg("reverse")
+            .arg(&dump_file)
+            .output()
+            .expect("Failed to execute command");
+        assert_eq!(output.status.code(), Some(2), "{:?}", dump_args);
+        assert!(output.stdout.is_empty());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("line 1, column") && stderr.contains(message), "{}", stderr);
+
+        // 정리
+        fs::remove_file(dump_file).ok();
+    }
+
+    // 정리
+    fs::remove_file(test_file).ok();
+}
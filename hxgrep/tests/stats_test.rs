@@ -0,0 +1,88 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("hxgrep");
+    path
+}
+
+fn create_test_file(content: &[u8]) -> PathBuf {
+    let temp_dir = std::env::temp_dir();
+    let file_path = temp_dir.join(format!("stats_test_{}.bin", uuid::Uuid::new_v4()));
+    let mut file = File::create(&file_path).unwrap();
+    file.write_all(content).unwrap();
+    file_path
+}
+
+#[test]
+fn test_stats_json_with_ranges() {
+    let binary_path = get_binary_path();
+    let mut test_data = vec![0x00u8; 1000];
+    test_data.extend(vec![0xFFu8; 500]);
+    test_data.extend(b"printable text ".repeat(20));
+
+    let test_file = create_test_file(&test_data);
+
+    let output = Command::new(&binary_path)
+        .arg("stats")
+        .arg(&test_file)
+        .arg("--range-size")
+        .arg("1024")
+        .arg("--chunk-size")
+        .arg("100")
+        .arg("-p")
+        .arg("-f")
+        .arg("json")
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let reports: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let reports = reports.as_array().unwrap();
+
+    // 구간 2개 + 전체 1개
+    assert_eq!(reports.len(), 3);
+    assert_eq!(reports[0]["scope"], "range");
+    assert_eq!(reports[1]["offset"], 1024);
+
+    let total = &reports[2];
+    assert_eq!(total["scope"], "total");
+    assert_eq!(total["length"], 1800);
+    assert_eq!(total["most_common_byte"], "0x00");
+    // 청크 경계를 넘는 연속 구간도 하나로 계산되어야 함
+    assert_eq!(total["longest_run_length"], 1000);
+    assert_eq!(total["longest_run_offset"], 0);
+
+    // 정리
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_stats_histogram_csv() {
+    let binary_path = get_binary_path();
+    let test_file = create_test_file(b"AAAB");
+
+    let output = Command::new(&binary_path)
+        .arg("stats")
+        .arg(&test_file)
+        .arg("--histogram")
+        .arg("-f")
+        .arg("csv")
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    // 헤더 + 256개 bin
+    assert_eq!(stdout.lines().count(), 257);
+    assert!(stdout.contains(",total,0,0x41,3,0.75,3,0"), "stdout: {}", stdout);
+
+    // 정리
+    fs::remove_file(test_file).ok();
+}
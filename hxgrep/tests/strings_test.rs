@@ -0,0 +1,87 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn get_binary_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("hxgrep");
+    path
+}
+
+fn create_test_file(content: &[u8]) -> PathBuf {
+    let temp_dir = std::env::temp_dir();
+    let file_path = temp_dir.join(format!("strings_test_{}.bin", uuid::Uuid::new_v4()));
+    let mut file = File::create(&file_path).unwrap();
+    file.write_all(content).unwrap();
+    file_path
+}
+
+#[test]
+fn test_strings_ascii_and_utf16le() {
+    let binary_path = get_binary_path();
+    let mut test_data = vec![0xFFu8; 16];
+    test_data.extend(b"config.ini\x00\x00");
+    test_data.extend("C:\\Windows".encode_utf16().flat_map(|u| u.to_le_bytes()));
+    test_data.extend([0xFF; 4]);
+
+    let test_file = create_test_file(&test_data);
+
+    let output = Command::new(&binary_path)
+        .arg("strings")
+        .arg(&test_file)
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+
+    // 오프셋과 인코딩이 함께 출력되어야 함
+    assert_eq!(lines, vec!["10h : [ascii] config.ini", "1Ch : [utf-16le] C:\\Windows"]);
+
+    // 정리
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_strings_parallel_matches_sequential() {
+    let binary_path = get_binary_path();
+    let mut test_data = Vec::new();
+    for i in 0..500 {
+        test_data.extend(format!("record-{:05}", i).as_bytes());
+        test_data.extend([0x00, 0x01, 0x02]);
+    }
+
+    let test_file = create_test_file(&test_data);
+
+    let run = |parallel: bool| {
+        let mut command = Command::new(&binary_path);
+        command
+            .arg("strings")
+            .arg(&test_file)
+            .arg("--filter")
+            .arg("record-0000[0-4]")
+            .arg("--chunk-size")
+            .arg("101")
+            .arg("-f")
+            .arg("csv");
+        if parallel {
+            command.arg("-p");
+        }
+        let output = command.output().expect("Failed to execute command");
+        String::from_utf8_lossy(&output.stdout).to_string()
+    };
+
+    let sequential = run(false);
+    let parallel = run(true);
+
+    // 헤더 + 필터에 맞는 5개 문자열, 청크 경계에서 잘리거나 중복되지 않아야 함
+    assert_eq!(sequential.lines().count(), 6, "stdout: {}", sequential);
+    assert_eq!(sequential, parallel);
+
+    // 정리
+    fs::remove_file(test_file).ok();
+}
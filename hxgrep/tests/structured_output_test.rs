@@ -54,7 +54,7 @@ fn test_output_format_parsing() {
         OutputFormat::from_str("HEX"),
         Some(OutputFormat::Hex)
     )); // Case insensitive
-    assert!(matches!(OutputFormat::from_str("invalid"), None));
+    assert!(OutputFormat::from_str("invalid").is_none());
 }
 
 #[test]
@@ -51,8 +51,8 @@ impl TestDataGenerator {
             let mut chunk = vec![0u8; chunk_size];
 
             // 각 MB마다 다른 패턴 채우기
-            for i in 0..chunk_size {
-                chunk[i] = ((i + mb * chunk_size) % 256) as u8;
+            for (i, byte) in chunk.iter_mut().enumerate() {
+                *byte = ((i + mb * chunk_size) % 256) as u8;
             }
 
             // 각 MB의 중간에 패턴 삽입